@@ -0,0 +1,452 @@
+//! Analysis module for flagging anomalies in flight telemetry.
+//!
+//! Runs once per parsed log, right after telemetry extraction, and produces
+//! findings stored alongside the flight. Each rule coalesces consecutive
+//! matching samples into a single finding so a long excursion is reported
+//! once, with its duration and worst value.
+
+use crate::models::{Finding, TelemetryPoint};
+use crate::parser::haversine_distance;
+
+pub const GPS_JUMP: &str = "gpsJump";
+pub const ALTITUDE_LOSS: &str = "altitudeLoss";
+pub const VOLTAGE_SAG: &str = "voltageSag";
+pub const ATTITUDE_EXCURSION: &str = "attitudeExcursion";
+pub const RC_SIGNAL_LOSS: &str = "rcSignalLoss";
+pub const SATELLITE_DROP: &str = "satelliteDrop";
+pub const CELL_IMBALANCE: &str = "cellImbalance";
+
+/// Ground speed between fixes (m/s) no consumer drone can reach
+const GPS_JUMP_SPEED_MS: f64 = 40.0;
+/// Minimum position change (m) considered a jump, filters out GPS noise
+const GPS_JUMP_MIN_DISTANCE_M: f64 = 20.0;
+
+/// Height lost (m) within the look-back window that counts as sudden
+const ALTITUDE_LOSS_M: f64 = 12.0;
+const ALTITUDE_LOSS_CRITICAL_M: f64 = 20.0;
+const ALTITUDE_LOSS_WINDOW_MS: i64 = 2_000;
+
+/// Relative pack voltage drop within the look-back window while current rises
+const VOLTAGE_SAG_RATIO: f64 = 0.07;
+const VOLTAGE_SAG_WINDOW_MS: i64 = 2_000;
+
+/// Pitch or roll (degrees) beyond normal flight envelopes
+const ATTITUDE_LIMIT_DEG: f64 = 35.0;
+const ATTITUDE_CRITICAL_DEG: f64 = 50.0;
+
+/// RC signal loss lasting at least this long is critical
+const RC_LOSS_CRITICAL_MS: i64 = 3_000;
+
+/// Satellite count below which positioning degrades
+const MIN_SATELLITES: i32 = 6;
+
+/// Spread between cell voltages (V) that points at a weak cell; shorter
+/// episodes are load spikes rather than a failing cell
+const CELL_IMBALANCE_V: f64 = 0.1;
+const CELL_IMBALANCE_CRITICAL_V: f64 = 0.2;
+const CELL_IMBALANCE_MIN_MS: i64 = 2_000;
+
+/// Scan a flight's telemetry for anomalies, ordered by time
+pub fn analyze(points: &[TelemetryPoint]) -> Vec<Finding> {
+    let mut findings = Vec::new();
+
+    findings.extend(detect_gps_jumps(points));
+    findings.extend(detect_altitude_loss(points));
+    findings.extend(detect_voltage_sag(points));
+    findings.extend(detect_attitude_excursions(points));
+    findings.extend(detect_rc_signal_loss(points));
+    findings.extend(detect_satellite_drops(points));
+    findings.extend(detect_cell_imbalance(points));
+
+    findings.sort_by_key(|f| f.timestamp_ms);
+    findings
+}
+
+fn detect_gps_jumps(points: &[TelemetryPoint]) -> Vec<Finding> {
+    let fixes: Vec<(i64, f64, f64)> = points
+        .iter()
+        .filter_map(|p| match (p.latitude, p.longitude) {
+            (Some(lat), Some(lon)) if lat != 0.0 || lon != 0.0 => Some((p.timestamp_ms, lat, lon)),
+            _ => None,
+        })
+        .collect();
+
+    let jumps = fixes.windows(2).filter_map(|pair| {
+        let (t1, lat1, lon1) = pair[0];
+        let (t2, lat2, lon2) = pair[1];
+        let dt = (t2 - t1) as f64 / 1000.0;
+        let distance = haversine_distance(lat1, lon1, lat2, lon2);
+
+        if distance < GPS_JUMP_MIN_DISTANCE_M {
+            return None;
+        }
+        // Identical timestamps with a position change are a jump as well
+        let speed = if dt > 0.0 {
+            distance / dt
+        } else {
+            f64::INFINITY
+        };
+        (speed > GPS_JUMP_SPEED_MS).then_some((t2, distance))
+    });
+
+    jumps
+        .map(|(timestamp_ms, distance)| Finding {
+            timestamp_ms,
+            duration_ms: 0,
+            kind: GPS_JUMP.to_string(),
+            severity: Finding::WARNING.to_string(),
+            message: format!("GPS position jumped {:.0} m between fixes", distance),
+            value: Some(distance),
+        })
+        .collect()
+}
+
+fn detect_altitude_loss(points: &[TelemetryPoint]) -> Vec<Finding> {
+    // Height lost since the highest point in the look-back window
+    let losses = window_drops(points, ALTITUDE_LOSS_WINDOW_MS, |p| p.height);
+
+    episodes(points, |i| {
+        losses[i].is_some_and(|loss| loss >= ALTITUDE_LOSS_M)
+    })
+    .into_iter()
+    .map(|(start, end)| {
+        let worst = losses[start..=end]
+            .iter()
+            .flatten()
+            .fold(0.0_f64, |a, &b| a.max(b));
+        Finding {
+            timestamp_ms: points[start].timestamp_ms,
+            duration_ms: points[end].timestamp_ms - points[start].timestamp_ms,
+            kind: ALTITUDE_LOSS.to_string(),
+            severity: if worst >= ALTITUDE_LOSS_CRITICAL_M {
+                Finding::CRITICAL
+            } else {
+                Finding::WARNING
+            }
+            .to_string(),
+            message: format!(
+                "Lost {:.1} m of height within {} s",
+                worst,
+                ALTITUDE_LOSS_WINDOW_MS / 1000
+            ),
+            value: Some(worst),
+        }
+    })
+    .collect()
+}
+
+fn detect_voltage_sag(points: &[TelemetryPoint]) -> Vec<Finding> {
+    let drops = window_drops(points, VOLTAGE_SAG_WINDOW_MS, |p| p.battery_voltage);
+    let current_rises = window_rises(points, VOLTAGE_SAG_WINDOW_MS, |p| {
+        p.battery_current.map(f64::abs)
+    });
+
+    let sagging = |i: usize| {
+        let (Some(drop), Some(voltage)) = (drops[i], points[i].battery_voltage) else {
+            return false;
+        };
+        let ratio = drop / (voltage + drop);
+        ratio >= VOLTAGE_SAG_RATIO && current_rises[i].is_some_and(|rise| rise > 0.0)
+    };
+
+    episodes(points, sagging)
+        .into_iter()
+        .map(|(start, end)| {
+            let worst = drops[start..=end]
+                .iter()
+                .flatten()
+                .fold(0.0_f64, |a, &b| a.max(b));
+            Finding {
+                timestamp_ms: points[start].timestamp_ms,
+                duration_ms: points[end].timestamp_ms - points[start].timestamp_ms,
+                kind: VOLTAGE_SAG.to_string(),
+                severity: Finding::WARNING.to_string(),
+                message: format!("Battery voltage sagged {:.2} V under load", worst),
+                value: Some(worst),
+            }
+        })
+        .collect()
+}
+
+fn detect_attitude_excursions(points: &[TelemetryPoint]) -> Vec<Finding> {
+    let tilt = |p: &TelemetryPoint| {
+        p.pitch
+            .map(f64::abs)
+            .into_iter()
+            .chain(p.roll.map(f64::abs))
+            .fold(0.0_f64, f64::max)
+    };
+
+    episodes(points, |i| tilt(&points[i]) > ATTITUDE_LIMIT_DEG)
+        .into_iter()
+        .map(|(start, end)| {
+            let worst = points[start..=end].iter().map(tilt).fold(0.0_f64, f64::max);
+            Finding {
+                timestamp_ms: points[start].timestamp_ms,
+                duration_ms: points[end].timestamp_ms - points[start].timestamp_ms,
+                kind: ATTITUDE_EXCURSION.to_string(),
+                severity: if worst >= ATTITUDE_CRITICAL_DEG {
+                    Finding::CRITICAL
+                } else {
+                    Finding::WARNING
+                }
+                .to_string(),
+                message: format!("Aircraft tilted {:.0}° (pitch/roll)", worst),
+                value: Some(worst),
+            }
+        })
+        .collect()
+}
+
+fn detect_rc_signal_loss(points: &[TelemetryPoint]) -> Vec<Finding> {
+    episodes(points, |i| points[i].rc_signal == Some(0))
+        .into_iter()
+        .map(|(start, end)| {
+            // Loss lasts until the next sample with signal
+            let recovered_ms = points
+                .get(end + 1)
+                .map(|p| p.timestamp_ms)
+                .unwrap_or(points[end].timestamp_ms);
+            let duration_ms = recovered_ms - points[start].timestamp_ms;
+            Finding {
+                timestamp_ms: points[start].timestamp_ms,
+                duration_ms,
+                kind: RC_SIGNAL_LOSS.to_string(),
+                severity: if duration_ms >= RC_LOSS_CRITICAL_MS {
+                    Finding::CRITICAL
+                } else {
+                    Finding::WARNING
+                }
+                .to_string(),
+                message: format!("RC signal lost for {:.1} s", duration_ms as f64 / 1000.0),
+                value: Some(duration_ms as f64 / 1000.0),
+            }
+        })
+        .collect()
+}
+
+fn detect_satellite_drops(points: &[TelemetryPoint]) -> Vec<Finding> {
+    // Only count drops after the aircraft had a usable fix
+    let Some(first_fix) = points
+        .iter()
+        .position(|p| p.satellites.is_some_and(|s| s >= MIN_SATELLITES))
+    else {
+        return Vec::new();
+    };
+
+    episodes(&points[first_fix..], |i| {
+        points[first_fix + i]
+            .satellites
+            .is_some_and(|s| s < MIN_SATELLITES)
+    })
+    .into_iter()
+    .map(|(start, end)| {
+        let (start, end) = (first_fix + start, first_fix + end);
+        let lowest = points[start..=end]
+            .iter()
+            .filter_map(|p| p.satellites)
+            .min()
+            .unwrap_or(0);
+        Finding {
+            timestamp_ms: points[start].timestamp_ms,
+            duration_ms: points[end].timestamp_ms - points[start].timestamp_ms,
+            kind: SATELLITE_DROP.to_string(),
+            severity: Finding::WARNING.to_string(),
+            message: format!("Satellite count dropped to {}", lowest),
+            value: Some(lowest as f64),
+        }
+    })
+    .collect()
+}
+
+fn detect_cell_imbalance(points: &[TelemetryPoint]) -> Vec<Finding> {
+    episodes(points, |i| {
+        points[i]
+            .cell_imbalance()
+            .is_some_and(|spread| spread >= CELL_IMBALANCE_V)
+    })
+    .into_iter()
+    .filter(|&(start, end)| {
+        points[end].timestamp_ms - points[start].timestamp_ms >= CELL_IMBALANCE_MIN_MS
+    })
+    .map(|(start, end)| {
+        let worst = points[start..=end]
+            .iter()
+            .filter_map(TelemetryPoint::cell_imbalance)
+            .fold(0.0_f64, f64::max);
+        Finding {
+            timestamp_ms: points[start].timestamp_ms,
+            duration_ms: points[end].timestamp_ms - points[start].timestamp_ms,
+            kind: CELL_IMBALANCE.to_string(),
+            severity: if worst >= CELL_IMBALANCE_CRITICAL_V {
+                Finding::CRITICAL
+            } else {
+                Finding::WARNING
+            }
+            .to_string(),
+            message: format!("Battery cells {:.2} V apart", worst),
+            value: Some(worst),
+        }
+    })
+    .collect()
+}
+
+/// Runs of consecutive sample indexes matching `predicate`, as (first, last)
+fn episodes(points: &[TelemetryPoint], predicate: impl Fn(usize) -> bool) -> Vec<(usize, usize)> {
+    let mut runs = Vec::new();
+    let mut start = None;
+
+    for i in 0..points.len() {
+        match (predicate(i), start) {
+            (true, None) => start = Some(i),
+            (false, Some(s)) => {
+                runs.push((s, i - 1));
+                start = None;
+            }
+            _ => {}
+        }
+    }
+    if let Some(s) = start {
+        runs.push((s, points.len() - 1));
+    }
+
+    runs
+}
+
+/// For each sample, how far `value` is below its maximum over the preceding window
+fn window_drops(
+    points: &[TelemetryPoint],
+    window_ms: i64,
+    value: impl Fn(&TelemetryPoint) -> Option<f64>,
+) -> Vec<Option<f64>> {
+    window_extremes(points, window_ms, &value, f64::max)
+        .into_iter()
+        .zip(points)
+        .map(|(peak, p)| Some(peak? - value(p)?))
+        .collect()
+}
+
+/// For each sample, how far `value` is above its minimum over the preceding window
+fn window_rises(
+    points: &[TelemetryPoint],
+    window_ms: i64,
+    value: impl Fn(&TelemetryPoint) -> Option<f64>,
+) -> Vec<Option<f64>> {
+    window_extremes(points, window_ms, &value, f64::min)
+        .into_iter()
+        .zip(points)
+        .map(|(low, p)| Some(value(p)? - low?))
+        .collect()
+}
+
+/// Extreme of `value` over samples within `window_ms` before each sample (inclusive)
+fn window_extremes(
+    points: &[TelemetryPoint],
+    window_ms: i64,
+    value: &impl Fn(&TelemetryPoint) -> Option<f64>,
+    pick: fn(f64, f64) -> f64,
+) -> Vec<Option<f64>> {
+    let mut start = 0;
+
+    points
+        .iter()
+        .enumerate()
+        .map(|(i, p)| {
+            // Timestamps can step back when fly_time resets; never look ahead
+            start = start.min(i);
+            while start < i && points[start].timestamp_ms < p.timestamp_ms - window_ms {
+                start += 1;
+            }
+            points[start..=i].iter().filter_map(value).reduce(pick)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn series(count: i64, f: impl Fn(i64, &mut TelemetryPoint)) -> Vec<TelemetryPoint> {
+        (0..count)
+            .map(|i| {
+                let mut point = TelemetryPoint {
+                    timestamp_ms: i * 100,
+                    latitude: Some(47.0),
+                    longitude: Some(8.0),
+                    height: Some(50.0),
+                    satellites: Some(15),
+                    rc_signal: Some(100),
+                    ..Default::default()
+                };
+                f(i, &mut point);
+                point
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_clean_flight_has_no_findings() {
+        let points = series(100, |i, p| p.longitude = Some(8.0 + i as f64 * 0.00001));
+        assert!(analyze(&points).is_empty());
+    }
+
+    #[test]
+    fn test_gps_jump_detected() {
+        let points = series(20, |i, p| {
+            if i >= 10 {
+                p.latitude = Some(47.01);
+            }
+        });
+
+        let findings = analyze(&points);
+        assert_eq!(findings.len(), 1);
+        assert_eq!(findings[0].kind, GPS_JUMP);
+        assert_eq!(findings[0].timestamp_ms, 1000);
+    }
+
+    #[test]
+    fn test_rc_loss_coalesced_into_one_finding() {
+        let points = series(100, |i, p| {
+            if (20..60).contains(&i) {
+                p.rc_signal = Some(0);
+            }
+        });
+
+        let findings = analyze(&points);
+        assert_eq!(findings.len(), 1);
+        assert_eq!(findings[0].kind, RC_SIGNAL_LOSS);
+        assert_eq!(findings[0].duration_ms, 4000);
+        assert_eq!(findings[0].severity, Finding::CRITICAL);
+    }
+
+    #[test]
+    fn test_sudden_altitude_loss_detected() {
+        let points = series(50, |i, p| {
+            if i >= 30 {
+                p.height = Some(50.0 - (i - 29) as f64 * 1.5);
+            }
+        });
+
+        let findings = analyze(&points);
+        assert!(findings.iter().any(|f| f.kind == ALTITUDE_LOSS));
+    }
+
+    #[test]
+    fn test_lasting_cell_imbalance_detected() {
+        // A one-sample spike at 2 s, then one cell drifting low from 5 s on
+        let points = series(100, |i, p| {
+            let weak = match i {
+                20 => 3.7,
+                50.. => 3.795 - (i - 50) as f64 * 0.01,
+                _ => 3.85,
+            };
+            p.battery_cell_voltages = vec![3.85, 3.85, weak, 3.85];
+        });
+
+        let findings = analyze(&points);
+        assert_eq!(findings.len(), 1);
+        assert_eq!(findings[0].kind, CELL_IMBALANCE);
+        assert_eq!(findings[0].timestamp_ms, 5500);
+        assert_eq!(findings[0].severity, Finding::CRITICAL);
+    }
+}
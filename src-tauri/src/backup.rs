@@ -0,0 +1,492 @@
+//! Full logbook backup and restore.
+//!
+//! A backup is a single zip archive holding:
+//! - `flights.db`: the DuckDB library, checkpointed so no WAL is needed
+//! - `raw_logs/`: the archived original logs
+//! - `config.json`: app configuration (API key), when present
+//! - `manifest.json`: format and app version, flight count and the size and
+//!   SHA256 of every file above
+//!
+//! Restoring extracts and verifies everything against the manifest before
+//! the library is touched, then either replaces the library (the previous
+//! database is kept as a `.bak` file) or merges new flights into it by file
+//! hash. Raw logs are added next to the existing ones in both modes.
+
+use std::fs::{self, File};
+use std::io::{BufReader, BufWriter, Read, Seek, Write};
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use thiserror::Error;
+use zip::write::SimpleFileOptions;
+use zip::{CompressionMethod, ZipArchive, ZipWriter};
+
+use crate::database::{Database, DatabaseError};
+use crate::parser::LogParser;
+
+/// Version of the archive layout written by this build
+pub const BACKUP_FORMAT_VERSION: u32 = 1;
+
+const MANIFEST_NAME: &str = "manifest.json";
+const DATABASE_NAME: &str = "flights.db";
+const CONFIG_NAME: &str = "config.json";
+const RAW_LOGS_PREFIX: &str = "raw_logs/";
+
+#[derive(Error, Debug)]
+pub enum BackupError {
+    #[error("IO error: {0}")]
+    Io(#[from] std::io::Error),
+
+    #[error("Database error: {0}")]
+    Database(#[from] DatabaseError),
+
+    #[error("Archive error: {0}")]
+    Zip(#[from] zip::result::ZipError),
+
+    #[error("Invalid manifest: {0}")]
+    Manifest(#[from] serde_json::Error),
+
+    #[error("Not a logbook backup: {0}")]
+    InvalidArchive(String),
+
+    #[error("Backup format version {0} is newer than this app supports ({1})")]
+    UnsupportedVersion(u32, u32),
+
+    #[error("Checksum mismatch for {0}, the backup is corrupted")]
+    ChecksumMismatch(String),
+
+    #[error("Unsupported restore mode: {0} (expected replace or merge)")]
+    UnsupportedMode(String),
+}
+
+/// Contents description stored as `manifest.json` in every backup
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BackupManifest {
+    pub format_version: u32,
+    pub app_version: String,
+    pub created_at: DateTime<Utc>,
+    pub flight_count: usize,
+    pub files: Vec<BackupFile>,
+}
+
+/// A file in a backup archive
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BackupFile {
+    /// Path inside the archive (`flights.db`, `raw_logs/<name>`, ...)
+    pub path: String,
+    pub size: u64,
+    pub sha256: String,
+}
+
+/// What to do with the current library when restoring
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum RestoreMode {
+    /// Swap in the backed-up library
+    #[default]
+    Replace,
+    /// Add the backed-up flights missing from the current library
+    Merge,
+}
+
+impl FromStr for RestoreMode {
+    type Err = BackupError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "replace" => Ok(Self::Replace),
+            "merge" => Ok(Self::Merge),
+            other => Err(BackupError::UnsupportedMode(other.to_string())),
+        }
+    }
+}
+
+/// Result of restoring a backup
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RestoreReport {
+    pub mode: RestoreMode,
+    pub flights_restored: usize,
+    /// Flights already in the library (merge mode)
+    pub flights_skipped: usize,
+    pub raw_logs_restored: usize,
+    /// Where the replaced database was moved (replace mode)
+    pub previous_database: Option<String>,
+}
+
+/// Write a backup of the whole library to `destination`
+pub fn create_backup(db: &Database, destination: &Path) -> Result<BackupManifest, BackupError> {
+    let snapshot = db.data_dir.join("flights.db.snapshot");
+    db.checkpoint_copy(&snapshot)?;
+
+    let result = write_archive(db, &snapshot, destination);
+
+    if let Err(e) = fs::remove_file(&snapshot) {
+        log::warn!("Failed to remove database snapshot {:?}: {}", snapshot, e);
+    }
+    if result.is_err() {
+        let _ = fs::remove_file(destination);
+    }
+    result
+}
+
+fn write_archive(
+    db: &Database,
+    snapshot: &Path,
+    destination: &Path,
+) -> Result<BackupManifest, BackupError> {
+    let mut zip = ZipWriter::new(BufWriter::new(File::create(destination)?));
+    let options = SimpleFileOptions::default()
+        .compression_method(CompressionMethod::Deflated)
+        .large_file(true);
+
+    let mut files = vec![add_file(&mut zip, options, DATABASE_NAME, snapshot)?];
+
+    let config_path = db.data_dir.join(CONFIG_NAME);
+    if config_path.is_file() {
+        files.push(add_file(&mut zip, options, CONFIG_NAME, &config_path)?);
+    }
+
+    let mut raw_logs: Vec<PathBuf> = fs::read_dir(db.raw_logs_dir())?
+        .filter_map(|entry| entry.ok().map(|e| e.path()))
+        .filter(|path| path.is_file())
+        .collect();
+    raw_logs.sort();
+    for path in raw_logs {
+        let Some(name) = path.file_name().and_then(|n| n.to_str()) else {
+            continue;
+        };
+        let archive_name = format!("{}{}", RAW_LOGS_PREFIX, name);
+        files.push(add_file(&mut zip, options, &archive_name, &path)?);
+    }
+
+    let manifest = BackupManifest {
+        format_version: BACKUP_FORMAT_VERSION,
+        app_version: env!("CARGO_PKG_VERSION").to_string(),
+        created_at: Utc::now(),
+        flight_count: Database::count_flights_in(snapshot)?,
+        files,
+    };
+
+    zip.start_file(MANIFEST_NAME, options)?;
+    serde_json::to_writer_pretty(&mut zip, &manifest)?;
+    zip.finish()?.flush()?;
+
+    log::info!(
+        "Backed up {} flights ({} files) to {:?}",
+        manifest.flight_count,
+        manifest.files.len(),
+        destination
+    );
+    Ok(manifest)
+}
+
+/// Stream a file into the archive, hashing it on the way
+fn add_file<W: Write + Seek>(
+    zip: &mut ZipWriter<W>,
+    options: SimpleFileOptions,
+    name: &str,
+    path: &Path,
+) -> Result<BackupFile, BackupError> {
+    zip.start_file(name, options)?;
+    let (size, sha256) = copy_hashed(&mut BufReader::new(File::open(path)?), zip)?;
+
+    Ok(BackupFile {
+        path: name.to_string(),
+        size,
+        sha256,
+    })
+}
+
+/// Restore a backup into the library
+pub fn restore_backup(
+    db: &Database,
+    archive_path: &Path,
+    mode: RestoreMode,
+) -> Result<RestoreReport, BackupError> {
+    let mut archive = ZipArchive::new(BufReader::new(File::open(archive_path)?))?;
+    let manifest = manifest_of(&mut archive)?;
+
+    // Nothing in the library is touched until every file checks out
+    let staging = db.data_dir.join("restore_staging");
+    if staging.exists() {
+        fs::remove_dir_all(&staging)?;
+    }
+
+    let result = extract_verified(&mut archive, &manifest, &staging)
+        .and_then(|_| apply_restore(db, &manifest, &staging, mode));
+
+    if let Err(e) = fs::remove_dir_all(&staging) {
+        log::warn!(
+            "Failed to remove restore staging directory {:?}: {}",
+            staging,
+            e
+        );
+    }
+    result
+}
+
+fn manifest_of<R: Read + Seek>(archive: &mut ZipArchive<R>) -> Result<BackupManifest, BackupError> {
+    let manifest: BackupManifest = match archive.by_name(MANIFEST_NAME) {
+        Ok(file) => serde_json::from_reader(file)?,
+        Err(zip::result::ZipError::FileNotFound) => {
+            return Err(BackupError::InvalidArchive(format!(
+                "{} is missing",
+                MANIFEST_NAME
+            )))
+        }
+        Err(e) => return Err(e.into()),
+    };
+
+    if manifest.format_version > BACKUP_FORMAT_VERSION {
+        return Err(BackupError::UnsupportedVersion(
+            manifest.format_version,
+            BACKUP_FORMAT_VERSION,
+        ));
+    }
+    if !manifest.files.iter().any(|f| f.path == DATABASE_NAME) {
+        return Err(BackupError::InvalidArchive(format!(
+            "{} is missing",
+            DATABASE_NAME
+        )));
+    }
+    if let Some(file) = manifest.files.iter().find(|f| !is_known_entry(&f.path)) {
+        return Err(BackupError::InvalidArchive(format!(
+            "unexpected entry {}",
+            file.path
+        )));
+    }
+
+    Ok(manifest)
+}
+
+/// Only the entries written by [`create_backup`] are restored; this also
+/// rules out paths escaping the staging directory
+fn is_known_entry(path: &str) -> bool {
+    match path.strip_prefix(RAW_LOGS_PREFIX) {
+        Some(name) => {
+            !name.is_empty() && !name.contains(['/', '\\']) && name != ".." && name != "."
+        }
+        None => path == DATABASE_NAME || path == CONFIG_NAME,
+    }
+}
+
+/// Extract every manifest entry into `staging`, checking size and SHA256
+fn extract_verified<R: Read + Seek>(
+    archive: &mut ZipArchive<R>,
+    manifest: &BackupManifest,
+    staging: &Path,
+) -> Result<(), BackupError> {
+    fs::create_dir_all(staging.join(RAW_LOGS_PREFIX))?;
+
+    for entry in &manifest.files {
+        let mut source = archive.by_name(&entry.path).map_err(|e| match e {
+            zip::result::ZipError::FileNotFound => {
+                BackupError::InvalidArchive(format!("{} is listed but missing", entry.path))
+            }
+            e => e.into(),
+        })?;
+        let mut target = BufWriter::new(File::create(staging.join(&entry.path))?);
+        let (size, sha256) = copy_hashed(&mut source, &mut target)?;
+        target.flush()?;
+
+        if size != entry.size || sha256 != entry.sha256 {
+            return Err(BackupError::ChecksumMismatch(entry.path.clone()));
+        }
+    }
+
+    Ok(())
+}
+
+fn apply_restore(
+    db: &Database,
+    manifest: &BackupManifest,
+    staging: &Path,
+    mode: RestoreMode,
+) -> Result<RestoreReport, BackupError> {
+    let staged_db = staging.join(DATABASE_NAME);
+    let source_flights = Database::count_flights_in(&staged_db)?;
+
+    let mut report = RestoreReport {
+        mode,
+        ..Default::default()
+    };
+
+    match mode {
+        RestoreMode::Replace => {
+            let previous = db.replace_database(&staged_db)?;
+            report.flights_restored = source_flights;
+            report.previous_database = Some(previous.to_string_lossy().to_string());
+        }
+        RestoreMode::Merge => {
+            let outcome = db.merge_database(&staged_db)?;
+            report.flights_restored = outcome.imported.len();
+            report.flights_skipped = outcome.source_flights - outcome.imported.len();
+        }
+    }
+
+    // A merge keeps the local configuration unless there is none
+    let staged_config = staging.join(CONFIG_NAME);
+    let config_path = db.data_dir.join(CONFIG_NAME);
+    if staged_config.is_file() && (mode == RestoreMode::Replace || !config_path.exists()) {
+        fs::copy(&staged_config, &config_path)?;
+    }
+
+    report.raw_logs_restored = restore_raw_logs(db, manifest, staging)?;
+
+    log::info!(
+        "Restored backup from {} ({:?}): {} flights, {} raw logs",
+        manifest.created_at,
+        mode,
+        report.flights_restored,
+        report.raw_logs_restored
+    );
+    Ok(report)
+}
+
+/// Move staged raw logs into raw_logs, skipping identical files and
+/// renaming on name clashes. Flights find their logs again by file hash.
+fn restore_raw_logs(
+    db: &Database,
+    manifest: &BackupManifest,
+    staging: &Path,
+) -> Result<usize, BackupError> {
+    let raw_logs_dir = db.raw_logs_dir();
+    fs::create_dir_all(&raw_logs_dir)?;
+    let mut restored = 0;
+
+    for entry in &manifest.files {
+        let Some(name) = entry.path.strip_prefix(RAW_LOGS_PREFIX) else {
+            continue;
+        };
+
+        let mut target = raw_logs_dir.join(name);
+        if target.exists() {
+            let existing_hash = LogParser::calculate_file_hash(&target).ok();
+            if existing_hash.as_deref() == Some(entry.sha256.as_str()) {
+                continue;
+            }
+            target = unique_path(&raw_logs_dir, name);
+        }
+
+        fs::rename(staging.join(&entry.path), &target)?;
+        restored += 1;
+    }
+
+    Ok(restored)
+}
+
+/// `<stem>_restored_<n>.<ext>` that does not exist yet in `dir`
+fn unique_path(dir: &Path, name: &str) -> PathBuf {
+    let path = Path::new(name);
+    let stem = path.file_stem().and_then(|s| s.to_str()).unwrap_or("log");
+    let extension = path.extension().and_then(|e| e.to_str());
+
+    (1..)
+        .map(|n| {
+            let file_name = match extension {
+                Some(ext) => format!("{}_restored_{}.{}", stem, n, ext),
+                None => format!("{}_restored_{}", stem, n),
+            };
+            dir.join(file_name)
+        })
+        .find(|candidate| !candidate.exists())
+        .expect("unbounded range always yields a free name")
+}
+
+/// Copy `reader` into `writer`, returning the byte count and SHA256
+fn copy_hashed<R: Read, W: Write>(
+    reader: &mut R,
+    writer: &mut W,
+) -> Result<(u64, String), BackupError> {
+    let mut hasher = Sha256::new();
+    let mut buffer = vec![0u8; 64 * 1024];
+    let mut size = 0u64;
+
+    loop {
+        let bytes_read = reader.read(&mut buffer)?;
+        if bytes_read == 0 {
+            break;
+        }
+        hasher.update(&buffer[..bytes_read]);
+        writer.write_all(&buffer[..bytes_read])?;
+        size += bytes_read as u64;
+    }
+
+    Ok((size, format!("{:x}", hasher.finalize())))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{FlightEvents, FlightMetadata, TelemetryPoint};
+    use crate::test_support::sample_flight;
+    use tempfile::tempdir;
+
+    fn add_flight(db: &Database, number: i64, hash: &str) {
+        let flight = FlightMetadata {
+            point_count: 1,
+            ..sample_flight(number, hash)
+        };
+        let points = vec![TelemetryPoint {
+            latitude: Some(47.0),
+            longitude: Some(8.0),
+            ..Default::default()
+        }];
+        db.insert_flight_with_telemetry(&flight, &points, &FlightEvents::default())
+            .unwrap();
+    }
+
+    #[test]
+    fn test_backup_restore_replace_and_merge() {
+        let source_dir = tempdir().unwrap();
+        let source = Database::new(source_dir.path().to_path_buf()).unwrap();
+        add_flight(&source, 1, "hash-a");
+        add_flight(&source, 2, "hash-b");
+        fs::write(source.raw_logs_dir().join("1.txt"), b"raw log").unwrap();
+
+        let archive_dir = tempdir().unwrap();
+        let archive = archive_dir.path().join("logbook.zip");
+        let manifest = create_backup(&source, &archive).unwrap();
+        assert_eq!(manifest.flight_count, 2);
+        assert!(manifest.files.iter().any(|f| f.path == "raw_logs/1.txt"));
+
+        // Merge: hash-a is already present locally
+        let merge_dir = tempdir().unwrap();
+        let merged = Database::new(merge_dir.path().to_path_buf()).unwrap();
+        add_flight(&merged, 7, "hash-a");
+        let report = restore_backup(&merged, &archive, RestoreMode::Merge).unwrap();
+        assert_eq!(report.flights_restored, 1);
+        assert_eq!(report.flights_skipped, 1);
+        assert_eq!(report.raw_logs_restored, 1);
+        assert_eq!(merged.get_all_flights().unwrap().len(), 2);
+
+        // Replace: the local flight is swapped out for the backed-up library
+        let replace_dir = tempdir().unwrap();
+        let replaced = Database::new(replace_dir.path().to_path_buf()).unwrap();
+        add_flight(&replaced, 9, "hash-z");
+        let report = restore_backup(&replaced, &archive, RestoreMode::Replace).unwrap();
+        assert_eq!(report.flights_restored, 2);
+        assert!(report.previous_database.is_some());
+        let mut names: Vec<String> = replaced
+            .get_all_flights()
+            .unwrap()
+            .into_iter()
+            .map(|f| f.display_name)
+            .collect();
+        names.sort();
+        assert_eq!(names, ["Flight 1", "Flight 2"]);
+    }
+
+    #[test]
+    fn test_rejects_unknown_entries() {
+        assert!(is_known_entry("raw_logs/DJIFlightRecord_2024.txt"));
+        assert!(!is_known_entry("raw_logs/../flights.db"));
+        assert!(!is_known_entry("../etc/passwd"));
+    }
+}
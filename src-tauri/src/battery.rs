@@ -0,0 +1,173 @@
+//! Battery lifecycle figures for the pack registry.
+//!
+//! The database keeps raw battery stats per flight (charge used, lowest
+//! voltage, temperature, resistance estimate). This module turns a pack's
+//! flights, oldest first, into running cycle counts and health scores
+//! relative to the pack when new, and summarizes them for the registry.
+
+use crate::models::{BatteryFlight, BatteryPack};
+
+/// Flights whose resistance estimates make up the "as new" baseline
+const BASELINE_FLIGHTS: usize = 5;
+
+/// Flights whose median gives the recent resistance and health figures,
+/// so a single noisy estimate doesn't swing them
+const RECENT_FLIGHTS: usize = 5;
+
+/// A health trend needs this many scored flights spread over this many cycles
+const MIN_TREND_FLIGHTS: usize = 5;
+const MIN_TREND_CYCLES: f64 = 1.0;
+
+/// Fill in the running cycle count and the health score of a pack's
+/// flights, which must be ordered oldest first
+///
+/// The health score compares the lowest resistance of the first flights
+/// with each flight's: 100 while the pack is as good as new, 50 once its
+/// resistance has doubled.
+pub fn apply_lifecycle(flights: &mut [BatteryFlight]) {
+    let mut cycles = 0.0;
+    for flight in flights.iter_mut() {
+        cycles += flight.discharged_percent.max(0.0) / 100.0;
+        flight.cycles = cycles;
+    }
+
+    let baseline = min(flights
+        .iter()
+        .filter_map(|f| f.internal_resistance_mohm)
+        .take(BASELINE_FLIGHTS));
+    for flight in flights.iter_mut() {
+        flight.health_score = match (baseline, flight.internal_resistance_mohm) {
+            (Some(base), Some(r)) if r > 0.0 => Some((100.0 * base / r).clamp(0.0, 100.0)),
+            _ => None,
+        };
+    }
+}
+
+/// Fill in the figures of a registry entry from its flights, after
+/// `apply_lifecycle`
+pub fn summarize(pack: &mut BatteryPack, flights: &[BatteryFlight]) {
+    pack.flight_count = flights.len() as i64;
+    pack.total_duration_secs = flights.iter().filter_map(|f| f.duration_secs).sum();
+    pack.cycles = flights.last().map_or(0.0, |f| f.cycles);
+    pack.first_flight = flights.iter().find_map(|f| f.start_time.clone());
+    pack.last_flight = flights.iter().rev().find_map(|f| f.start_time.clone());
+    pack.min_cell_voltage = min(flights.iter().filter_map(|f| f.min_cell_voltage));
+    pack.max_temp = flights.iter().filter_map(|f| f.max_temp).reduce(f64::max);
+    pack.internal_resistance_mohm = median(recent(flights, |f| f.internal_resistance_mohm));
+    pack.cell_imbalance = median(recent(flights, |f| f.max_cell_imbalance));
+    pack.health_score = median(recent(flights, |f| f.health_score));
+    pack.health_trend = health_trend(flights);
+}
+
+fn min(values: impl Iterator<Item = f64>) -> Option<f64> {
+    values.reduce(f64::min)
+}
+
+/// Values of the latest flights that have one
+fn recent(flights: &[BatteryFlight], value: impl Fn(&BatteryFlight) -> Option<f64>) -> Vec<f64> {
+    flights
+        .iter()
+        .rev()
+        .filter_map(value)
+        .take(RECENT_FLIGHTS)
+        .collect()
+}
+
+fn median(mut values: Vec<f64>) -> Option<f64> {
+    if values.is_empty() {
+        return None;
+    }
+    values.sort_by(f64::total_cmp);
+    let mid = values.len() / 2;
+    Some(if values.len() % 2 == 0 {
+        (values[mid - 1] + values[mid]) / 2.0
+    } else {
+        values[mid]
+    })
+}
+
+/// Least-squares slope of the health score against cycles, per 100 cycles
+fn health_trend(flights: &[BatteryFlight]) -> Option<f64> {
+    let points: Vec<(f64, f64)> = flights
+        .iter()
+        .filter_map(|f| f.health_score.map(|h| (f.cycles, h)))
+        .collect();
+    if points.len() < MIN_TREND_FLIGHTS {
+        return None;
+    }
+    let first_cycle = points.first()?.0;
+    let last_cycle = points.last()?.0;
+    if last_cycle - first_cycle < MIN_TREND_CYCLES {
+        return None;
+    }
+
+    let n = points.len() as f64;
+    let mean_x = points.iter().map(|p| p.0).sum::<f64>() / n;
+    let mean_y = points.iter().map(|p| p.1).sum::<f64>() / n;
+    let sxx: f64 = points.iter().map(|p| (p.0 - mean_x).powi(2)).sum();
+    let sxy: f64 = points.iter().map(|p| (p.0 - mean_x) * (p.1 - mean_y)).sum();
+    Some(sxy / sxx * 100.0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn flight(discharged_percent: f64, resistance: Option<f64>) -> BatteryFlight {
+        BatteryFlight {
+            discharged_percent,
+            duration_secs: Some(600.0),
+            internal_resistance_mohm: resistance,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_cycles_and_health_relative_to_new_pack() {
+        let mut flights = vec![
+            flight(80.0, Some(22.0)),
+            flight(70.0, Some(20.0)),
+            flight(50.0, None),
+            flight(100.0, Some(40.0)),
+        ];
+        apply_lifecycle(&mut flights);
+
+        let cycles: Vec<f64> = flights.iter().map(|f| f.cycles).collect();
+        assert_eq!(cycles, [0.8, 1.5, 2.0, 3.0]);
+        // Baseline is the best of the early estimates
+        assert_eq!(flights[1].health_score, Some(100.0));
+        assert_eq!(flights[2].health_score, None);
+        assert_eq!(flights[3].health_score, Some(50.0));
+    }
+
+    #[test]
+    fn test_summary_uses_recent_median_and_trend() {
+        // Resistance creeps up by 1 mOhm per flight, one cycle each
+        let mut flights: Vec<BatteryFlight> = (0..20)
+            .map(|i| flight(100.0, Some(20.0 + i as f64)))
+            .collect();
+        flights[19].internal_resistance_mohm = Some(200.0);
+        apply_lifecycle(&mut flights);
+
+        let mut pack = BatteryPack::default();
+        summarize(&mut pack, &flights);
+
+        assert_eq!(pack.flight_count, 20);
+        assert_eq!(pack.cycles, 20.0);
+        assert_eq!(pack.total_duration_secs, 12_000.0);
+        // The outlier on the last flight doesn't move the median
+        assert_eq!(pack.internal_resistance_mohm, Some(37.0));
+        assert!(pack.health_trend.unwrap() < 0.0);
+    }
+
+    #[test]
+    fn test_no_trend_without_enough_flights() {
+        let mut flights = vec![flight(100.0, Some(20.0)), flight(100.0, Some(25.0))];
+        apply_lifecycle(&mut flights);
+
+        let mut pack = BatteryPack::default();
+        summarize(&mut pack, &flights);
+        assert_eq!(pack.health_trend, None);
+        assert_eq!(pack.health_score, Some(90.0));
+    }
+}
@@ -0,0 +1,1185 @@
+//! DJI Flight Log Viewer - Headless CLI
+//!
+//! Batch import, listing and export of flight logs without the Tauri window.
+//! Shares the DuckDB library layout with the desktop app, so both can point
+//! at the same data directory.
+
+use std::fs::File;
+use std::io::{self, BufWriter, Write};
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+use std::sync::Arc;
+
+use clap::{Args, Parser, Subcommand};
+
+use dji_logviewer_lib::backup::{self, RestoreMode};
+use dji_logviewer_lib::database::{DatabaseError, ParquetPartition};
+use dji_logviewer_lib::export::{self, ExportFormat, ExportOptions};
+use dji_logviewer_lib::importer;
+use dji_logviewer_lib::logbook::{self, LogbookStyle};
+use dji_logviewer_lib::{
+    Database, Daylight, FileImportStatus, FlightEventQuery, FlightQuery, FlightSortKey, GeoBounds,
+    MaintenanceStatus, SortDirection,
+};
+
+/// Tauri bundle identifier, used to locate the desktop app's data directory
+const APP_IDENTIFIER: &str = "com.dji-logviewer";
+
+#[derive(Parser)]
+#[command(
+    name = "dji-logbook-cli",
+    version,
+    about = "Headless DJI flight log importer and exporter"
+)]
+struct Cli {
+    /// Data directory holding flights.db and raw_logs (defaults to the desktop app's data dir)
+    #[arg(long, global = true, env = "DJI_LOGBOOK_DATA_DIR")]
+    data_dir: Option<PathBuf>,
+
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Import log files, or every supported log inside the given directories
+    Import {
+        #[arg(required = true)]
+        paths: Vec<PathBuf>,
+        /// Only import logs directly inside the given directories
+        #[arg(long)]
+        no_recursive: bool,
+    },
+    /// Re-parse flights from their archived raw logs (all flights by default)
+    Reprocess {
+        /// Only re-process this flight
+        id: Option<i64>,
+    },
+    /// List imported flights, optionally filtered
+    List {
+        #[command(flatten)]
+        filter: FilterArgs,
+        /// Sort by date, name, duration, distance or altitude
+        #[arg(long, default_value = "date", value_parser = parse_sort_key)]
+        sort: FlightSortKey,
+        /// Sort ascending instead of descending
+        #[arg(long)]
+        asc: bool,
+        /// Show at most this many flights
+        #[arg(long)]
+        limit: Option<usize>,
+        /// Skip this many flights
+        #[arg(long)]
+        offset: Option<usize>,
+    },
+    /// Set the notes, tags or custom field values of a flight
+    Annotate {
+        id: i64,
+        /// Replace the notes (an empty string clears them)
+        #[arg(long)]
+        notes: Option<String>,
+        /// Replace the tags with these (repeatable)
+        #[arg(long = "tag")]
+        tags: Option<Vec<String>>,
+        /// Set a custom field as name=value, defining it if needed; an empty value clears it
+        #[arg(long = "field", value_parser = parse_field)]
+        fields: Vec<(String, String)>,
+    },
+    /// Show details for a single flight
+    Show { id: i64 },
+    /// Export one or more flights at full resolution into a single file
+    Export {
+        #[arg(required = true)]
+        ids: Vec<i64>,
+        /// Output format: csv, gpx, kml, geojson or json
+        #[arg(long, short, default_value = "csv")]
+        format: String,
+        /// KML altitude mode: relative, absolute or clamp
+        #[arg(long, default_value = "relative")]
+        altitude_mode: String,
+        /// Output file (defaults to stdout)
+        #[arg(long, short)]
+        output: Option<PathBuf>,
+    },
+    /// Print the archived original log path of a flight, or copy it with --output
+    RawLog {
+        id: i64,
+        /// Copy the archived log to this path
+        #[arg(long, short)]
+        output: Option<PathBuf>,
+    },
+    /// Export the whole logbook as Parquet files into a directory
+    ParquetExport {
+        dir: PathBuf,
+        /// Split per-flight tables into directories: none, flight or month
+        #[arg(long, default_value = "none")]
+        partition: String,
+    },
+    /// Import a Parquet export, skipping flights already in the library
+    ParquetImport { dir: PathBuf },
+    /// Write a backup archive of the database, raw logs and config
+    Backup { output: PathBuf },
+    /// Restore a backup archive (replaces the library unless --merge is given)
+    Restore {
+        archive: PathBuf,
+        /// Add the backed-up flights missing from the current library instead
+        #[arg(long)]
+        merge: bool,
+    },
+    /// Print overview statistics, optionally for a subset of flights
+    Stats {
+        #[command(flatten)]
+        filter: FilterArgs,
+    },
+    /// List app tips and warnings across all flights
+    Events {
+        /// Only show events of this severity (info or warning)
+        #[arg(long)]
+        severity: Option<String>,
+        /// Only show events of this category (battery, gps, signal, ...)
+        #[arg(long)]
+        category: Option<String>,
+        /// Only show events whose message contains this text
+        #[arg(long)]
+        search: Option<String>,
+        /// Maximum number of events to print
+        #[arg(long)]
+        limit: Option<usize>,
+    },
+    /// List battery packs with cycle counts and health
+    Batteries,
+    /// Show the flight history of a battery pack, or edit its registry entry
+    Battery {
+        serial: String,
+        /// Name the pack (an empty string clears the name)
+        #[arg(long)]
+        name: Option<String>,
+        /// Replace the notes (an empty string clears them)
+        #[arg(long)]
+        notes: Option<String>,
+        /// Mark the pack as retired
+        #[arg(long, conflicts_with = "reactivate")]
+        retire: bool,
+        /// Put a retired pack back in service
+        #[arg(long)]
+        reactivate: bool,
+    },
+    /// List aircraft with airframe hours and maintenance status, or show and
+    /// edit one aircraft
+    Aircraft {
+        serial: Option<String>,
+        /// Name the aircraft (an empty string clears the name)
+        #[arg(long, requires = "serial")]
+        name: Option<String>,
+        /// Replace the notes (an empty string clears them)
+        #[arg(long, requires = "serial")]
+        notes: Option<String>,
+    },
+    /// Add, complete or remove aircraft maintenance items
+    Maintenance {
+        #[command(subcommand)]
+        action: MaintenanceAction,
+    },
+    /// List pilots with flight, night and 90-day totals
+    Pilots,
+    /// Add or remove pilots and assign flights to them
+    Pilot {
+        #[command(subcommand)]
+        action: PilotAction,
+    },
+    /// Write a pilot logbook of the matching flights, oldest first
+    Logbook {
+        #[command(flatten)]
+        filter: FilterArgs,
+        /// Column layout: easa or part107
+        #[arg(long, default_value = "easa")]
+        style: String,
+        /// Write a printable HTML page instead of CSV
+        #[arg(long)]
+        html: bool,
+        /// Output file (defaults to stdout)
+        #[arg(long, short)]
+        output: Option<PathBuf>,
+    },
+}
+
+#[derive(Subcommand)]
+enum MaintenanceAction {
+    /// Add a recurring maintenance item to an aircraft
+    Add {
+        serial: String,
+        name: String,
+        /// Due every this many flight hours
+        #[arg(long)]
+        hours: Option<f64>,
+        /// Due every this many flights
+        #[arg(long)]
+        flights: Option<i64>,
+    },
+    /// Record that a maintenance item was done
+    Done {
+        id: i64,
+        /// When it was done (YYYY-MM-DD or a timestamp, defaults to now)
+        #[arg(long)]
+        at: Option<String>,
+    },
+    /// Remove a maintenance item
+    Remove { id: i64 },
+}
+
+#[derive(Subcommand)]
+enum PilotAction {
+    /// Add a pilot
+    Add {
+        name: String,
+        /// Remote pilot licence or certificate number
+        #[arg(long, default_value = "")]
+        license: String,
+    },
+    /// Remove a pilot; their flights become unassigned
+    Remove { id: i64 },
+    /// Assign flights to a pilot
+    Assign {
+        pilot: i64,
+        #[arg(required = true)]
+        ids: Vec<i64>,
+    },
+    /// Clear the pilot of flights
+    Unassign {
+        #[arg(required = true)]
+        ids: Vec<i64>,
+    },
+    /// Assign all flights of an aircraft to a pilot, including future
+    /// imports (omit the pilot to remove the rule)
+    Aircraft { serial: String, pilot: Option<i64> },
+}
+
+/// Flight filters shared by `list`, `stats` and `logbook`
+#[derive(Args)]
+struct FilterArgs {
+    /// Only flights whose name, notes, tags or custom fields contain this text
+    #[arg(long)]
+    search: Option<String>,
+    /// Only flights with this tag (repeat to require several)
+    #[arg(long = "tag")]
+    tags: Vec<String>,
+    /// Only flights with this custom field value, as name=value (repeatable)
+    #[arg(long = "field", value_parser = parse_field)]
+    fields: Vec<(String, String)>,
+    /// Only flights on or after this day (YYYY-MM-DD, UTC)
+    #[arg(long)]
+    from: Option<String>,
+    /// Only flights on or before this day (YYYY-MM-DD, UTC)
+    #[arg(long)]
+    to: Option<String>,
+    #[arg(long)]
+    drone_model: Option<String>,
+    #[arg(long)]
+    drone_serial: Option<String>,
+    #[arg(long)]
+    battery_serial: Option<String>,
+    /// Only flights assigned to this pilot id
+    #[arg(long)]
+    pilot: Option<i64>,
+    /// Only flights flown in this light: day, twilight or night
+    #[arg(long, value_parser = parse_daylight)]
+    daylight: Option<Daylight>,
+    /// Minimum duration in seconds
+    #[arg(long)]
+    min_duration: Option<f64>,
+    /// Minimum distance in meters
+    #[arg(long)]
+    min_distance: Option<f64>,
+    /// Only flights with their home point in min_lat,min_lon,max_lat,max_lon
+    #[arg(long, value_parser = parse_bounds)]
+    bbox: Option<GeoBounds>,
+}
+
+impl FilterArgs {
+    fn into_query(self) -> FlightQuery {
+        FlightQuery {
+            search: self.search,
+            tags: self.tags,
+            custom_fields: self.fields.into_iter().collect(),
+            date_from: self.from,
+            date_to: self.to,
+            drone_model: self.drone_model,
+            drone_serial: self.drone_serial,
+            battery_serial: self.battery_serial,
+            pilot_id: self.pilot,
+            daylight: self.daylight,
+            min_duration_secs: self.min_duration,
+            min_distance_m: self.min_distance,
+            bounds: self.bbox,
+            ..Default::default()
+        }
+    }
+}
+
+#[tokio::main]
+async fn main() -> ExitCode {
+    env_logger::Builder::from_env(env_logger::Env::default().default_filter_or("warn")).init();
+
+    let cli = Cli::parse();
+
+    match run(cli).await {
+        Ok(code) => code,
+        Err(e) => {
+            eprintln!("Error: {}", e);
+            ExitCode::FAILURE
+        }
+    }
+}
+
+async fn run(cli: Cli) -> Result<ExitCode, String> {
+    let data_dir = match cli.data_dir {
+        Some(dir) => dir,
+        None => default_data_dir()?,
+    };
+
+    let db =
+        Database::new(data_dir).map_err(|e| format!("Failed to initialize database: {}", e))?;
+
+    match cli.command {
+        Command::Import {
+            paths,
+            no_recursive,
+        } => import(db, &paths, !no_recursive).await,
+        Command::Reprocess { id } => reprocess(db, id).await,
+        Command::List {
+            filter,
+            sort,
+            asc,
+            limit,
+            offset,
+        } => {
+            let query = FlightQuery {
+                sort_by: sort,
+                sort_direction: if asc {
+                    SortDirection::Asc
+                } else {
+                    SortDirection::Desc
+                },
+                offset,
+                limit,
+                ..filter.into_query()
+            };
+            list(&db, &query).map(|_| ExitCode::SUCCESS)
+        }
+        Command::Annotate {
+            id,
+            notes,
+            tags,
+            fields,
+        } => {
+            annotate(&db, id, notes.as_deref(), tags.as_deref(), &fields).map(|_| ExitCode::SUCCESS)
+        }
+        Command::Show { id } => show(&db, id).map(|_| ExitCode::SUCCESS),
+        Command::Export {
+            ids,
+            format,
+            altitude_mode,
+            output,
+        } => export_flights(&db, &ids, &format, &altitude_mode, output.as_deref())
+            .map(|_| ExitCode::SUCCESS),
+        Command::RawLog { id, output } => {
+            raw_log(&db, id, output.as_deref()).map(|_| ExitCode::SUCCESS)
+        }
+        Command::ParquetExport { dir, partition } => {
+            parquet_export(&db, &dir, &partition).map(|_| ExitCode::SUCCESS)
+        }
+        Command::ParquetImport { dir } => parquet_import(&db, &dir).map(|_| ExitCode::SUCCESS),
+        Command::Backup { output } => create_backup(&db, &output).map(|_| ExitCode::SUCCESS),
+        Command::Restore { archive, merge } => {
+            restore_backup(&db, &archive, merge).map(|_| ExitCode::SUCCESS)
+        }
+        Command::Stats { filter } => stats(&db, &filter.into_query()).map(|_| ExitCode::SUCCESS),
+        Command::Events {
+            severity,
+            category,
+            search,
+            limit,
+        } => {
+            let query = FlightEventQuery {
+                severity,
+                category,
+                search,
+                limit,
+            };
+            events(&db, &query).map(|_| ExitCode::SUCCESS)
+        }
+        Command::Batteries => batteries(&db).map(|_| ExitCode::SUCCESS),
+        Command::Battery {
+            serial,
+            name,
+            notes,
+            retire,
+            reactivate,
+        } => {
+            let retired = match (retire, reactivate) {
+                (true, _) => Some(true),
+                (_, true) => Some(false),
+                _ => None,
+            };
+            battery(&db, &serial, name.as_deref(), notes.as_deref(), retired)
+                .map(|_| ExitCode::SUCCESS)
+        }
+        Command::Aircraft { serial: None, .. } => fleet(&db).map(|_| ExitCode::SUCCESS),
+        Command::Aircraft {
+            serial: Some(serial),
+            name,
+            notes,
+        } => aircraft(&db, &serial, name.as_deref(), notes.as_deref()).map(|_| ExitCode::SUCCESS),
+        Command::Maintenance { action } => maintenance(&db, action).map(|_| ExitCode::SUCCESS),
+        Command::Pilots => pilots(&db).map(|_| ExitCode::SUCCESS),
+        Command::Pilot { action } => pilot(&db, action).map(|_| ExitCode::SUCCESS),
+        Command::Logbook {
+            filter,
+            style,
+            html,
+            output,
+        } => logbook(&db, &filter.into_query(), &style, html, output.as_deref())
+            .map(|_| ExitCode::SUCCESS),
+    }
+}
+
+/// Same location Tauri resolves for `app_data_dir()`
+fn default_data_dir() -> Result<PathBuf, String> {
+    dirs::data_dir()
+        .map(|dir| dir.join(APP_IDENTIFIER))
+        .ok_or_else(|| "Failed to get app data directory, pass --data-dir".to_string())
+}
+
+async fn import(db: Database, paths: &[PathBuf], recursive: bool) -> Result<ExitCode, String> {
+    let mut files = Vec::new();
+    for path in paths {
+        if path.is_dir() {
+            let found = importer::collect_log_files(path, recursive)
+                .map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+            files.extend(found);
+        } else {
+            files.push(path.clone());
+        }
+    }
+
+    let report = importer::import_files(Arc::new(db), files).await;
+
+    for outcome in &report.files {
+        println!(
+            "{}\t{}\t{}",
+            status_label(outcome.status),
+            outcome.file_path,
+            outcome.message
+        );
+    }
+
+    println!(
+        "Imported {} of {} files ({} points): {} duplicate, {} no telemetry, {} decrypt failed, {} parse error, {} storage error",
+        report.imported,
+        report.total_files,
+        report.total_points,
+        report.duplicates,
+        report.no_telemetry,
+        report.decrypt_failed,
+        report.parse_errors,
+        report.storage_errors
+    );
+
+    // Duplicates and empty logs are expected in SD-card dumps; only real failures fail the run
+    if report.decrypt_failed + report.parse_errors + report.storage_errors > 0 {
+        Ok(ExitCode::FAILURE)
+    } else {
+        Ok(ExitCode::SUCCESS)
+    }
+}
+
+fn status_label(status: FileImportStatus) -> &'static str {
+    match status {
+        FileImportStatus::Imported => "imported",
+        FileImportStatus::Duplicate => "duplicate",
+        FileImportStatus::NoTelemetry => "no-telemetry",
+        FileImportStatus::DecryptFailed => "decrypt-failed",
+        FileImportStatus::ParseError => "parse-error",
+        FileImportStatus::StorageError => "storage-error",
+    }
+}
+
+async fn reprocess(db: Database, id: Option<i64>) -> Result<ExitCode, String> {
+    let db = Arc::new(db);
+    let outcomes = match id {
+        Some(id) => vec![importer::reprocess_flight(db, id).await?],
+        None => importer::reprocess_all(db).await?.flights,
+    };
+
+    let mut failed = 0usize;
+    for outcome in &outcomes {
+        if !outcome.success {
+            failed += 1;
+        }
+        println!(
+            "{}\t{}\t{}",
+            if outcome.success { "ok" } else { "failed" },
+            outcome.flight_id,
+            outcome.message
+        );
+    }
+
+    println!(
+        "Re-processed {} of {} flights",
+        outcomes.len() - failed,
+        outcomes.len()
+    );
+    Ok(if failed > 0 {
+        ExitCode::FAILURE
+    } else {
+        ExitCode::SUCCESS
+    })
+}
+
+fn list(db: &Database, query: &FlightQuery) -> Result<(), String> {
+    let page = db
+        .query_flights(query)
+        .map_err(|e| format!("Failed to get flights: {}", e))?;
+
+    println!("ID\tSTART\tDURATION_S\tDISTANCE_M\tDRONE\tLIGHT\tTAGS\tNAME");
+    for f in &page.flights {
+        println!(
+            "{}\t{}\t{:.0}\t{:.0}\t{}\t{}\t{}\t{}",
+            f.id,
+            f.start_time.as_deref().unwrap_or("-"),
+            f.duration_secs.unwrap_or(0.0),
+            f.total_distance.unwrap_or(0.0),
+            f.drone_model.as_deref().unwrap_or("-"),
+            daylight_label(f.daylight),
+            if f.tags.is_empty() {
+                "-".to_string()
+            } else {
+                f.tags.join(",")
+            },
+            f.display_name
+        );
+    }
+    if page.flights.len() as i64 != page.total_count {
+        eprintln!(
+            "Showing {}-{} of {} flights",
+            page.offset + 1,
+            page.offset + page.flights.len(),
+            page.total_count
+        );
+    }
+    eprintln!(
+        "Total: {} flights, {:.1} h, {:.1} km",
+        page.total_count,
+        page.total_duration_secs / 3600.0,
+        page.total_distance_m / 1000.0
+    );
+    Ok(())
+}
+
+fn annotate(
+    db: &Database,
+    id: i64,
+    notes: Option<&str>,
+    tags: Option<&[String]>,
+    fields: &[(String, String)],
+) -> Result<(), String> {
+    if let Some(notes) = notes {
+        db.update_flight_notes(id, notes)
+            .map_err(|e| format!("Failed to update notes: {}", e))?;
+    }
+    if let Some(tags) = tags {
+        db.set_flight_tags(id, tags)
+            .map_err(|e| format!("Failed to update tags: {}", e))?;
+    }
+    for (name, value) in fields {
+        db.add_custom_field(name)
+            .and_then(|_| db.set_flight_custom_field(id, name.trim(), value))
+            .map_err(|e| format!("Failed to set {}: {}", name, e))?;
+    }
+    Ok(())
+}
+
+/// Parse a `name=value` custom field argument
+fn parse_field(arg: &str) -> Result<(String, String), String> {
+    let (name, value) = arg
+        .split_once('=')
+        .ok_or_else(|| format!("expected name=value, got {}", arg))?;
+    Ok((name.trim().to_string(), value.to_string()))
+}
+
+/// Parse a `min_lat,min_lon,max_lat,max_lon` bounding box argument
+fn parse_bounds(arg: &str) -> Result<GeoBounds, String> {
+    let values = arg
+        .split(',')
+        .map(|v| v.trim().parse::<f64>().map_err(|e| format!("{}: {}", v, e)))
+        .collect::<Result<Vec<_>, _>>()?;
+    match values[..] {
+        [min_lat, min_lon, max_lat, max_lon] => Ok(GeoBounds {
+            min_lat,
+            min_lon,
+            max_lat,
+            max_lon,
+        }),
+        _ => Err(format!(
+            "expected min_lat,min_lon,max_lat,max_lon, got {}",
+            arg
+        )),
+    }
+}
+
+fn parse_sort_key(arg: &str) -> Result<FlightSortKey, String> {
+    match arg.to_lowercase().as_str() {
+        "date" => Ok(FlightSortKey::StartTime),
+        "name" => Ok(FlightSortKey::Name),
+        "duration" => Ok(FlightSortKey::Duration),
+        "distance" => Ok(FlightSortKey::Distance),
+        "altitude" => Ok(FlightSortKey::MaxAltitude),
+        other => Err(format!("unknown sort key: {}", other)),
+    }
+}
+
+fn parse_daylight(arg: &str) -> Result<Daylight, String> {
+    match arg.to_lowercase().as_str() {
+        "day" => Ok(Daylight::Day),
+        "twilight" | "civil-twilight" => Ok(Daylight::CivilTwilight),
+        "night" => Ok(Daylight::Night),
+        other => Err(format!("unknown light condition: {}", other)),
+    }
+}
+
+fn daylight_label(daylight: Option<Daylight>) -> &'static str {
+    match daylight {
+        Some(Daylight::Day) => "day",
+        Some(Daylight::CivilTwilight) => "twilight",
+        Some(Daylight::Night) => "night",
+        None => "-",
+    }
+}
+
+fn show(db: &Database, id: i64) -> Result<(), String> {
+    let flight = db
+        .get_flight(id)
+        .map_err(|e| format!("Failed to get flight: {}", e))?;
+
+    let json = serde_json::to_string_pretty(&flight).map_err(|e| e.to_string())?;
+    println!("{}", json);
+    Ok(())
+}
+
+fn export_flights(
+    db: &Database,
+    ids: &[i64],
+    format: &str,
+    altitude_mode: &str,
+    output: Option<&Path>,
+) -> Result<(), String> {
+    let format: ExportFormat = format
+        .parse()
+        .map_err(|e: export::ExportError| e.to_string())?;
+    let options = ExportOptions {
+        altitude_mode: altitude_mode
+            .parse()
+            .map_err(|e: export::ExportError| e.to_string())?,
+    };
+
+    let result = match output {
+        Some(path) => {
+            let file = File::create(path)
+                .map_err(|e| format!("Failed to create {}: {}", path.display(), e))?;
+            let mut writer = BufWriter::new(file);
+            export::export_flights(db, ids, format, &options, &mut writer)
+                .and_then(|_| writer.flush().map_err(Into::into))
+        }
+        None => {
+            let stdout = io::stdout();
+            let mut writer = BufWriter::new(stdout.lock());
+            export::export_flights(db, ids, format, &options, &mut writer)
+                .and_then(|_| writer.flush().map_err(Into::into))
+        }
+    };
+
+    result.map_err(|e| format!("Export failed: {}", e))
+}
+
+fn raw_log(db: &Database, id: i64, output: Option<&Path>) -> Result<(), String> {
+    match output {
+        Some(path) => importer::export_archived_log(db, id, path),
+        None => {
+            println!("{}", importer::archived_log_path(db, id)?.display());
+            Ok(())
+        }
+    }
+}
+
+fn parquet_export(db: &Database, dir: &Path, partition: &str) -> Result<(), String> {
+    let partition: ParquetPartition = partition
+        .parse()
+        .map_err(|e: DatabaseError| e.to_string())?;
+    let count = db
+        .export_parquet(dir, partition)
+        .map_err(|e| format!("Parquet export failed: {}", e))?;
+
+    println!("Exported {} flights to {}", count, dir.display());
+    Ok(())
+}
+
+fn parquet_import(db: &Database, dir: &Path) -> Result<(), String> {
+    let report = db
+        .import_parquet(dir)
+        .map_err(|e| format!("Parquet import failed: {}", e))?;
+
+    println!(
+        "Imported {} flights ({} telemetry points), {} already in the library",
+        report.flights_imported, report.telemetry_rows, report.flights_skipped
+    );
+    Ok(())
+}
+
+fn create_backup(db: &Database, output: &Path) -> Result<(), String> {
+    let manifest =
+        backup::create_backup(db, output).map_err(|e| format!("Backup failed: {}", e))?;
+
+    println!(
+        "Backed up {} flights ({} files) to {}",
+        manifest.flight_count,
+        manifest.files.len(),
+        output.display()
+    );
+    Ok(())
+}
+
+fn restore_backup(db: &Database, archive: &Path, merge: bool) -> Result<(), String> {
+    let mode = if merge {
+        RestoreMode::Merge
+    } else {
+        RestoreMode::Replace
+    };
+    let report =
+        backup::restore_backup(db, archive, mode).map_err(|e| format!("Restore failed: {}", e))?;
+
+    match mode {
+        RestoreMode::Merge => println!(
+            "Merged {} flights, {} already in the library",
+            report.flights_restored, report.flights_skipped
+        ),
+        RestoreMode::Replace => println!("Restored {} flights", report.flights_restored),
+    }
+    println!("Restored {} raw logs", report.raw_logs_restored);
+    if let Some(previous) = report.previous_database {
+        println!("Previous database kept at {}", previous);
+    }
+    Ok(())
+}
+
+fn stats(db: &Database, query: &FlightQuery) -> Result<(), String> {
+    let stats = db
+        .get_overview_stats(query)
+        .map_err(|e| format!("Failed to get overview stats: {}", e))?;
+
+    println!("Flights:             {}", stats.total_flights);
+    println!(
+        "Total distance:      {:.1} km",
+        stats.total_distance_m / 1000.0
+    );
+    println!(
+        "Total duration:      {:.1} h",
+        stats.total_duration_secs / 3600.0
+    );
+    println!("Telemetry points:    {}", stats.total_points);
+    println!("Max altitude:        {:.1} m", stats.max_altitude_m);
+    println!(
+        "Max dist. from home: {:.1} m",
+        stats.max_distance_from_home_m
+    );
+    println!("Day flights:         {}", stats.day_flights);
+    println!("Twilight flights:    {}", stats.civil_twilight_flights);
+    println!("Night flights:       {}", stats.night_flights);
+
+    if !stats.drones_used.is_empty() {
+        println!();
+        println!("Drones:");
+        for drone in &stats.drones_used {
+            println!(
+                "  {} {} ({} flights)",
+                drone.drone_model,
+                drone.drone_serial.as_deref().unwrap_or(""),
+                drone.flight_count
+            );
+        }
+    }
+
+    if !stats.batteries_used.is_empty() {
+        println!();
+        println!("Batteries:");
+        for battery in &stats.batteries_used {
+            println!(
+                "  {} ({} flights, {:.1} h)",
+                battery.battery_serial,
+                battery.flight_count,
+                battery.total_duration_secs / 3600.0
+            );
+        }
+    }
+
+    Ok(())
+}
+
+fn events(db: &Database, query: &FlightEventQuery) -> Result<(), String> {
+    let entries = db
+        .query_flight_events(query)
+        .map_err(|e| format!("Failed to query flight events: {}", e))?;
+
+    println!("FLIGHT\tTIME_S\tSEVERITY\tCATEGORY\tMESSAGE");
+    for entry in entries {
+        println!(
+            "{}\t{:.1}\t{}\t{}\t{}",
+            entry.flight_id,
+            entry.event.timestamp_ms as f64 / 1000.0,
+            entry.event.severity,
+            entry.event.category,
+            entry.event.message
+        );
+    }
+    Ok(())
+}
+
+/// Format an optional reading, or "-" when unknown
+fn reading(value: Option<f64>, precision: usize) -> String {
+    value.map_or_else(|| "-".to_string(), |v| format!("{:.*}", precision, v))
+}
+
+fn batteries(db: &Database) -> Result<(), String> {
+    let packs = db
+        .get_batteries()
+        .map_err(|e| format!("Failed to get batteries: {}", e))?;
+
+    println!("SERIAL\tNAME\tFLIGHTS\tCYCLES\tHEALTH_%\tTREND_%/100CYC\tIR_MOHM\tMIN_CELL_V\tCELL_DELTA_V\tMAX_TEMP_C\tLAST_FLIGHT\tSTATUS");
+    for pack in packs {
+        println!(
+            "{}\t{}\t{}\t{:.1}\t{}\t{}\t{}\t{}\t{}\t{}\t{}\t{}",
+            pack.serial,
+            pack.name.as_deref().unwrap_or(""),
+            pack.flight_count,
+            pack.cycles,
+            reading(pack.health_score, 0),
+            reading(pack.health_trend, 1),
+            reading(pack.internal_resistance_mohm, 1),
+            reading(pack.min_cell_voltage, 2),
+            reading(pack.cell_imbalance, 3),
+            reading(pack.max_temp, 1),
+            pack.last_flight.as_deref().unwrap_or("-"),
+            if pack.retired_at.is_some() {
+                "retired"
+            } else {
+                "active"
+            }
+        );
+    }
+    Ok(())
+}
+
+fn battery(
+    db: &Database,
+    serial: &str,
+    name: Option<&str>,
+    notes: Option<&str>,
+    retired: Option<bool>,
+) -> Result<(), String> {
+    if let Some(name) = name {
+        db.rename_battery(serial, name)
+            .map_err(|e| format!("Failed to rename battery: {}", e))?;
+    }
+    if let Some(notes) = notes {
+        db.update_battery_notes(serial, notes)
+            .map_err(|e| format!("Failed to update battery notes: {}", e))?;
+    }
+    if let Some(retired) = retired {
+        db.set_battery_retired(serial, retired)
+            .map_err(|e| format!("Failed to update battery: {}", e))?;
+    }
+
+    let detail = db
+        .get_battery(serial)
+        .map_err(|e| format!("Failed to get battery: {}", e))?;
+    let pack = &detail.pack;
+    println!("Serial:        {}", pack.serial);
+    println!("Name:          {}", pack.name.as_deref().unwrap_or("-"));
+    println!(
+        "Status:        {}",
+        pack.retired_at
+            .as_deref()
+            .map_or("active".to_string(), |at| format!("retired {}", at))
+    );
+    println!("Flights:       {}", pack.flight_count);
+    println!("Cycles:        {:.1}", pack.cycles);
+    println!("Health:        {} %", reading(pack.health_score, 0));
+    println!(
+        "Health trend:  {} % per 100 cycles",
+        reading(pack.health_trend, 1)
+    );
+    println!(
+        "Resistance:    {} mOhm",
+        reading(pack.internal_resistance_mohm, 1)
+    );
+    println!("Cell delta:    {} V", reading(pack.cell_imbalance, 3));
+    if let Some(notes) = &pack.notes {
+        println!("Notes:         {}", notes);
+    }
+
+    println!();
+    println!("FLIGHT\tSTART\tDURATION_S\tUSED_%\tCYCLES\tMIN_V\tCELLS\tMIN_CELL_V\tCELL_DELTA_V\tMAX_TEMP_C\tIR_MOHM\tHEALTH_%");
+    for flight in &detail.flights {
+        println!(
+            "{}\t{}\t{}\t{:.0}\t{:.2}\t{}\t{}\t{}\t{}\t{}\t{}\t{}",
+            flight.flight_id,
+            flight.start_time.as_deref().unwrap_or("-"),
+            reading(flight.duration_secs, 0),
+            flight.discharged_percent,
+            flight.cycles,
+            reading(flight.min_voltage, 2),
+            flight.cell_count.map_or("-".to_string(), |c| c.to_string()),
+            reading(flight.min_cell_voltage, 2),
+            reading(flight.max_cell_imbalance, 3),
+            reading(flight.max_temp, 1),
+            reading(flight.internal_resistance_mohm, 1),
+            reading(flight.health_score, 0)
+        );
+    }
+    Ok(())
+}
+
+fn fleet(db: &Database) -> Result<(), String> {
+    let aircraft = db
+        .get_aircraft()
+        .map_err(|e| format!("Failed to get aircraft: {}", e))?;
+
+    println!("SERIAL\tNAME\tMODEL\tFLIGHTS\tHOURS\tFIRMWARE\tLAST_FLIGHT\tDUE\tOVERDUE");
+    for ac in aircraft {
+        let count = |status| ac.maintenance.iter().filter(|m| m.status == status).count();
+        println!(
+            "{}\t{}\t{}\t{}\t{:.1}\t{}\t{}\t{}\t{}",
+            ac.serial,
+            ac.name
+                .as_deref()
+                .or(ac.aircraft_name.as_deref())
+                .unwrap_or(""),
+            ac.drone_model.as_deref().unwrap_or("-"),
+            ac.flight_count,
+            ac.total_duration_secs / 3600.0,
+            ac.firmware_versions.last().map_or("-", String::as_str),
+            ac.last_flight.as_deref().unwrap_or("-"),
+            count(MaintenanceStatus::Due),
+            count(MaintenanceStatus::Overdue)
+        );
+    }
+    Ok(())
+}
+
+fn aircraft(
+    db: &Database,
+    serial: &str,
+    name: Option<&str>,
+    notes: Option<&str>,
+) -> Result<(), String> {
+    if let Some(name) = name {
+        db.rename_aircraft(serial, name)
+            .map_err(|e| format!("Failed to rename aircraft: {}", e))?;
+    }
+    if let Some(notes) = notes {
+        db.update_aircraft_notes(serial, notes)
+            .map_err(|e| format!("Failed to update aircraft notes: {}", e))?;
+    }
+
+    let ac = db
+        .get_aircraft_by_serial(serial)
+        .map_err(|e| format!("Failed to get aircraft: {}", e))?;
+    println!("Serial:        {}", ac.serial);
+    println!(
+        "Name:          {}",
+        ac.name
+            .as_deref()
+            .or(ac.aircraft_name.as_deref())
+            .unwrap_or("-")
+    );
+    println!(
+        "Model:         {}",
+        ac.drone_model.as_deref().unwrap_or("-")
+    );
+    println!("Flights:       {}", ac.flight_count);
+    println!("Airframe time: {:.1} h", ac.total_duration_secs / 3600.0);
+    println!(
+        "First flight:  {}",
+        ac.first_flight.as_deref().unwrap_or("-")
+    );
+    println!(
+        "Last flight:   {}",
+        ac.last_flight.as_deref().unwrap_or("-")
+    );
+    println!(
+        "Firmware:      {}",
+        if ac.firmware_versions.is_empty() {
+            "-".to_string()
+        } else {
+            ac.firmware_versions.join(", ")
+        }
+    );
+    if let Some(notes) = &ac.notes {
+        println!("Notes:         {}", notes);
+    }
+
+    println!();
+    println!("ID\tITEM\tEVERY_H\tEVERY_FLIGHTS\tLAST_DONE\tHOURS_SINCE\tFLIGHTS_SINCE\tHOURS_LEFT\tFLIGHTS_LEFT\tSTATUS");
+    for item in &ac.maintenance {
+        println!(
+            "{}\t{}\t{}\t{}\t{}\t{:.1}\t{}\t{}\t{}\t{}",
+            item.id,
+            item.name,
+            reading(item.interval_hours, 1),
+            item.interval_flights
+                .map_or("-".to_string(), |f| f.to_string()),
+            item.last_done_at.as_deref().unwrap_or("never"),
+            item.hours_since,
+            item.flights_since,
+            reading(item.hours_remaining, 1),
+            item.flights_remaining
+                .map_or("-".to_string(), |f| f.to_string()),
+            match item.status {
+                MaintenanceStatus::Ok => "ok",
+                MaintenanceStatus::Due => "due",
+                MaintenanceStatus::Overdue => "overdue",
+            }
+        );
+    }
+    Ok(())
+}
+
+fn maintenance(db: &Database, action: MaintenanceAction) -> Result<(), String> {
+    match action {
+        MaintenanceAction::Add {
+            serial,
+            name,
+            hours,
+            flights,
+        } => {
+            let id = db
+                .add_maintenance_item(&serial, &name, hours, flights)
+                .map_err(|e| format!("Failed to add maintenance item: {}", e))?;
+            println!("Added maintenance item {}", id);
+        }
+        MaintenanceAction::Done { id, at } => {
+            db.complete_maintenance_item(id, at.as_deref())
+                .map_err(|e| format!("Failed to complete maintenance item: {}", e))?;
+            println!("Marked maintenance item {} as done", id);
+        }
+        MaintenanceAction::Remove { id } => {
+            db.delete_maintenance_item(id)
+                .map_err(|e| format!("Failed to delete maintenance item: {}", e))?;
+            println!("Removed maintenance item {}", id);
+        }
+    }
+    Ok(())
+}
+
+fn pilots(db: &Database) -> Result<(), String> {
+    let pilots = db
+        .get_pilots()
+        .map_err(|e| format!("Failed to get pilots: {}", e))?;
+
+    println!("ID\tNAME\tLICENSE\tFLIGHTS\tHOURS\tNIGHT\tFLIGHTS_90D\tHOURS_90D\tNIGHT_90D\tLAST_FLIGHT\tAIRCRAFT");
+    for p in pilots {
+        println!(
+            "{}\t{}\t{}\t{}\t{:.1}\t{}\t{}\t{:.1}\t{}\t{}\t{}",
+            p.id,
+            p.name,
+            p.license_number.as_deref().unwrap_or("-"),
+            p.flight_count,
+            p.total_duration_secs / 3600.0,
+            p.night_flights,
+            p.recent_flights,
+            p.recent_duration_secs / 3600.0,
+            p.recent_night_flights,
+            p.last_flight.as_deref().unwrap_or("-"),
+            p.aircraft_serials.join(",")
+        );
+    }
+    Ok(())
+}
+
+fn pilot(db: &Database, action: PilotAction) -> Result<(), String> {
+    let assign = |ids: &[i64], pilot_id: Option<i64>| -> Result<(), String> {
+        for &id in ids {
+            db.set_flight_pilot(id, pilot_id)
+                .map_err(|e| format!("Failed to assign flight {}: {}", id, e))?;
+        }
+        Ok(())
+    };
+
+    match action {
+        PilotAction::Add { name, license } => {
+            let id = db
+                .add_pilot(&name, &license)
+                .map_err(|e| format!("Failed to add pilot: {}", e))?;
+            println!("Added pilot {}", id);
+        }
+        PilotAction::Remove { id } => {
+            db.delete_pilot(id)
+                .map_err(|e| format!("Failed to delete pilot: {}", e))?;
+            println!("Removed pilot {}", id);
+        }
+        PilotAction::Assign { pilot, ids } => {
+            assign(&ids, Some(pilot))?;
+            println!("Assigned {} flights to pilot {}", ids.len(), pilot);
+        }
+        PilotAction::Unassign { ids } => {
+            assign(&ids, None)?;
+            println!("Unassigned {} flights", ids.len());
+        }
+        PilotAction::Aircraft { serial, pilot } => {
+            let assigned = db
+                .set_aircraft_pilot(&serial, pilot)
+                .map_err(|e| format!("Failed to assign aircraft: {}", e))?;
+            match pilot {
+                Some(pilot) => println!(
+                    "Flights of {} go to pilot {} ({} existing flights assigned)",
+                    serial, pilot, assigned
+                ),
+                None => println!("Removed the pilot rule for {}", serial),
+            }
+        }
+    }
+    Ok(())
+}
+
+fn logbook(
+    db: &Database,
+    query: &FlightQuery,
+    style: &str,
+    html: bool,
+    output: Option<&Path>,
+) -> Result<(), String> {
+    let style: LogbookStyle = style
+        .parse()
+        .map_err(|e: logbook::LogbookError| e.to_string())?;
+
+    let result = match output {
+        Some(path) => {
+            let file = File::create(path)
+                .map_err(|e| format!("Failed to create {}: {}", path.display(), e))?;
+            let mut writer = BufWriter::new(file);
+            logbook::export_logbook(db, query, style, html, &mut writer)
+                .and_then(|count| writer.flush().map(|_| count).map_err(Into::into))
+        }
+        None => {
+            let stdout = io::stdout();
+            let mut writer = BufWriter::new(stdout.lock());
+            logbook::export_logbook(db, query, style, html, &mut writer)
+                .and_then(|count| writer.flush().map(|_| count).map_err(Into::into))
+        }
+    };
+
+    let count = result.map_err(|e| format!("Logbook export failed: {}", e))?;
+    if let Some(path) = output {
+        println!("Wrote {} flights to {}", count, path.display());
+    }
+    Ok(())
+}
@@ -0,0 +1,367 @@
+//! CSV import for flights exported by other DJI log tools.
+//!
+//! Supports the two layouts most historic logbooks exist in:
+//! - Airdata UAV exports (`time(millisecond)`, `height_above_takeoff(feet)`, ...)
+//! - DJI log CSV converters such as TXTlogToCSVtool / CsvView
+//!   (`OSD.flyTime [s]`, `OSD.height [ft]`, `BATTERY.voltage [V]`, ...)
+//!
+//! Headers are matched case-insensitively with their unit stripped, and values
+//! are converted to the metric units used by `TelemetryPoint`.
+
+use std::collections::HashMap;
+use std::path::Path;
+
+use chrono::{DateTime, NaiveDateTime, Utc};
+
+use crate::models::{FlightEvents, TelemetryPoint};
+use crate::parser::{ParsedLog, ParserError};
+
+/// Telemetry fields a CSV column can map to
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+enum Field {
+    Time,
+    Latitude,
+    Longitude,
+    Altitude,
+    Height,
+    VpsHeight,
+    AltitudeAbs,
+    Speed,
+    VelocityX,
+    VelocityY,
+    VelocityZ,
+    Pitch,
+    Roll,
+    Yaw,
+    GimbalPitch,
+    GimbalRoll,
+    GimbalYaw,
+    BatteryPercent,
+    BatteryVoltage,
+    BatteryCurrent,
+    BatteryTemp,
+    FlightMode,
+    GpsSignal,
+    Satellites,
+    RcUplink,
+    RcDownlink,
+    DateTime,
+    DroneModel,
+    DroneSerial,
+    AircraftName,
+    BatterySerial,
+}
+
+/// Known column names (lowercase, unit removed) for each field, Airdata first
+const COLUMNS: &[(Field, &[&str])] = &[
+    (Field::Time, &["time", "osd.flytime"]),
+    (Field::Latitude, &["latitude", "osd.latitude"]),
+    (Field::Longitude, &["longitude", "osd.longitude"]),
+    (Field::Altitude, &["altitude", "osd.altitude"]),
+    (Field::Height, &["height_above_takeoff", "osd.height"]),
+    (Field::VpsHeight, &["height_sonar", "osd.vpsheight"]),
+    (Field::AltitudeAbs, &["altitude_above_sealevel"]),
+    (Field::Speed, &["speed", "osd.hspeed"]),
+    (Field::VelocityX, &["xspeed", "osd.xspeed"]),
+    (Field::VelocityY, &["yspeed", "osd.yspeed"]),
+    (Field::VelocityZ, &["zspeed", "osd.zspeed"]),
+    (Field::Pitch, &["pitch", "osd.pitch"]),
+    (Field::Roll, &["roll", "osd.roll"]),
+    (Field::Yaw, &["compass_heading", "osd.yaw"]),
+    (Field::GimbalPitch, &["gimbal_pitch", "gimbal.pitch"]),
+    (Field::GimbalRoll, &["gimbal_roll", "gimbal.roll"]),
+    (Field::GimbalYaw, &["gimbal_heading", "gimbal.yaw"]),
+    (
+        Field::BatteryPercent,
+        &["battery_percent", "battery.chargelevel"],
+    ),
+    (Field::BatteryVoltage, &["voltage", "battery.voltage"]),
+    (Field::BatteryCurrent, &["current", "battery.current"]),
+    (
+        Field::BatteryTemp,
+        &["battery_temperature", "battery.temperature"],
+    ),
+    (Field::FlightMode, &["flycstate", "osd.flycstate"]),
+    (Field::GpsSignal, &["gpslevel", "osd.gpslevel"]),
+    (Field::Satellites, &["satellites", "osd.gpsnum"]),
+    (Field::RcUplink, &["rc.uplinksignal"]),
+    (Field::RcDownlink, &["rc.downlinksignal"]),
+    (Field::DateTime, &["datetime", "custom.datetime"]),
+    (
+        Field::DroneModel,
+        &["details.producttype", "recover.producttype"],
+    ),
+    (
+        Field::DroneSerial,
+        &["details.aircraftsn", "recover.aircraftsn"],
+    ),
+    (
+        Field::AircraftName,
+        &["details.aircraftname", "recover.aircraftname"],
+    ),
+    (
+        Field::BatterySerial,
+        &["details.batterysn", "recover.batterysn"],
+    ),
+];
+
+/// Column position and unit (lowercase, e.g. `feet`, `mph`) of a mapped field
+#[derive(Debug, Clone)]
+struct Column {
+    index: usize,
+    unit: Option<String>,
+}
+
+/// Whether a path should be read as a CSV export rather than a DJI log
+pub fn is_csv_log(path: &Path) -> bool {
+    path.extension()
+        .and_then(|e| e.to_str())
+        .map(|e| e.eq_ignore_ascii_case("csv"))
+        .unwrap_or(false)
+}
+
+/// Parse an Airdata or DJI-converter CSV export into telemetry
+pub(crate) fn parse_csv_log(data: &[u8]) -> Result<ParsedLog, ParserError> {
+    let mut reader = csv::ReaderBuilder::new()
+        .flexible(true)
+        .trim(csv::Trim::All)
+        .from_reader(strip_bom(data));
+
+    let headers = reader
+        .headers()
+        .map_err(|e| ParserError::Parse(format!("Invalid CSV header: {}", e)))?
+        .clone();
+    let columns = map_columns(&headers);
+
+    if !columns.contains_key(&Field::Latitude) || !columns.contains_key(&Field::Longitude) {
+        return Err(ParserError::Parse(
+            "Unrecognized CSV layout: no latitude/longitude columns".to_string(),
+        ));
+    }
+
+    let mut points = Vec::new();
+    let mut first_time_ms: Option<i64> = None;
+    let mut start_time = None;
+    let mut details: HashMap<Field, String> = HashMap::new();
+
+    for (row_index, record) in reader.records().enumerate() {
+        let record = record.map_err(|e| ParserError::Parse(format!("Invalid CSV row: {}", e)))?;
+        let text = |field: Field| {
+            columns
+                .get(&field)
+                .and_then(|c| record.get(c.index))
+                .filter(|v| !v.is_empty())
+        };
+        let number = |field: Field| {
+            let column = columns.get(&field)?;
+            let value: f64 = record.get(column.index)?.parse().ok()?;
+            value
+                .is_finite()
+                .then(|| convert(value, column.unit.as_deref()))
+        };
+
+        if start_time.is_none() {
+            start_time = text(Field::DateTime).and_then(parse_datetime);
+        }
+        for field in [
+            Field::DroneModel,
+            Field::DroneSerial,
+            Field::AircraftName,
+            Field::BatterySerial,
+        ] {
+            if let Some(value) = text(field) {
+                details.entry(field).or_insert_with(|| value.to_string());
+            }
+        }
+
+        // Rows without a position fix carry no track information
+        let (Some(lat), Some(lon)) = (number(Field::Latitude), number(Field::Longitude)) else {
+            continue;
+        };
+        if lat == 0.0 && lon == 0.0 {
+            continue;
+        }
+
+        // Airdata time is in ms, converter flyTime in seconds; default 10Hz
+        let time_ms = match (columns.get(&Field::Time), number(Field::Time)) {
+            (Some(column), Some(value)) if column.unit.as_deref() == Some("millisecond") => {
+                value as i64
+            }
+            (Some(_), Some(value)) => (value * 1000.0) as i64,
+            _ => row_index as i64 * 100,
+        };
+        let first = *first_time_ms.get_or_insert(time_ms);
+
+        points.push(TelemetryPoint {
+            timestamp_ms: time_ms - first,
+            latitude: Some(lat),
+            longitude: Some(lon),
+            altitude: number(Field::Altitude),
+            height: number(Field::Height),
+            vps_height: number(Field::VpsHeight),
+            altitude_abs: number(Field::AltitudeAbs),
+            speed: number(Field::Speed),
+            velocity_x: number(Field::VelocityX),
+            velocity_y: number(Field::VelocityY),
+            velocity_z: number(Field::VelocityZ),
+            pitch: number(Field::Pitch),
+            roll: number(Field::Roll),
+            yaw: number(Field::Yaw),
+            gimbal_pitch: number(Field::GimbalPitch),
+            gimbal_roll: number(Field::GimbalRoll),
+            gimbal_yaw: number(Field::GimbalYaw),
+            battery_percent: number(Field::BatteryPercent).map(|v| v.round() as i32),
+            battery_voltage: number(Field::BatteryVoltage),
+            battery_current: number(Field::BatteryCurrent),
+            battery_temp: number(Field::BatteryTemp),
+            flight_mode: text(Field::FlightMode).map(str::to_string),
+            gps_signal: number(Field::GpsSignal).map(|v| v as i32),
+            satellites: number(Field::Satellites).map(|v| v as i32),
+            rc_signal: number(Field::RcDownlink)
+                .or(number(Field::RcUplink))
+                .map(|v| v as i32),
+            rc_uplink: number(Field::RcUplink).map(|v| v as i32),
+            rc_downlink: number(Field::RcDownlink).map(|v| v as i32),
+            ..Default::default()
+        });
+    }
+
+    if points.is_empty() {
+        return Err(ParserError::NoTelemetryData);
+    }
+
+    let end_time = start_time.map(|start: DateTime<Utc>| {
+        start + chrono::Duration::milliseconds(points.last().map(|p| p.timestamp_ms).unwrap_or(0))
+    });
+
+    Ok(ParsedLog {
+        points,
+        events: FlightEvents::default(),
+        drone_model: details.remove(&Field::DroneModel),
+        drone_serial: details.remove(&Field::DroneSerial),
+        aircraft_name: details.remove(&Field::AircraftName),
+        battery_serial: details.remove(&Field::BatterySerial),
+        firmware_version: None,
+        start_time,
+        end_time,
+    })
+}
+
+/// Map header positions to fields; the first matching column wins
+fn map_columns(headers: &csv::StringRecord) -> HashMap<Field, Column> {
+    let mut columns = HashMap::new();
+
+    for (index, header) in headers.iter().enumerate() {
+        let (name, unit) = split_header(header);
+        for (field, names) in COLUMNS {
+            if names.contains(&name.as_str()) {
+                columns.entry(*field).or_insert(Column {
+                    index,
+                    unit: unit.clone(),
+                });
+            }
+        }
+    }
+
+    columns
+}
+
+/// Split `height_above_takeoff(feet)` or `OSD.height [ft]` into name and unit
+fn split_header(header: &str) -> (String, Option<String>) {
+    let header = header.trim().to_lowercase();
+    match header.find(['(', '[']) {
+        Some(pos) => {
+            let unit = header[pos + 1..]
+                .trim_end_matches([')', ']'])
+                .trim()
+                .to_string();
+            (header[..pos].trim().to_string(), Some(unit))
+        }
+        None => (header, None),
+    }
+}
+
+/// Convert a value to metric based on the unit found in its header
+fn convert(value: f64, unit: Option<&str>) -> f64 {
+    match unit {
+        Some("feet") | Some("ft") => value * 0.3048,
+        Some("mph") => value * 0.44704,
+        Some("kph") | Some("km/h") | Some("kmh") => value / 3.6,
+        Some("knots") | Some("kn") => value * 0.514444,
+        Some("f") | Some("fahrenheit") => (value - 32.0) * 5.0 / 9.0,
+        _ => value,
+    }
+}
+
+/// Parse the timestamp formats used by Airdata and the DJI converters (UTC)
+fn parse_datetime(value: &str) -> Option<DateTime<Utc>> {
+    if let Ok(t) = DateTime::parse_from_rfc3339(value) {
+        return Some(t.with_timezone(&Utc));
+    }
+
+    [
+        "%Y-%m-%d %H:%M:%S%.f",
+        "%Y-%m-%dT%H:%M:%S%.f",
+        "%Y/%m/%d %H:%M:%S%.f",
+        "%m/%d/%Y %I:%M:%S%.f %p",
+        "%m/%d/%Y %H:%M:%S%.f",
+    ]
+    .iter()
+    .find_map(|format| NaiveDateTime::parse_from_str(value.trim_end_matches('Z'), format).ok())
+    .map(|t| t.and_utc())
+}
+
+fn strip_bom(data: &[u8]) -> &[u8] {
+    data.strip_prefix(b"\xEF\xBB\xBF").unwrap_or(data)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_airdata_layout_converts_imperial_units() {
+        let csv = "\u{feff}time(millisecond),datetime(utc),latitude,longitude,height_above_takeoff(feet),speed(mph),satellites,voltage(v),battery_temperature(f),flycState\n\
+                   0,2021-05-01 12:00:00,0,0,0,0,0,16.8,68,Motors_Started\n\
+                   100,2021-05-01 12:00:00,47.0,8.0,10,10,12,16.7,68,P-GPS\n\
+                   200,2021-05-01 12:00:00,47.0001,8.0,20,20,13,16.6,86,P-GPS\n";
+
+        let log = parse_csv_log(csv.as_bytes()).unwrap();
+
+        assert_eq!(log.points.len(), 2);
+        let p = &log.points[1];
+        assert_eq!(p.timestamp_ms, 100);
+        assert!((p.height.unwrap() - 6.096).abs() < 1e-9);
+        assert!((p.speed.unwrap() - 8.9408).abs() < 1e-9);
+        assert!((p.battery_temp.unwrap() - 30.0).abs() < 1e-9);
+        assert_eq!(p.satellites, Some(13));
+        assert_eq!(p.flight_mode.as_deref(), Some("P-GPS"));
+        assert_eq!(
+            log.start_time.unwrap().to_rfc3339(),
+            "2021-05-01T12:00:00+00:00"
+        );
+    }
+
+    #[test]
+    fn test_dji_converter_layout() {
+        let csv = "CUSTOM.dateTime,OSD.flyTime [s],OSD.latitude,OSD.longitude,OSD.height [m],OSD.xSpeed [m/s],BATTERY.chargeLevel,RC.downlinkSignal,DETAILS.aircraftSN\n\
+                   2022-06-01T08:00:00Z,0.0,46.5,7.5,0.0,0.0,100,100,1581F\n\
+                   2022-06-01T08:00:00Z,0.5,46.5,7.5001,3.2,1.5,99,98,1581F\n";
+
+        let log = parse_csv_log(csv.as_bytes()).unwrap();
+
+        assert_eq!(log.points.len(), 2);
+        assert_eq!(log.points[1].timestamp_ms, 500);
+        assert_eq!(log.points[1].height, Some(3.2));
+        assert_eq!(log.points[1].velocity_x, Some(1.5));
+        assert_eq!(log.points[1].rc_signal, Some(98));
+        assert_eq!(log.drone_serial.as_deref(), Some("1581F"));
+        assert!(log.end_time.is_some());
+    }
+
+    #[test]
+    fn test_unknown_layout_is_rejected() {
+        let result = parse_csv_log(b"foo,bar\n1,2\n");
+        assert!(matches!(result, Err(ParserError::Parse(_))));
+    }
+}
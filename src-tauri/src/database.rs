@@ -5,16 +5,30 @@
 //! - Schema creation for flights and telemetry tables
 //! - Optimized bulk inserts using Appender
 //! - Downsampled query retrieval for large datasets
+//! - Parquet export/import and merging or replacing the library file
 
-use std::collections::HashSet;
+use std::collections::{BTreeMap, HashMap, HashSet};
 use std::fs;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
 use std::sync::Mutex;
 
+use chrono::TimeZone;
+use duckdb::types::{Type, Value};
 use duckdb::{params, Connection, Result as DuckResult};
 use thiserror::Error;
 
-use crate::models::{BatteryHealthPoint, BatteryUsage, DroneUsage, Flight, FlightDateCount, FlightMetadata, OverviewStats, TelemetryPoint, TelemetryRecord, TopDistanceFlight, TopFlight};
+use crate::battery;
+use crate::logbook;
+use crate::maintenance;
+use crate::models::{
+    Aircraft, BatteryDetail, BatteryFlight, BatteryHealthPoint, BatteryPack, BatteryUsage,
+    CameraEvent, Daylight, DroneUsage, Finding, Flight, FlightDateCount, FlightEvent,
+    FlightEventEntry, FlightEventQuery, FlightEvents, FlightMetadata, FlightPage, FlightQuery,
+    FlightSortKey, HomeEvent, LogbookEntry, MaintenanceItem, OverviewStats, ParquetImportReport,
+    Pilot, SortDirection, TelemetryPoint, TelemetryRecord, TopDistanceFlight, TopFlight,
+};
+use crate::solar;
 
 #[derive(Error, Debug)]
 pub enum DatabaseError {
@@ -32,8 +46,455 @@ pub enum DatabaseError {
 
     #[error("Flight not found: {0}")]
     FlightNotFound(i64),
+
+    #[error("Unsupported Parquet partitioning: {0} (expected none, flight or month)")]
+    UnsupportedPartition(String),
+
+    #[error("Not a Parquet logbook export: {0}")]
+    InvalidParquetExport(String),
+
+    #[error("Unknown custom field: {0}")]
+    UnknownCustomField(String),
+
+    #[error("Custom field name cannot be empty")]
+    EmptyCustomFieldName,
+
+    #[error("Battery not found: {0}")]
+    BatteryNotFound(String),
+
+    #[error("Aircraft not found: {0}")]
+    AircraftNotFound(String),
+
+    #[error("Maintenance item not found: {0}")]
+    MaintenanceItemNotFound(i64),
+
+    #[error("Invalid maintenance item: {0}")]
+    InvalidMaintenanceItem(&'static str),
+
+    #[error("Pilot not found: {0}")]
+    PilotNotFound(i64),
+
+    #[error("Pilot name cannot be empty")]
+    EmptyPilotName,
+
+    #[error(
+        "Database schema version {0} is newer than this app supports ({1}), please update the app"
+    )]
+    UnsupportedSchemaVersion(i64, i64),
+}
+
+/// Tables keyed by `flight_id`, exported next to `flights` as Parquet
+const FLIGHT_DATA_TABLES: &[&str] = &[
+    "telemetry",
+    "camera_events",
+    "home_events",
+    "flight_events",
+    "flight_findings",
+    "flight_tags",
+    "flight_custom_fields",
+];
+
+/// Flights added by merging another library or export
+#[derive(Debug, Clone, Default)]
+pub struct MergeOutcome {
+    /// Flights in the source
+    pub source_flights: usize,
+    /// Source id and new id of each imported flight
+    pub imported: Vec<(i64, i64)>,
+    pub telemetry_rows: usize,
+}
+
+/// How the per-flight tables are split into files in a Parquet export
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ParquetPartition {
+    /// One `<table>.parquet` file per table
+    #[default]
+    None,
+    /// Hive-style `<table>/flight_id=<id>/` directories
+    Flight,
+    /// Hive-style `<table>/month=<YYYY-MM>/` directories, by flight start
+    Month,
 }
 
+impl FromStr for ParquetPartition {
+    type Err = DatabaseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "none" => Ok(Self::None),
+            "flight" => Ok(Self::Flight),
+            "month" => Ok(Self::Month),
+            other => Err(DatabaseError::UnsupportedPartition(other.to_string())),
+        }
+    }
+}
+
+/// Distance of every telemetry point from the home point in effect at that
+/// time (haversine, meters). Home point updates mid-flight are matched with an
+/// ASOF join; points before the first recorded home use the flight's home.
+/// Only flights in the `scope` CTE are included.
+const HOME_DISTANCE_SQL: &str = r#"
+    SELECT
+        t.flight_id,
+        6371000 * 2 * ASIN(SQRT(
+            POWER(SIN(RADIANS(t.latitude - COALESCE(h.latitude, f.home_lat)) / 2), 2) +
+            COS(RADIANS(COALESCE(h.latitude, f.home_lat))) * COS(RADIANS(t.latitude)) *
+            POWER(SIN(RADIANS(t.longitude - COALESCE(h.longitude, f.home_lon)) / 2), 2)
+        )) AS distance_m
+    FROM telemetry t
+    JOIN scope f ON f.id = t.flight_id
+    ASOF LEFT JOIN (
+        SELECT flight_id, timestamp_ms, latitude, longitude
+        FROM home_events
+        WHERE event_type IN ('homeSet', 'homeUpdated')
+          AND latitude IS NOT NULL AND longitude IS NOT NULL
+    ) h ON h.flight_id = t.flight_id AND t.timestamp_ms >= h.timestamp_ms
+    WHERE t.latitude IS NOT NULL AND t.longitude IS NOT NULL
+      AND COALESCE(h.latitude, f.home_lat) IS NOT NULL
+      AND COALESCE(h.longitude, f.home_lon) IS NOT NULL
+"#;
+
+/// Max minus min cell voltage of a telemetry row, NULL unless at least two
+/// cells are reported
+const CELL_IMBALANCE_SQL: &str = "CASE WHEN len(battery_cell_voltages) >= 2 \
+    THEN list_max(battery_cell_voltages) - list_min(battery_cell_voltages) END";
+
+/// Per-flight battery figures from telemetry, for the flights matching the
+/// `{flights}` condition on `f` that have a battery serial
+///
+/// The internal resistance comes from voltage sag under load: within one
+/// percent step the state of charge barely moves, so the spread of voltage
+/// against current there is resistive. The slope is averaged over all steps
+/// with enough samples and current variation. Cell figures come from the
+/// reported cell voltages; logs without them get the cell count from the
+/// charged voltage (~4.2 V per cell) and an even split of the pack voltage.
+const BATTERY_STATS_SQL: &str = r#"
+    INSERT INTO battery_flight_stats
+    WITH samples AS (
+        SELECT
+            t.flight_id,
+            f.battery_serial,
+            t.battery_percent,
+            NULLIF(t.battery_voltage, 0) AS voltage,
+            ABS(t.battery_current) AS current_a,
+            t.battery_temp,
+            t.battery_cell_voltages AS cells
+        FROM telemetry t
+        JOIN flights f ON f.id = t.flight_id
+        WHERE ({flights}) AND f.battery_serial IS NOT NULL AND f.battery_serial <> ''
+    ),
+    sag AS (
+        SELECT flight_id, -REGR_SLOPE(voltage, current_a) AS resistance_ohm, COUNT(*) AS n
+        FROM samples
+        WHERE voltage IS NOT NULL AND current_a IS NOT NULL AND battery_percent IS NOT NULL
+        GROUP BY flight_id, battery_percent
+        HAVING COUNT(*) >= 5 AND VAR_POP(current_a) >= 0.25
+    ),
+    resistance AS (
+        SELECT flight_id, SUM(resistance_ohm * n) / SUM(n) AS resistance_ohm
+        FROM sag
+        GROUP BY flight_id
+    ),
+    per_flight AS (
+        SELECT
+            flight_id,
+            ANY_VALUE(battery_serial) AS battery_serial,
+            COALESCE(MAX(battery_percent) - MIN(battery_percent), 0)::DOUBLE AS discharged_percent,
+            MIN(voltage) AS min_voltage,
+            MAX(voltage) AS max_voltage,
+            MAX(battery_temp) AS max_temp,
+            MAX(current_a) AS max_current,
+            MAX(len(cells)) AS reported_cells,
+            MIN(list_min(cells)) AS min_reported_cell,
+            MAX(CASE WHEN len(cells) >= 2 THEN list_max(cells) - list_min(cells) END) AS max_cell_imbalance
+        FROM samples
+        GROUP BY flight_id
+    )
+    SELECT
+        p.flight_id,
+        p.battery_serial,
+        p.discharged_percent,
+        p.min_voltage,
+        p.max_voltage,
+        COALESCE(p.reported_cells, GREATEST(ROUND(p.max_voltage / 4.2), 1))::INTEGER AS cell_count,
+        COALESCE(p.min_reported_cell, p.min_voltage / GREATEST(ROUND(p.max_voltage / 4.2), 1)) AS min_cell_voltage,
+        p.max_temp,
+        p.max_current,
+        CASE WHEN r.resistance_ohm > 0 THEN r.resistance_ohm * 1000 END AS internal_resistance_mohm,
+        p.max_cell_imbalance
+    FROM per_flight p
+    LEFT JOIN resistance r ON r.flight_id = p.flight_id
+"#;
+
+/// A numbered schema change
+///
+/// Migrations run in order, each exactly once and inside its own
+/// transaction; the applied version is recorded in `schema_version`.
+/// Never edit a released migration, append a new one instead.
+struct Migration {
+    version: i64,
+    description: &'static str,
+    apply: fn(&Connection) -> Result<(), DatabaseError>,
+}
+
+const MIGRATIONS: &[Migration] = &[
+    Migration {
+        version: 1,
+        description: "Baseline schema",
+        apply: Database::migrate_baseline,
+    },
+    Migration {
+        version: 2,
+        description: "Flight id sequence",
+        apply: Database::migrate_flight_id_sequence,
+    },
+    Migration {
+        version: 3,
+        description: "Flight tags and custom fields",
+        apply: Database::migrate_tags_and_custom_fields,
+    },
+    Migration {
+        version: 4,
+        description: "Battery registry and per-flight battery stats",
+        apply: Database::migrate_battery_registry,
+    },
+    Migration {
+        version: 5,
+        description: "Battery cell voltages, capacity and voltage warnings",
+        apply: Database::migrate_battery_cells,
+    },
+    Migration {
+        version: 6,
+        description: "Aircraft registry, maintenance items and flight firmware",
+        apply: Database::migrate_aircraft_registry,
+    },
+    Migration {
+        version: 7,
+        description: "Pilots and pilot assignment of flights",
+        apply: Database::migrate_pilots,
+    },
+    Migration {
+        version: 8,
+        description: "Day, civil twilight or night classification of flights",
+        apply: Database::migrate_daylight,
+    },
+];
+
+/// Schema version created by this build; newer databases are refused
+pub const SCHEMA_VERSION: i64 = MIGRATIONS.len() as i64;
+
+/// Tables, indexes and pre-versioning columns of schema version 1
+const BASELINE_SCHEMA_SQL: &str = r#"
+    -- ============================================================
+    -- FLIGHTS TABLE: Stores metadata for each imported flight log
+    -- ============================================================
+    CREATE TABLE IF NOT EXISTS flights (
+        id              BIGINT PRIMARY KEY,
+        file_name       VARCHAR NOT NULL,
+        display_name    VARCHAR NOT NULL,
+        file_hash       VARCHAR UNIQUE,          -- SHA256 to prevent duplicates
+        drone_model     VARCHAR,
+        drone_serial    VARCHAR,
+        aircraft_name   VARCHAR,
+        battery_serial  VARCHAR,
+        start_time      TIMESTAMP WITH TIME ZONE,
+        end_time        TIMESTAMP WITH TIME ZONE,
+        duration_secs   DOUBLE,
+        total_distance  DOUBLE,                  -- Total distance in meters
+        max_altitude    DOUBLE,                  -- Max altitude in meters
+        max_speed       DOUBLE,                  -- Max speed in m/s
+        home_lat        DOUBLE,
+        home_lon        DOUBLE,
+        point_count     INTEGER,                 -- Number of telemetry points
+        imported_at     TIMESTAMP WITH TIME ZONE DEFAULT CURRENT_TIMESTAMP,
+        notes           VARCHAR,
+        archive_path    VARCHAR,                 -- Copy of the original log in raw_logs
+        source_path     VARCHAR,                 -- Path the log was imported from
+        photo_count     INTEGER,                 -- Photos taken during the flight
+        video_secs      DOUBLE                   -- Total video recording time
+    );
+
+    -- Columns added before schema versioning; DuckDB cannot add columns
+    -- to an indexed table, so the index is (re)created afterwards
+    DROP INDEX IF EXISTS idx_flights_start_time;
+    ALTER TABLE flights ADD COLUMN IF NOT EXISTS display_name VARCHAR;
+    ALTER TABLE flights ADD COLUMN IF NOT EXISTS aircraft_name VARCHAR;
+    ALTER TABLE flights ADD COLUMN IF NOT EXISTS battery_serial VARCHAR;
+    ALTER TABLE flights ADD COLUMN IF NOT EXISTS archive_path VARCHAR;
+    ALTER TABLE flights ADD COLUMN IF NOT EXISTS source_path VARCHAR;
+    ALTER TABLE flights ADD COLUMN IF NOT EXISTS photo_count INTEGER;
+    ALTER TABLE flights ADD COLUMN IF NOT EXISTS video_secs DOUBLE;
+
+    -- Index for sorting by flight date
+    CREATE INDEX IF NOT EXISTS idx_flights_start_time 
+        ON flights(start_time DESC);
+
+    -- ============================================================
+    -- TELEMETRY TABLE: Time-series data for each flight
+    -- Optimized for range queries on timestamp
+    -- ============================================================
+    CREATE TABLE IF NOT EXISTS telemetry (
+        flight_id       BIGINT NOT NULL,
+        timestamp_ms    BIGINT NOT NULL,         -- Milliseconds since flight start
+        
+        -- Position
+        latitude        DOUBLE,
+        longitude       DOUBLE,
+        altitude        DOUBLE,                  -- Relative altitude in meters
+        height          DOUBLE,                  -- Height above takeoff in meters
+        vps_height      DOUBLE,                  -- VPS height in meters
+        altitude_abs    DOUBLE,                  -- Absolute altitude (MSL)
+        
+        -- Velocity
+        speed           DOUBLE,                  -- Ground speed in m/s
+        velocity_x      DOUBLE,                  -- North velocity
+        velocity_y      DOUBLE,                  -- East velocity  
+        velocity_z      DOUBLE,                  -- Down velocity
+        
+        -- Orientation (Euler angles in degrees)
+        pitch           DOUBLE,
+        roll            DOUBLE,
+        yaw             DOUBLE,
+        
+        -- Gimbal
+        gimbal_pitch    DOUBLE,
+        gimbal_roll     DOUBLE,
+        gimbal_yaw      DOUBLE,
+        
+        -- Power
+        battery_percent INTEGER,
+        battery_voltage DOUBLE,
+        battery_current DOUBLE,
+        battery_temp    DOUBLE,
+        
+        -- Flight status
+        flight_mode     VARCHAR,
+        gps_signal      INTEGER,
+        satellites      INTEGER,
+        
+        -- RC
+        rc_signal       INTEGER,
+        rc_uplink       INTEGER,
+        rc_downlink     INTEGER,
+        
+        -- Composite primary key for efficient range queries
+        PRIMARY KEY (flight_id, timestamp_ms)
+    );
+
+    -- Index for time-range queries within a flight
+    CREATE INDEX IF NOT EXISTS idx_telemetry_flight_time 
+        ON telemetry(flight_id, timestamp_ms);
+
+    -- ============================================================
+    -- CAMERA EVENTS TABLE: Photos taken and video start/stop
+    -- ============================================================
+    CREATE TABLE IF NOT EXISTS camera_events (
+        flight_id       BIGINT NOT NULL,
+        timestamp_ms    BIGINT NOT NULL,         -- Milliseconds since flight start
+        event_type      VARCHAR NOT NULL,        -- photo, videoStart, videoStop
+        latitude        DOUBLE,
+        longitude       DOUBLE,
+        altitude        DOUBLE                   -- Height above takeoff in meters
+    );
+
+    CREATE INDEX IF NOT EXISTS idx_camera_events_flight
+        ON camera_events(flight_id, timestamp_ms);
+
+    -- ============================================================
+    -- HOME EVENTS TABLE: Home point records and RTH phases
+    -- ============================================================
+    CREATE TABLE IF NOT EXISTS home_events (
+        flight_id       BIGINT NOT NULL,
+        timestamp_ms    BIGINT NOT NULL,
+        event_type      VARCHAR NOT NULL,        -- homeSet, homeUpdated, rthStart, rthEnd
+        latitude        DOUBLE,
+        longitude       DOUBLE,
+        altitude        DOUBLE,
+        detail          VARCHAR                  -- RTH trigger
+    );
+
+    CREATE INDEX IF NOT EXISTS idx_home_events_flight
+        ON home_events(flight_id, timestamp_ms);
+
+    -- ============================================================
+    -- FLIGHT EVENTS TABLE: App tips and warnings shown to the pilot
+    -- ============================================================
+    CREATE TABLE IF NOT EXISTS flight_events (
+        flight_id       BIGINT NOT NULL,
+        timestamp_ms    BIGINT NOT NULL,
+        severity        VARCHAR NOT NULL,        -- info, warning
+        category        VARCHAR NOT NULL,        -- battery, gps, signal, ...
+        message         VARCHAR NOT NULL
+    );
+
+    CREATE INDEX IF NOT EXISTS idx_flight_events_flight
+        ON flight_events(flight_id, timestamp_ms);
+
+    -- ============================================================
+    -- FINDINGS TABLE: Anomalies flagged by the analysis pass
+    -- ============================================================
+    CREATE TABLE IF NOT EXISTS flight_findings (
+        flight_id       BIGINT NOT NULL,
+        timestamp_ms    BIGINT NOT NULL,
+        duration_ms     BIGINT NOT NULL,
+        kind            VARCHAR NOT NULL,        -- gpsJump, altitudeLoss, ...
+        severity        VARCHAR NOT NULL,        -- warning, critical
+        message         VARCHAR NOT NULL,
+        value           DOUBLE
+    );
+
+    CREATE INDEX IF NOT EXISTS idx_flight_findings_flight
+        ON flight_findings(flight_id, timestamp_ms);
+
+    -- ============================================================
+    -- KEYCHAIN TABLE: Store cached decryption keys for V13+ logs
+    -- serial_number holds the SHA256 of the log's keychain request,
+    -- encryption_key the fetched keychains as JSON
+    -- ============================================================
+    CREATE TABLE IF NOT EXISTS keychains (
+        serial_number   VARCHAR PRIMARY KEY,
+        encryption_key  VARCHAR NOT NULL,
+        fetched_at      TIMESTAMP WITH TIME ZONE DEFAULT CURRENT_TIMESTAMP
+    );
+"#;
+
+/// Telemetry columns of schema version 1, in order
+///
+/// Later migrations only append columns, rebuilding the table as needed.
+const BASELINE_TELEMETRY_COLUMNS: &[&str] = &[
+    "flight_id",
+    "timestamp_ms",
+    "latitude",
+    "longitude",
+    "altitude",
+    "height",
+    "vps_height",
+    "altitude_abs",
+    "speed",
+    "velocity_x",
+    "velocity_y",
+    "velocity_z",
+    "pitch",
+    "roll",
+    "yaw",
+    "gimbal_pitch",
+    "gimbal_roll",
+    "gimbal_yaw",
+    "battery_percent",
+    "battery_voltage",
+    "battery_current",
+    "battery_temp",
+    "flight_mode",
+    "gps_signal",
+    "satellites",
+    "rc_signal",
+    "rc_uplink",
+    "rc_downlink",
+];
+
+/// Flight id, file hash and archive path of a stored flight
+pub type ArchiveRef = (i64, Option<String>, Option<String>);
+
 /// Thread-safe database manager
 pub struct Database {
     conn: Mutex<Connection>,
@@ -44,7 +505,7 @@ impl Database {
     /// Initialize the database in the app data directory.
     ///
     /// Creates the following directory structure:
-    /// ```
+    /// ```text
     /// {app_data_dir}/
     /// ├── flights.db       # DuckDB database file
     /// ├── raw_logs/        # Original log files
@@ -95,7 +556,10 @@ impl Database {
                 match Connection::open(db_path) {
                     Ok(conn) => Ok(conn),
                     Err(second_err) => {
-                        log::warn!("WAL recovery failed: {}. Backing up DB and recreating...", second_err);
+                        log::warn!(
+                            "WAL recovery failed: {}. Backing up DB and recreating...",
+                            second_err
+                        );
 
                         let backup_path = Self::backup_db(db_path)?;
                         log::warn!("Database backed up to {:?}", backup_path);
@@ -138,199 +602,400 @@ impl Database {
         Ok(())
     }
 
-    /// Initialize the database schema with optimized tables
+    /// Bring the schema up to [`SCHEMA_VERSION`]
+    ///
+    /// Pending migrations run in order, each in its own transaction. An
+    /// existing database is copied to `flights.db.v<version>.bak.<timestamp>`
+    /// before the first of them. Databases from a newer app are refused.
     fn init_schema(&self) -> Result<(), DatabaseError> {
-        let conn = self.conn.lock().unwrap();
+        let mut conn = self.conn.lock().unwrap();
 
         conn.execute_batch(
             r#"
-            -- ============================================================
-            -- FLIGHTS TABLE: Stores metadata for each imported flight log
-            -- ============================================================
-            CREATE TABLE IF NOT EXISTS flights (
-                id              BIGINT PRIMARY KEY,
-                file_name       VARCHAR NOT NULL,
-                display_name    VARCHAR NOT NULL,
-                file_hash       VARCHAR UNIQUE,          -- SHA256 to prevent duplicates
-                drone_model     VARCHAR,
-                drone_serial    VARCHAR,
-                aircraft_name   VARCHAR,
-                battery_serial  VARCHAR,
-                start_time      TIMESTAMP WITH TIME ZONE,
-                end_time        TIMESTAMP WITH TIME ZONE,
-                duration_secs   DOUBLE,
-                total_distance  DOUBLE,                  -- Total distance in meters
-                max_altitude    DOUBLE,                  -- Max altitude in meters
-                max_speed       DOUBLE,                  -- Max speed in m/s
-                home_lat        DOUBLE,
-                home_lon        DOUBLE,
-                point_count     INTEGER,                 -- Number of telemetry points
-                imported_at     TIMESTAMP WITH TIME ZONE DEFAULT CURRENT_TIMESTAMP,
-                notes           VARCHAR
+            CREATE TABLE IF NOT EXISTS schema_version (
+                version         INTEGER PRIMARY KEY,
+                description     VARCHAR NOT NULL,
+                applied_at      TIMESTAMP WITH TIME ZONE DEFAULT CURRENT_TIMESTAMP
             );
+            "#,
+        )?;
 
-            -- Index for sorting by flight date
-            CREATE INDEX IF NOT EXISTS idx_flights_start_time 
-                ON flights(start_time DESC);
+        let current = Self::schema_version_of(&conn, None)?;
+        if current > SCHEMA_VERSION {
+            return Err(DatabaseError::UnsupportedSchemaVersion(
+                current,
+                SCHEMA_VERSION,
+            ));
+        }
 
-            -- Schema migrations for existing databases
-            ALTER TABLE flights ADD COLUMN IF NOT EXISTS display_name VARCHAR;
-            ALTER TABLE flights ADD COLUMN IF NOT EXISTS aircraft_name VARCHAR;
-            ALTER TABLE flights ADD COLUMN IF NOT EXISTS battery_serial VARCHAR;
+        let pending: Vec<&Migration> = MIGRATIONS.iter().filter(|m| m.version > current).collect();
+        if pending.is_empty() {
+            return Ok(());
+        }
 
-            -- ============================================================
-            -- TELEMETRY TABLE: Time-series data for each flight
-            -- Optimized for range queries on timestamp
-            -- ============================================================
-            CREATE TABLE IF NOT EXISTS telemetry (
-                flight_id       BIGINT NOT NULL,
-                timestamp_ms    BIGINT NOT NULL,         -- Milliseconds since flight start
-                
-                -- Position
-                latitude        DOUBLE,
-                longitude       DOUBLE,
-                altitude        DOUBLE,                  -- Relative altitude in meters
-                height          DOUBLE,                  -- Height above takeoff in meters
-                vps_height      DOUBLE,                  -- VPS height in meters
-                altitude_abs    DOUBLE,                  -- Absolute altitude (MSL)
-                
-                -- Velocity
-                speed           DOUBLE,                  -- Ground speed in m/s
-                velocity_x      DOUBLE,                  -- North velocity
-                velocity_y      DOUBLE,                  -- East velocity  
-                velocity_z      DOUBLE,                  -- Down velocity
-                
-                -- Orientation (Euler angles in degrees)
-                pitch           DOUBLE,
-                roll            DOUBLE,
-                yaw             DOUBLE,
-                
-                -- Gimbal
-                gimbal_pitch    DOUBLE,
-                gimbal_roll     DOUBLE,
-                gimbal_yaw      DOUBLE,
-                
-                -- Power
-                battery_percent INTEGER,
-                battery_voltage DOUBLE,
-                battery_current DOUBLE,
-                battery_temp    DOUBLE,
-                
-                -- Flight status
-                flight_mode     VARCHAR,
-                gps_signal      INTEGER,
-                satellites      INTEGER,
-                
-                -- RC
-                rc_signal       INTEGER,
-                rc_uplink       INTEGER,
-                rc_downlink     INTEGER,
-                
-                -- Composite primary key for efficient range queries
-                PRIMARY KEY (flight_id, timestamp_ms)
+        // Fresh databases have nothing to lose; anything else, including
+        // libraries from before schema versioning, is copied first
+        let existing_tables: i64 = conn.query_row(
+            r#"
+            SELECT COUNT(*) FROM duckdb_tables()
+            WHERE database_name = current_database() AND table_name <> 'schema_version'
+            "#,
+            [],
+            |row| row.get(0),
+        )?;
+        if existing_tables > 0 {
+            let timestamp = chrono::Utc::now().format("%Y%m%d_%H%M%S");
+            let backup_path = self
+                .data_dir
+                .join(format!("flights.db.v{}.bak.{}", current, timestamp));
+            Self::copy_checkpointed(&conn, &self.data_dir.join("flights.db"), &backup_path)?;
+            log::info!(
+                "Backed up schema version {} database to {:?}",
+                current,
+                backup_path
             );
+        }
 
-            -- Index for time-range queries within a flight
-            CREATE INDEX IF NOT EXISTS idx_telemetry_flight_time 
-                ON telemetry(flight_id, timestamp_ms);
-
-            -- Schema migrations for existing databases
-            ALTER TABLE telemetry ADD COLUMN IF NOT EXISTS height DOUBLE;
-            ALTER TABLE telemetry ADD COLUMN IF NOT EXISTS vps_height DOUBLE;
-            ALTER TABLE telemetry ADD COLUMN IF NOT EXISTS rc_uplink INTEGER;
-            ALTER TABLE telemetry ADD COLUMN IF NOT EXISTS rc_downlink INTEGER;
-
-            -- ============================================================
-            -- KEYCHAIN TABLE: Store cached decryption keys for V13+ logs
-            -- ============================================================
-            CREATE TABLE IF NOT EXISTS keychains (
-                serial_number   VARCHAR PRIMARY KEY,
-                encryption_key  VARCHAR NOT NULL,
-                fetched_at      TIMESTAMP WITH TIME ZONE DEFAULT CURRENT_TIMESTAMP
+        for migration in pending {
+            log::info!(
+                "Migrating database to schema version {}: {}",
+                migration.version,
+                migration.description
             );
+
+            let tx = conn.transaction()?;
+            (migration.apply)(&tx)?;
+            tx.execute(
+                "INSERT INTO schema_version (version, description) VALUES (?, ?)",
+                params![migration.version, migration.description],
+            )?;
+            tx.commit()?;
+        }
+
+        log::info!("Database schema at version {}", SCHEMA_VERSION);
+        Ok(())
+    }
+
+    /// Highest applied migration, 0 if none
+    ///
+    /// Reads the connection's own database, or an attached one by name.
+    fn schema_version_of(conn: &Connection, attached: Option<&str>) -> Result<i64, DatabaseError> {
+        let versioned: bool = conn.query_row(
+            r#"
+            SELECT COUNT(*) > 0 FROM duckdb_tables()
+            WHERE database_name = COALESCE(?, current_database()) AND table_name = 'schema_version'
             "#,
+            params![attached],
+            |row| row.get(0),
         )?;
+        if !versioned {
+            return Ok(0);
+        }
 
-        Self::ensure_telemetry_column_order(&conn)?;
+        let table = match attached {
+            Some(database) => format!("{}.schema_version", database),
+            None => "schema_version".to_string(),
+        };
+        let version: i64 = conn.query_row(
+            &format!("SELECT COALESCE(MAX(version), 0) FROM {}", table),
+            [],
+            |row| row.get(0),
+        )?;
+        Ok(version)
+    }
 
-        log::info!("Database schema initialized successfully");
+    /// Checkpoint the WAL into the database file, then copy the file
+    fn copy_checkpointed(
+        conn: &Connection,
+        db_path: &Path,
+        destination: &Path,
+    ) -> Result<(), DatabaseError> {
+        conn.execute_batch("CHECKPOINT")?;
+        fs::copy(db_path, destination)?;
         Ok(())
     }
 
-    fn ensure_telemetry_column_order(conn: &Connection) -> Result<(), DatabaseError> {
-        let expected = vec![
-            "flight_id",
-            "timestamp_ms",
-            "latitude",
-            "longitude",
-            "altitude",
-            "height",
-            "vps_height",
-            "altitude_abs",
-            "speed",
-            "velocity_x",
-            "velocity_y",
-            "velocity_z",
-            "pitch",
-            "roll",
-            "yaw",
-            "gimbal_pitch",
-            "gimbal_roll",
-            "gimbal_yaw",
-            "battery_percent",
-            "battery_voltage",
-            "battery_current",
-            "battery_temp",
-            "flight_mode",
-            "gps_signal",
-            "satellites",
-            "rc_signal",
-            "rc_uplink",
-            "rc_downlink",
-        ];
-
-        let mut stmt = conn.prepare("PRAGMA table_info('telemetry')")?;
-        let actual: Vec<String> = stmt
-            .query_map([], |row| row.get::<_, String>(1))?
+    /// Migration 1: create the schema, or adopt a database from before
+    /// schema versioning
+    ///
+    /// Older telemetry tables may lack columns or have them in another
+    /// order (the Appender writes by position). Those are rebuilt once with
+    /// the current definition, copying rows over by column name.
+    fn migrate_baseline(conn: &Connection) -> Result<(), DatabaseError> {
+        let mut stmt = conn.prepare(
+            r#"
+            SELECT column_name FROM duckdb_columns()
+            WHERE database_name = current_database() AND table_name = 'telemetry'
+            ORDER BY column_index
+            "#,
+        )?;
+        let legacy_columns: Vec<String> = stmt
+            .query_map([], |row| row.get::<_, String>(0))?
             .collect::<Result<Vec<_>, _>>()?;
 
-        if actual.iter().map(String::as_str).eq(expected.iter().copied()) {
-            return Ok(());
+        let rebuild_telemetry = !legacy_columns.is_empty()
+            && !legacy_columns
+                .iter()
+                .map(String::as_str)
+                .eq(BASELINE_TELEMETRY_COLUMNS.iter().copied());
+        if rebuild_telemetry {
+            log::warn!("Telemetry table predates the current layout. Rebuilding it.");
+            conn.execute_batch(
+                r#"
+                DROP INDEX IF EXISTS idx_telemetry_flight_time;
+                ALTER TABLE telemetry RENAME TO telemetry_legacy;
+                "#,
+            )?;
         }
 
-        log::warn!("Telemetry column order mismatch detected. Rebuilding table.");
+        conn.execute_batch(BASELINE_SCHEMA_SQL)?;
 
-        let existing: std::collections::HashSet<&str> =
-            actual.iter().map(|s| s.as_str()).collect();
+        if rebuild_telemetry {
+            let columns = BASELINE_TELEMETRY_COLUMNS
+                .iter()
+                .filter(|col| legacy_columns.iter().any(|c| c == *col))
+                .copied()
+                .collect::<Vec<_>>()
+                .join(", ");
 
-        let select_list = expected
-            .iter()
-            .map(|col| {
-                if existing.contains(col) {
-                    col.to_string()
-                } else {
-                    format!("NULL AS {}", col)
-                }
-            })
-            .collect::<Vec<_>>()
-            .join(", ");
+            // Earlier rebuilds dropped the primary key, so duplicates may exist
+            conn.execute_batch(&format!(
+                r#"
+                INSERT OR IGNORE INTO telemetry ({0})
+                SELECT {0} FROM telemetry_legacy;
+                DROP TABLE telemetry_legacy;
+                "#,
+                columns
+            ))?;
+        }
+
+        Ok(())
+    }
+
+    /// Migration 2: allocate flight ids from `flight_id_seq`
+    ///
+    /// Ids used to be derived from the import time and could collide. Existing
+    /// flights keep their ids (exports and links refer to them); the sequence
+    /// starts after the largest one.
+    fn migrate_flight_id_sequence(conn: &Connection) -> Result<(), DatabaseError> {
+        let max_id: i64 =
+            conn.query_row("SELECT COALESCE(MAX(id), 0) FROM flights", [], |row| {
+                row.get(0)
+            })?;
+        conn.execute_batch(&format!(
+            "CREATE SEQUENCE flight_id_seq START WITH {}",
+            max_id + 1
+        ))?;
+        Ok(())
+    }
+
+    /// Migration 3: tags and user-defined custom fields per flight
+    fn migrate_tags_and_custom_fields(conn: &Connection) -> Result<(), DatabaseError> {
+        conn.execute_batch(
+            r#"
+            -- Free-form tags, many per flight
+            CREATE TABLE flight_tags (
+                flight_id       BIGINT NOT NULL,
+                tag             VARCHAR NOT NULL,
+                PRIMARY KEY (flight_id, tag)
+            );
+
+            CREATE INDEX idx_flight_tags_tag ON flight_tags(tag);
+
+            -- Field definitions (client, job number, ...), shown for every flight
+            CREATE TABLE custom_fields (
+                name            VARCHAR PRIMARY KEY,
+                created_at      TIMESTAMP WITH TIME ZONE DEFAULT CURRENT_TIMESTAMP
+            );
+
+            -- Field values set on a flight
+            CREATE TABLE flight_custom_fields (
+                flight_id       BIGINT NOT NULL,
+                field           VARCHAR NOT NULL,
+                value           VARCHAR NOT NULL,
+                PRIMARY KEY (flight_id, field)
+            );
+            "#,
+        )?;
+        Ok(())
+    }
+
+    /// Migration 4: battery registry and per-flight battery stats
+    ///
+    /// Stats of existing flights are computed by migration 5, once the
+    /// telemetry has the cell columns the stats are derived from.
+    fn migrate_battery_registry(conn: &Connection) -> Result<(), DatabaseError> {
+        conn.execute_batch(
+            r#"
+            -- Names, notes and retirement of packs, keyed by battery serial
+            CREATE TABLE batteries (
+                serial          VARCHAR PRIMARY KEY,
+                name            VARCHAR,
+                notes           VARCHAR,
+                retired_at      TIMESTAMP WITH TIME ZONE,
+                created_at      TIMESTAMP WITH TIME ZONE DEFAULT CURRENT_TIMESTAMP
+            );
+
+            -- Derived from telemetry, rebuilt whenever a flight's telemetry changes
+            CREATE TABLE battery_flight_stats (
+                flight_id                   BIGINT NOT NULL,
+                battery_serial              VARCHAR NOT NULL,
+                discharged_percent          DOUBLE NOT NULL,
+                min_voltage                 DOUBLE,
+                max_voltage                 DOUBLE,
+                cell_count                  INTEGER,
+                min_cell_voltage            DOUBLE,
+                max_temp                    DOUBLE,
+                max_current                 DOUBLE,
+                internal_resistance_mohm    DOUBLE
+            );
+            "#,
+        )?;
+        Ok(())
+    }
 
+    /// Migration 5: per-cell voltages, capacities and voltage warnings in
+    /// telemetry, cell imbalance in the battery stats
+    ///
+    /// DuckDB cannot add columns to the indexed telemetry table, so it is
+    /// rebuilt with the new columns at the end and the rows copied over.
+    /// Battery stats of all flights are then computed from scratch.
+    fn migrate_battery_cells(conn: &Connection) -> Result<(), DatabaseError> {
         conn.execute_batch(&format!(
             r#"
-            BEGIN TRANSACTION;
-            CREATE TABLE telemetry_new AS SELECT {} FROM telemetry;
-            DROP TABLE telemetry;
-            ALTER TABLE telemetry_new RENAME TO telemetry;
-            CREATE INDEX IF NOT EXISTS idx_telemetry_flight_time
+            DROP INDEX IF EXISTS idx_telemetry_flight_time;
+            ALTER TABLE telemetry RENAME TO telemetry_v4;
+
+            CREATE TABLE telemetry (
+                flight_id                   BIGINT NOT NULL,
+                timestamp_ms                BIGINT NOT NULL,
+                latitude                    DOUBLE,
+                longitude                   DOUBLE,
+                altitude                    DOUBLE,
+                height                      DOUBLE,
+                vps_height                  DOUBLE,
+                altitude_abs                DOUBLE,
+                speed                       DOUBLE,
+                velocity_x                  DOUBLE,
+                velocity_y                  DOUBLE,
+                velocity_z                  DOUBLE,
+                pitch                       DOUBLE,
+                roll                        DOUBLE,
+                yaw                         DOUBLE,
+                gimbal_pitch                DOUBLE,
+                gimbal_roll                 DOUBLE,
+                gimbal_yaw                  DOUBLE,
+                battery_percent             INTEGER,
+                battery_voltage             DOUBLE,
+                battery_current             DOUBLE,
+                battery_temp                DOUBLE,
+                flight_mode                 VARCHAR,
+                gps_signal                  INTEGER,
+                satellites                  INTEGER,
+                rc_signal                   INTEGER,
+                rc_uplink                   INTEGER,
+                rc_downlink                 INTEGER,
+                battery_cell_voltages       DOUBLE[],    -- Volts, in pack order
+                battery_full_capacity       INTEGER,     -- mAh
+                battery_remaining_capacity  INTEGER,     -- mAh
+                battery_voltage_warning     INTEGER,     -- 0 none, 1 low, 2 critically low
+                battery_cells_estimated     BOOLEAN,
+                PRIMARY KEY (flight_id, timestamp_ms)
+            );
+
+            CREATE INDEX idx_telemetry_flight_time
                 ON telemetry(flight_id, timestamp_ms);
-            COMMIT;
+
+            INSERT INTO telemetry ({0})
+            SELECT {0} FROM telemetry_v4;
+            DROP TABLE telemetry_v4;
+
+            ALTER TABLE battery_flight_stats ADD COLUMN max_cell_imbalance DOUBLE;
             "#,
-            select_list
+            BASELINE_TELEMETRY_COLUMNS.join(", ")
         ))?;
+        Self::refresh_battery_stats(conn, "TRUE")
+    }
+
+    /// Migration 6: aircraft registry, maintenance items and the firmware
+    /// version of each flight
+    ///
+    /// Firmware is only known for flights imported or re-processed from now on.
+    fn migrate_aircraft_registry(conn: &Connection) -> Result<(), DatabaseError> {
+        conn.execute_batch(
+            r#"
+            -- DuckDB cannot add columns to an indexed table
+            DROP INDEX IF EXISTS idx_flights_start_time;
+            ALTER TABLE flights ADD COLUMN firmware_version VARCHAR;
+            CREATE INDEX idx_flights_start_time ON flights(start_time DESC);
+
+            -- Names and notes of aircraft, keyed by drone serial
+            CREATE TABLE aircraft (
+                serial          VARCHAR PRIMARY KEY,
+                name            VARCHAR,
+                notes           VARCHAR,
+                created_at      TIMESTAMP WITH TIME ZONE DEFAULT CURRENT_TIMESTAMP
+            );
+
+            -- Recurring maintenance, due every interval_hours flight hours
+            -- and/or interval_flights flights after last_done_at
+            CREATE SEQUENCE maintenance_item_id_seq;
+            CREATE TABLE maintenance_items (
+                id              BIGINT PRIMARY KEY DEFAULT nextval('maintenance_item_id_seq'),
+                drone_serial    VARCHAR NOT NULL,
+                name            VARCHAR NOT NULL,
+                interval_hours  DOUBLE,
+                interval_flights INTEGER,
+                last_done_at    TIMESTAMP WITH TIME ZONE,
+                notes           VARCHAR,
+                created_at      TIMESTAMP WITH TIME ZONE DEFAULT CURRENT_TIMESTAMP
+            );
+            "#,
+        )?;
+        Ok(())
+    }
+
+    /// Migration 7: pilots, the pilot of each flight, and aircraft whose
+    /// flights are assigned to a pilot automatically
+    fn migrate_pilots(conn: &Connection) -> Result<(), DatabaseError> {
+        conn.execute_batch(
+            r#"
+            DROP INDEX IF EXISTS idx_flights_start_time;
+            ALTER TABLE flights ADD COLUMN pilot_id BIGINT;
+            CREATE INDEX idx_flights_start_time ON flights(start_time DESC);
+
+            CREATE SEQUENCE pilot_id_seq;
+            CREATE TABLE pilots (
+                id              BIGINT PRIMARY KEY DEFAULT nextval('pilot_id_seq'),
+                name            VARCHAR NOT NULL,
+                license_number  VARCHAR,                 -- Remote pilot certificate
+                notes           VARCHAR,
+                created_at      TIMESTAMP WITH TIME ZONE DEFAULT CURRENT_TIMESTAMP
+            );
 
+            -- Flights of these aircraft get the pilot when imported
+            CREATE TABLE pilot_aircraft (
+                drone_serial    VARCHAR PRIMARY KEY,
+                pilot_id        BIGINT NOT NULL
+            );
+            "#,
+        )?;
         Ok(())
     }
 
+    /// Migration 8: light conditions of each flight, worked out for the
+    /// flights already in the library
+    fn migrate_daylight(conn: &Connection) -> Result<(), DatabaseError> {
+        conn.execute_batch(
+            r#"
+            DROP INDEX IF EXISTS idx_flights_start_time;
+            ALTER TABLE flights ADD COLUMN daylight VARCHAR;    -- day, civilTwilight or night
+            CREATE INDEX idx_flights_start_time ON flights(start_time DESC);
+            "#,
+        )?;
+        Self::refresh_daylight(conn, "TRUE")
+    }
+
     /// Get the path to the raw_logs directory
     pub fn raw_logs_dir(&self) -> PathBuf {
         self.data_dir.join("raw_logs")
@@ -341,32 +1006,53 @@ impl Database {
         self.data_dir.join("keychains")
     }
 
-    /// Generate a new unique flight ID using timestamp + random
-    pub fn generate_flight_id(&self) -> i64 {
-        use std::time::{SystemTime, UNIX_EPOCH};
-        let timestamp = SystemTime::now()
-            .duration_since(UNIX_EPOCH)
-            .unwrap()
-            .as_millis() as i64;
-        // Use lower bits for uniqueness
-        timestamp % 1_000_000_000_000
-    }
-
-    /// Insert flight metadata and return the flight ID
+    /// Insert flight metadata and return the allocated flight ID
     pub fn insert_flight(&self, flight: &FlightMetadata) -> Result<i64, DatabaseError> {
         let conn = self.conn.lock().unwrap();
+        Self::insert_flight_row(&conn, flight)
+    }
 
-        conn.execute(
+    /// Insert a flight with all of its telemetry and events in a single transaction.
+    ///
+    /// Either both the flight row and its telemetry are stored, or nothing is:
+    /// a failure (or crash) part-way never leaves a flight without points.
+    pub fn insert_flight_with_telemetry(
+        &self,
+        flight: &FlightMetadata,
+        points: &[TelemetryPoint],
+        events: &FlightEvents,
+    ) -> Result<(i64, usize), DatabaseError> {
+        let mut conn = self.conn.lock().unwrap();
+        let tx = conn.transaction()?;
+
+        let flight_id = Self::insert_flight_row(&tx, flight)?;
+        let inserted = Self::append_telemetry(&tx, flight_id, points)?;
+        Self::insert_events(&tx, flight_id, events)?;
+        Self::refresh_battery_stats(&tx, &format!("f.id = {}", flight_id))?;
+
+        // Dropping the transaction without commit rolls everything back
+        tx.commit()?;
+
+        Ok((flight_id, inserted))
+    }
+
+    fn insert_flight_row(conn: &Connection, flight: &FlightMetadata) -> Result<i64, DatabaseError> {
+        let flight_id: i64 = conn.query_row(
             r#"
             INSERT INTO flights (
                 id, file_name, display_name, file_hash, drone_model, drone_serial,
                 aircraft_name, battery_serial,
                 start_time, end_time, duration_secs, total_distance,
-                max_altitude, max_speed, home_lat, home_lon, point_count
-            ) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
+                max_altitude, max_speed, home_lat, home_lon, point_count,
+                archive_path, source_path, photo_count, video_secs, firmware_version,
+                daylight, pilot_id
+            ) VALUES (
+                nextval('flight_id_seq'), ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?,
+                (SELECT pilot_id FROM pilot_aircraft WHERE drone_serial = ?)
+            )
+            RETURNING id
             "#,
             params![
-                flight.id,
                 flight.file_name,
                 flight.display_name,
                 flight.file_hash,
@@ -383,11 +1069,19 @@ impl Database {
                 flight.home_lat,
                 flight.home_lon,
                 flight.point_count,
+                flight.archive_path,
+                flight.source_path,
+                flight.photo_count,
+                flight.video_secs,
+                flight.firmware_version,
+                Self::metadata_daylight(flight).map(|d| d.as_str()),
+                flight.drone_serial,
             ],
+            |row| row.get(0),
         )?;
 
-        log::info!("Inserted flight with ID: {}", flight.id);
-        Ok(flight.id)
+        log::info!("Inserted flight with ID: {}", flight_id);
+        Ok(flight_id)
     }
 
     /// Bulk insert telemetry data using DuckDB's Appender for maximum performance
@@ -399,7 +1093,15 @@ impl Database {
         points: &[TelemetryPoint],
     ) -> Result<usize, DatabaseError> {
         let conn = self.conn.lock().unwrap();
+        Self::append_telemetry(&conn, flight_id, points)
+    }
 
+    /// Append telemetry rows on an already locked connection (or open transaction)
+    fn append_telemetry(
+        conn: &Connection,
+        flight_id: i64,
+        points: &[TelemetryPoint],
+    ) -> Result<usize, DatabaseError> {
         // Use DuckDB Appender for high-performance bulk inserts
         let mut appender = conn.appender("telemetry")?;
 
@@ -441,6 +1143,11 @@ impl Database {
                 point.rc_signal,
                 point.rc_uplink,
                 point.rc_downlink,
+                cell_voltages_literal(&point.battery_cell_voltages),
+                point.battery_full_capacity,
+                point.battery_remaining_capacity,
+                point.battery_voltage_warning,
+                point.battery_cells_estimated,
             ]) {
                 Ok(()) => inserted += 1,
                 Err(err) => {
@@ -468,76 +1175,591 @@ impl Database {
         Ok(inserted)
     }
 
-    /// Get all flights metadata (for the flight list sidebar)
-    pub fn get_all_flights(&self) -> Result<Vec<Flight>, DatabaseError> {
-        let conn = self.conn.lock().unwrap();
+    /// Insert the events extracted from a log for a flight
+    fn insert_events(
+        conn: &Connection,
+        flight_id: i64,
+        events: &FlightEvents,
+    ) -> Result<(), DatabaseError> {
+        let mut stmt = conn.prepare(
+            r#"
+            INSERT INTO camera_events (flight_id, timestamp_ms, event_type, latitude, longitude, altitude)
+            VALUES (?, ?, ?, ?, ?, ?)
+            "#,
+        )?;
+
+        for event in &events.camera {
+            stmt.execute(params![
+                flight_id,
+                event.timestamp_ms,
+                event.event_type,
+                event.latitude,
+                event.longitude,
+                event.altitude,
+            ])?;
+        }
 
         let mut stmt = conn.prepare(
             r#"
-            SELECT 
-                id, file_name, COALESCE(display_name, file_name) AS display_name,
-                drone_model, drone_serial, aircraft_name, battery_serial,
-                CAST(start_time AS VARCHAR) AS start_time,
-                duration_secs, total_distance,
-                max_altitude, max_speed, home_lat, home_lon, point_count
-            FROM flights
-            ORDER BY start_time DESC
+            INSERT INTO home_events (flight_id, timestamp_ms, event_type, latitude, longitude, altitude, detail)
+            VALUES (?, ?, ?, ?, ?, ?, ?)
             "#,
         )?;
 
-        let flights = stmt
-            .query_map([], |row| {
-                Ok(Flight {
-                    id: row.get(0)?,
-                    file_name: row.get(1)?,
-                    display_name: row.get(2)?,
-                    drone_model: row.get(3)?,
-                    drone_serial: row.get(4)?,
-                    aircraft_name: row.get(5)?,
-                    battery_serial: row.get(6)?,
-                    start_time: row.get(7)?,
-                    duration_secs: row.get(8)?,
-                    total_distance: row.get(9)?,
-                    max_altitude: row.get(10)?,
-                    max_speed: row.get(11)?,
-                    home_lat: row.get(12)?,
-                    home_lon: row.get(13)?,
-                    point_count: row.get(14)?,
-                })
-            })?
-            .collect::<Result<Vec<_>, _>>()?;
+        for event in &events.home {
+            stmt.execute(params![
+                flight_id,
+                event.timestamp_ms,
+                event.event_type,
+                event.latitude,
+                event.longitude,
+                event.altitude,
+                event.detail,
+            ])?;
+        }
 
-        Ok(flights)
-    }
+        let mut stmt = conn.prepare(
+            r#"
+            INSERT INTO flight_events (flight_id, timestamp_ms, severity, category, message)
+            VALUES (?, ?, ?, ?, ?)
+            "#,
+        )?;
 
-    /// Get flight telemetry with automatic downsampling for large datasets.
-    ///
-    /// Strategy:
-    /// - If points < 5000: return raw data
-    /// - If points >= 5000: group by 1-second intervals, averaging values
-    /// - This keeps the frontend responsive while preserving data trends
-    pub fn get_flight_telemetry(
-        &self,
-        flight_id: i64,
-        max_points: Option<usize>,
-    ) -> Result<Vec<TelemetryRecord>, DatabaseError> {
-        let conn = self.conn.lock().unwrap();
-        let max_points = max_points.unwrap_or(5000);
+        for event in &events.messages {
+            stmt.execute(params![
+                flight_id,
+                event.timestamp_ms,
+                event.severity,
+                event.category,
+                event.message,
+            ])?;
+        }
 
-        // First, get the point count for this flight
-        let point_count: i64 = conn.query_row(
-            "SELECT COUNT(*) FROM telemetry WHERE flight_id = ?",
-            params![flight_id],
-            |row| row.get(0),
+        let mut stmt = conn.prepare(
+            r#"
+            INSERT INTO flight_findings (flight_id, timestamp_ms, duration_ms, kind, severity, message, value)
+            VALUES (?, ?, ?, ?, ?, ?, ?)
+            "#,
         )?;
 
-        if point_count == 0 {
-            return Err(DatabaseError::FlightNotFound(flight_id));
+        for finding in &events.findings {
+            stmt.execute(params![
+                flight_id,
+                finding.timestamp_ms,
+                finding.duration_ms,
+                finding.kind,
+                finding.severity,
+                finding.message,
+                finding.value,
+            ])?;
         }
 
-        let records = if point_count as usize <= max_points {
-            // Return raw data - no downsampling needed
-            log::debug!(
+        Ok(())
+    }
+
+    /// Delete all events of a flight
+    fn delete_events(conn: &Connection, flight_id: i64) -> Result<(), DatabaseError> {
+        conn.execute(
+            "DELETE FROM camera_events WHERE flight_id = ?",
+            params![flight_id],
+        )?;
+        conn.execute(
+            "DELETE FROM home_events WHERE flight_id = ?",
+            params![flight_id],
+        )?;
+        conn.execute(
+            "DELETE FROM flight_events WHERE flight_id = ?",
+            params![flight_id],
+        )?;
+        conn.execute(
+            "DELETE FROM flight_findings WHERE flight_id = ?",
+            params![flight_id],
+        )?;
+        Ok(())
+    }
+
+    /// Recompute the battery stats of the flights matching `flights`, an SQL
+    /// condition on `f` (the flights table)
+    fn refresh_battery_stats(conn: &Connection, flights: &str) -> Result<(), DatabaseError> {
+        conn.execute(
+            &format!(
+                "DELETE FROM battery_flight_stats WHERE flight_id IN (SELECT f.id FROM flights f WHERE {})",
+                flights
+            ),
+            [],
+        )?;
+        conn.execute(&BATTERY_STATS_SQL.replace("{flights}", flights), [])?;
+        Ok(())
+    }
+
+    /// Light conditions of a flight about to be stored
+    fn metadata_daylight(flight: &FlightMetadata) -> Option<Daylight> {
+        Self::classify_daylight(
+            flight.home_lat,
+            flight.home_lon,
+            flight.start_time,
+            flight.end_time,
+            flight.duration_secs,
+        )
+    }
+
+    /// Light conditions at the home point at takeoff and landing; landing is
+    /// takeoff plus duration when the log has no end time
+    fn classify_daylight(
+        home_lat: Option<f64>,
+        home_lon: Option<f64>,
+        start: Option<chrono::DateTime<chrono::Utc>>,
+        end: Option<chrono::DateTime<chrono::Utc>>,
+        duration_secs: Option<f64>,
+    ) -> Option<Daylight> {
+        let (lat, lon, takeoff) = (home_lat?, home_lon?, start?);
+        let landing = end.unwrap_or_else(|| {
+            takeoff + chrono::Duration::milliseconds((duration_secs.unwrap_or(0.0) * 1000.0) as i64)
+        });
+        Some(solar::classify_flight(lat, lon, takeoff, landing))
+    }
+
+    /// Recompute the light conditions of the flights matching `flights`, an
+    /// SQL condition on `f` (the flights table)
+    fn refresh_daylight(conn: &Connection, flights: &str) -> Result<(), DatabaseError> {
+        let mut stmt = conn.prepare(&format!(
+            r#"
+            SELECT
+                f.id, f.home_lat, f.home_lon,
+                epoch_ms(f.start_time),
+                epoch_ms(f.end_time),
+                f.duration_secs
+            FROM flights f
+            WHERE {}
+            "#,
+            flights
+        ))?;
+        let to_time =
+            |ms: Option<i64>| ms.and_then(|ms| chrono::Utc.timestamp_millis_opt(ms).single());
+        let classified = stmt
+            .query_map([], |row| {
+                let daylight = Self::classify_daylight(
+                    row.get(1)?,
+                    row.get(2)?,
+                    to_time(row.get(3)?),
+                    to_time(row.get(4)?),
+                    row.get(5)?,
+                );
+                Ok((row.get::<_, i64>(0)?, daylight))
+            })?
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let mut update = conn.prepare("UPDATE flights SET daylight = ? WHERE id = ?")?;
+        for (flight_id, daylight) in classified {
+            update.execute(params![daylight.map(|d| d.as_str()), flight_id])?;
+        }
+        Ok(())
+    }
+
+    /// Replace the telemetry and derived metadata of an existing flight.
+    ///
+    /// Used when re-processing archived logs: the flight id, file name, hash,
+    /// display name, notes and import time are preserved. Runs in a single
+    /// transaction so a failure leaves the previous data untouched.
+    pub fn replace_flight_data(
+        &self,
+        flight_id: i64,
+        flight: &FlightMetadata,
+        points: &[TelemetryPoint],
+        events: &FlightEvents,
+    ) -> Result<usize, DatabaseError> {
+        let mut conn = self.conn.lock().unwrap();
+        let tx = conn.transaction()?;
+
+        let updated = tx.execute(
+            r#"
+            UPDATE flights SET
+                drone_model = ?, drone_serial = ?, aircraft_name = ?, battery_serial = ?,
+                start_time = ?, end_time = ?, duration_secs = ?, total_distance = ?,
+                max_altitude = ?, max_speed = ?, home_lat = ?, home_lon = ?, point_count = ?,
+                photo_count = ?, video_secs = ?, firmware_version = ?, daylight = ?
+            WHERE id = ?
+            "#,
+            params![
+                flight.drone_model,
+                flight.drone_serial,
+                flight.aircraft_name,
+                flight.battery_serial,
+                flight.start_time.map(|t| t.to_rfc3339()),
+                flight.end_time.map(|t| t.to_rfc3339()),
+                flight.duration_secs,
+                flight.total_distance,
+                flight.max_altitude,
+                flight.max_speed,
+                flight.home_lat,
+                flight.home_lon,
+                flight.point_count,
+                flight.photo_count,
+                flight.video_secs,
+                flight.firmware_version,
+                Self::metadata_daylight(flight).map(|d| d.as_str()),
+                flight_id,
+            ],
+        )?;
+
+        if updated == 0 {
+            return Err(DatabaseError::FlightNotFound(flight_id));
+        }
+
+        tx.execute(
+            "DELETE FROM telemetry WHERE flight_id = ?",
+            params![flight_id],
+        )?;
+        let inserted = Self::append_telemetry(&tx, flight_id, points)?;
+
+        Self::delete_events(&tx, flight_id)?;
+        Self::insert_events(&tx, flight_id, events)?;
+        Self::refresh_battery_stats(&tx, &format!("f.id = {}", flight_id))?;
+
+        tx.commit()?;
+
+        log::info!(
+            "Replaced telemetry for flight {} ({} points)",
+            flight_id,
+            inserted
+        );
+        Ok(inserted)
+    }
+
+    /// Get (flight id, file hash, archive path) for every flight, used to locate archived logs
+    pub fn get_flight_archive_refs(&self) -> Result<Vec<ArchiveRef>, DatabaseError> {
+        let conn = self.conn.lock().unwrap();
+
+        let mut stmt = conn
+            .prepare("SELECT id, file_hash, archive_path FROM flights ORDER BY start_time ASC")?;
+        let refs = stmt
+            .query_map([], |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)))?
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(refs)
+    }
+
+    /// Record where the archived copy of a flight's original log lives
+    pub fn set_flight_archive_path(
+        &self,
+        flight_id: i64,
+        archive_path: &str,
+    ) -> Result<(), DatabaseError> {
+        let conn = self.conn.lock().unwrap();
+
+        conn.execute(
+            "UPDATE flights SET archive_path = ? WHERE id = ?",
+            params![archive_path, flight_id],
+        )?;
+
+        Ok(())
+    }
+
+    /// Get all flights, most recent first
+    pub fn get_all_flights(&self) -> Result<Vec<Flight>, DatabaseError> {
+        Ok(self.query_flights(&FlightQuery::default())?.flights)
+    }
+
+    /// Get one page of the flights matching a query, with totals over all matches
+    ///
+    /// Every filter narrows the list: `search` matches names, notes, tags
+    /// and custom field values, and each tag and custom field must match.
+    pub fn query_flights(&self, query: &FlightQuery) -> Result<FlightPage, DatabaseError> {
+        let conn = self.conn.lock().unwrap();
+        let (where_clause, values) = Self::flight_filter(query);
+
+        let (total_count, total_duration_secs, total_distance_m) = conn.query_row(
+            &format!(
+                "SELECT COUNT(*), COALESCE(SUM(f.duration_secs), 0), COALESCE(SUM(f.total_distance), 0) FROM flights f {}",
+                where_clause
+            ),
+            duckdb::params_from_iter(values.iter()),
+            |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)),
+        )?;
+
+        let direction = match query.sort_direction {
+            SortDirection::Asc => "ASC",
+            SortDirection::Desc => "DESC",
+        };
+        let sort_column = match query.sort_by {
+            FlightSortKey::StartTime => "f.start_time",
+            FlightSortKey::Name => "lower(COALESCE(f.display_name, f.file_name))",
+            FlightSortKey::Duration => "f.duration_secs",
+            FlightSortKey::Distance => "f.total_distance",
+            FlightSortKey::MaxAltitude => "f.max_altitude",
+        };
+        // The id breaks ties so that pages never overlap
+        let mut tail = format!(
+            "ORDER BY {col} {dir} NULLS LAST, f.id {dir}",
+            col = sort_column,
+            dir = direction
+        );
+        if let Some(limit) = query.limit {
+            tail.push_str(&format!(" LIMIT {}", limit));
+        }
+        let offset = query.offset.unwrap_or(0);
+        if offset > 0 {
+            tail.push_str(&format!(" OFFSET {}", offset));
+        }
+
+        let flights = Self::select_flights(&conn, &where_clause, &tail, &values)?;
+
+        Ok(FlightPage {
+            flights,
+            total_count,
+            total_duration_secs,
+            total_distance_m,
+            offset,
+        })
+    }
+
+    /// Get a single flight by id
+    pub fn get_flight(&self, flight_id: i64) -> Result<Flight, DatabaseError> {
+        let conn = self.conn.lock().unwrap();
+        Self::select_flights(&conn, "WHERE f.id = ?", "", &[Value::BigInt(flight_id)])?
+            .pop()
+            .ok_or(DatabaseError::FlightNotFound(flight_id))
+    }
+
+    /// Build the WHERE clause and its parameters for a flight query
+    fn flight_filter(query: &FlightQuery) -> (String, Vec<Value>) {
+        let mut conditions: Vec<&str> = Vec::new();
+        let mut values: Vec<Value> = Vec::new();
+
+        if let Some(search) = query
+            .search
+            .as_deref()
+            .map(str::trim)
+            .filter(|s| !s.is_empty())
+        {
+            conditions.push(
+                r#"(
+                    COALESCE(f.display_name, f.file_name) ILIKE ? ESCAPE '\'
+                    OR f.notes ILIKE ? ESCAPE '\'
+                    OR EXISTS (SELECT 1 FROM flight_tags t WHERE t.flight_id = f.id AND t.tag ILIKE ? ESCAPE '\')
+                    OR EXISTS (SELECT 1 FROM flight_custom_fields c WHERE c.flight_id = f.id AND c.value ILIKE ? ESCAPE '\')
+                )"#,
+            );
+            let pattern = Value::Text(contains_pattern(search));
+            values.resize(values.len() + 4, pattern);
+        }
+        for tag in &query.tags {
+            conditions.push(
+                "EXISTS (SELECT 1 FROM flight_tags t WHERE t.flight_id = f.id AND lower(t.tag) = lower(?))",
+            );
+            values.push(Value::Text(tag.trim().to_string()));
+        }
+        for (field, value) in &query.custom_fields {
+            conditions.push(
+                "EXISTS (SELECT 1 FROM flight_custom_fields c WHERE c.flight_id = f.id AND c.field = ? AND lower(c.value) = lower(?))",
+            );
+            values.push(Value::Text(field.clone()));
+            values.push(Value::Text(value.trim().to_string()));
+        }
+        if let Some(from) = &query.date_from {
+            conditions.push("f.start_time >= CAST(? AS DATE)");
+            values.push(Value::Text(from.clone()));
+        }
+        if let Some(to) = &query.date_to {
+            conditions.push("f.start_time < CAST(? AS DATE) + INTERVAL 1 DAY");
+            values.push(Value::Text(to.clone()));
+        }
+        for (column, value) in [
+            ("f.drone_model = ?", &query.drone_model),
+            ("f.drone_serial = ?", &query.drone_serial),
+            ("f.battery_serial = ?", &query.battery_serial),
+        ] {
+            if let Some(value) = value {
+                conditions.push(column);
+                values.push(Value::Text(value.clone()));
+            }
+        }
+        if let Some(pilot_id) = query.pilot_id {
+            conditions.push("f.pilot_id = ?");
+            values.push(Value::BigInt(pilot_id));
+        }
+        if let Some(daylight) = query.daylight {
+            conditions.push("f.daylight = ?");
+            values.push(Value::Text(daylight.as_str().to_string()));
+        }
+        if let Some(min) = query.min_duration_secs {
+            conditions.push("f.duration_secs >= ?");
+            values.push(Value::Double(min));
+        }
+        if let Some(min) = query.min_distance_m {
+            conditions.push("f.total_distance >= ?");
+            values.push(Value::Double(min));
+        }
+        if let Some(bounds) = query.bounds {
+            if bounds.min_lon <= bounds.max_lon {
+                conditions.push("f.home_lat BETWEEN ? AND ? AND f.home_lon BETWEEN ? AND ?");
+            } else {
+                conditions
+                    .push("f.home_lat BETWEEN ? AND ? AND (f.home_lon >= ? OR f.home_lon <= ?)");
+            }
+            values.extend(
+                [
+                    bounds.min_lat,
+                    bounds.max_lat,
+                    bounds.min_lon,
+                    bounds.max_lon,
+                ]
+                .map(Value::Double),
+            );
+        }
+
+        let where_clause = if conditions.is_empty() {
+            String::new()
+        } else {
+            format!("WHERE {}", conditions.join(" AND "))
+        };
+        (where_clause, values)
+    }
+
+    /// Run the flight list SELECT with a WHERE clause and ORDER/LIMIT tail
+    fn select_flights(
+        conn: &Connection,
+        where_clause: &str,
+        tail: &str,
+        values: &[Value],
+    ) -> Result<Vec<Flight>, DatabaseError> {
+        let mut stmt = conn.prepare(&format!(
+            r#"
+            SELECT 
+                f.id, f.file_name, COALESCE(f.display_name, f.file_name) AS display_name,
+                f.drone_model, f.drone_serial, f.aircraft_name, f.battery_serial,
+                CAST(f.start_time AS VARCHAR) AS start_time,
+                f.duration_secs, f.total_distance,
+                f.max_altitude, f.max_speed, f.home_lat, f.home_lon, f.point_count,
+                f.archive_path, f.source_path, f.photo_count, f.video_secs,
+                (SELECT COUNT(*) FROM flight_findings ff WHERE ff.flight_id = f.id) AS issue_count,
+                f.notes, f.firmware_version, f.pilot_id, p.name AS pilot_name, f.daylight
+            FROM flights f
+            LEFT JOIN pilots p ON p.id = f.pilot_id
+            {}
+            {}
+            "#,
+            where_clause, tail
+        ))?;
+
+        let mut flights = stmt
+            .query_map(duckdb::params_from_iter(values.iter()), |row| {
+                Ok(Flight {
+                    id: row.get(0)?,
+                    file_name: row.get(1)?,
+                    display_name: row.get(2)?,
+                    drone_model: row.get(3)?,
+                    drone_serial: row.get(4)?,
+                    aircraft_name: row.get(5)?,
+                    battery_serial: row.get(6)?,
+                    start_time: row.get(7)?,
+                    duration_secs: row.get(8)?,
+                    total_distance: row.get(9)?,
+                    max_altitude: row.get(10)?,
+                    max_speed: row.get(11)?,
+                    home_lat: row.get(12)?,
+                    home_lon: row.get(13)?,
+                    daylight: row
+                        .get::<_, Option<String>>(24)?
+                        .as_deref()
+                        .and_then(Daylight::from_db),
+                    point_count: row.get(14)?,
+                    archive_path: row.get(15)?,
+                    source_path: row.get(16)?,
+                    photo_count: row.get(17)?,
+                    video_secs: row.get(18)?,
+                    issue_count: row.get(19)?,
+                    notes: row.get(20)?,
+                    firmware_version: row.get(21)?,
+                    pilot_id: row.get(22)?,
+                    pilot_name: row.get(23)?,
+                    tags: Vec::new(),
+                    custom_fields: BTreeMap::new(),
+                })
+            })?
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Self::attach_tags_and_fields(conn, &mut flights)?;
+
+        Ok(flights)
+    }
+
+    /// Fill in the tags and custom field values of listed flights
+    fn attach_tags_and_fields(
+        conn: &Connection,
+        flights: &mut [Flight],
+    ) -> Result<(), DatabaseError> {
+        if flights.is_empty() {
+            return Ok(());
+        }
+        let ids: Vec<Value> = flights.iter().map(|f| Value::BigInt(f.id)).collect();
+        let placeholders = vec!["?"; ids.len()].join(", ");
+
+        let mut tags: HashMap<i64, Vec<String>> = HashMap::new();
+        let mut stmt = conn.prepare(&format!(
+            "SELECT flight_id, tag FROM flight_tags WHERE flight_id IN ({}) ORDER BY lower(tag)",
+            placeholders
+        ))?;
+        let rows = stmt.query_map(duckdb::params_from_iter(ids.iter()), |row| {
+            Ok((row.get::<_, i64>(0)?, row.get::<_, String>(1)?))
+        })?;
+        for row in rows {
+            let (flight_id, tag) = row?;
+            tags.entry(flight_id).or_default().push(tag);
+        }
+
+        let mut fields: HashMap<i64, BTreeMap<String, String>> = HashMap::new();
+        let mut stmt = conn.prepare(&format!(
+            "SELECT flight_id, field, value FROM flight_custom_fields WHERE flight_id IN ({})",
+            placeholders
+        ))?;
+        let rows = stmt.query_map(duckdb::params_from_iter(ids.iter()), |row| {
+            Ok((
+                row.get::<_, i64>(0)?,
+                row.get::<_, String>(1)?,
+                row.get::<_, String>(2)?,
+            ))
+        })?;
+        for row in rows {
+            let (flight_id, field, value) = row?;
+            fields.entry(flight_id).or_default().insert(field, value);
+        }
+
+        for flight in flights {
+            flight.tags = tags.remove(&flight.id).unwrap_or_default();
+            flight.custom_fields = fields.remove(&flight.id).unwrap_or_default();
+        }
+        Ok(())
+    }
+
+    /// Get flight telemetry with automatic downsampling for large datasets.
+    ///
+    /// Strategy:
+    /// - If points < 5000: return raw data
+    /// - If points >= 5000: group by 1-second intervals, averaging values
+    /// - This keeps the frontend responsive while preserving data trends
+    pub fn get_flight_telemetry(
+        &self,
+        flight_id: i64,
+        max_points: Option<usize>,
+    ) -> Result<Vec<TelemetryRecord>, DatabaseError> {
+        let conn = self.conn.lock().unwrap();
+        let max_points = max_points.unwrap_or(5000);
+
+        // First, get the point count for this flight
+        let point_count: i64 = conn.query_row(
+            "SELECT COUNT(*) FROM telemetry WHERE flight_id = ?",
+            params![flight_id],
+            |row| row.get(0),
+        )?;
+
+        if point_count == 0 {
+            return Err(DatabaseError::FlightNotFound(flight_id));
+        }
+
+        let records = if point_count as usize <= max_points {
+            // Return raw data - no downsampling needed
+            log::debug!(
                 "Returning {} raw telemetry points for flight {}",
                 point_count,
                 flight_id
@@ -563,7 +1785,7 @@ impl Database {
         conn: &Connection,
         flight_id: i64,
     ) -> Result<Vec<TelemetryRecord>, DatabaseError> {
-        let mut stmt = conn.prepare(
+        let mut stmt = conn.prepare(&format!(
             r#"
             SELECT 
                 timestamp_ms,
@@ -586,12 +1808,18 @@ impl Database {
                 flight_mode,
                 rc_signal,
                 rc_uplink,
-                rc_downlink
+                rc_downlink,
+                battery_cell_voltages,
+                {imbalance} AS battery_cell_imbalance,
+                battery_full_capacity,
+                battery_remaining_capacity,
+                battery_voltage_warning
             FROM telemetry
             WHERE flight_id = ?
             ORDER BY timestamp_ms ASC
             "#,
-        )?;
+            imbalance = CELL_IMBALANCE_SQL
+        ))?;
 
         let records = stmt
             .query_map(params![flight_id], |row| {
@@ -609,6 +1837,11 @@ impl Database {
                     battery_percent: row.get(10)?,
                     battery_voltage: row.get(11)?,
                     battery_temp: row.get(12)?,
+                    battery_cell_voltages: cell_voltages(row, 21)?,
+                    battery_cell_imbalance: row.get(22)?,
+                    battery_full_capacity: row.get(23)?,
+                    battery_remaining_capacity: row.get(24)?,
+                    battery_voltage_warning: row.get(25)?,
                     pitch: row.get(13)?,
                     roll: row.get(14)?,
                     yaw: row.get(15)?,
@@ -643,7 +1876,7 @@ impl Database {
         let duration_ms = max_ts - min_ts;
         let bucket_size_ms = (duration_ms / target_points as i64).max(1000); // At least 1 second
 
-        let mut stmt = conn.prepare(
+        let mut stmt = conn.prepare(&format!(
             r#"
             WITH bucketed AS (
                 SELECT 
@@ -667,7 +1900,13 @@ impl Database {
                     MODE(flight_mode) AS flight_mode,
                     AVG(rc_signal)::INTEGER AS rc_signal,
                     AVG(rc_uplink)::INTEGER AS rc_uplink,
-                    AVG(rc_downlink)::INTEGER AS rc_downlink
+                    AVG(rc_downlink)::INTEGER AS rc_downlink,
+                    -- Latest cell readings, but the worst imbalance of the bucket
+                    ARG_MAX(battery_cell_voltages, timestamp_ms) AS battery_cell_voltages,
+                    MAX({imbalance}) AS battery_cell_imbalance,
+                    MAX(battery_full_capacity) AS battery_full_capacity,
+                    MIN(battery_remaining_capacity) AS battery_remaining_capacity,
+                    MAX(battery_voltage_warning) AS battery_voltage_warning
                 FROM telemetry
                 WHERE flight_id = ?
                 GROUP BY bucket_ts
@@ -675,7 +1914,8 @@ impl Database {
             )
             SELECT * FROM bucketed
             "#,
-        )?;
+            imbalance = CELL_IMBALANCE_SQL
+        ))?;
 
         let records = stmt
             .query_map(params![bucket_size_ms, bucket_size_ms, flight_id], |row| {
@@ -693,6 +1933,11 @@ impl Database {
                     battery_percent: row.get(10)?,
                     battery_voltage: row.get(11)?,
                     battery_temp: row.get(12)?,
+                    battery_cell_voltages: cell_voltages(row, 21)?,
+                    battery_cell_imbalance: row.get(22)?,
+                    battery_full_capacity: row.get(23)?,
+                    battery_remaining_capacity: row.get(24)?,
+                    battery_voltage_warning: row.get(25)?,
                     pitch: row.get(13)?,
                     roll: row.get(14)?,
                     yaw: row.get(15)?,
@@ -748,7 +1993,11 @@ impl Database {
 
         let track = stmt
             .query_map(params![flight_id, skip_factor as i64], |row| {
-                Ok([row.get::<_, f64>(0)?, row.get::<_, f64>(1)?, row.get::<_, f64>(2)?])
+                Ok([
+                    row.get::<_, f64>(0)?,
+                    row.get::<_, f64>(1)?,
+                    row.get::<_, f64>(2)?,
+                ])
             })?
             .filter_map(|r| r.ok())
             .collect();
@@ -756,336 +2005,2949 @@ impl Database {
         Ok(track)
     }
 
-    /// Delete a flight and all associated telemetry data
-    pub fn delete_flight(&self, flight_id: i64) -> Result<(), DatabaseError> {
-        let conn = self.conn.lock().unwrap();
-
-        conn.execute(
-            "DELETE FROM telemetry WHERE flight_id = ?",
-            params![flight_id],
-        )?;
-        conn.execute("DELETE FROM flights WHERE id = ?", params![flight_id])?;
+    /// Get every telemetry point for a flight at full resolution (for exports)
+    pub fn get_flight_points(&self, flight_id: i64) -> Result<Vec<TelemetryPoint>, DatabaseError> {
+        let mut points = Vec::new();
+        self.for_each_flight_point(flight_id, |point| {
+            points.push(point);
+            Ok::<_, DatabaseError>(())
+        })?;
 
-        log::info!("Deleted flight {}", flight_id);
-        Ok(())
+        Ok(points)
     }
 
-    /// Delete all flights and associated telemetry
-    pub fn delete_all_flights(&self) -> Result<(), DatabaseError> {
+    /// Stream a flight's full-resolution telemetry in time order without
+    /// collecting it, so exports of long flights stay flat in memory.
+    ///
+    /// The database lock is held until the callback has seen every point.
+    pub fn for_each_flight_point<E, F>(&self, flight_id: i64, mut f: F) -> Result<(), E>
+    where
+        E: From<DatabaseError>,
+        F: FnMut(TelemetryPoint) -> Result<(), E>,
+    {
         let conn = self.conn.lock().unwrap();
 
-        conn.execute("DELETE FROM telemetry", params![])?;
-        conn.execute("DELETE FROM flights", params![])?;
+        let mut stmt = conn
+            .prepare(
+                r#"
+            SELECT
+                timestamp_ms, latitude, longitude, altitude, height, vps_height, altitude_abs,
+                speed, velocity_x, velocity_y, velocity_z,
+                pitch, roll, yaw,
+                gimbal_pitch, gimbal_roll, gimbal_yaw,
+                battery_percent, battery_voltage, battery_current, battery_temp,
+                flight_mode, gps_signal, satellites,
+                rc_signal, rc_uplink, rc_downlink,
+                battery_cell_voltages, battery_full_capacity,
+                battery_remaining_capacity, battery_voltage_warning, battery_cells_estimated
+            FROM telemetry
+            WHERE flight_id = ?
+            ORDER BY timestamp_ms ASC
+            "#,
+            )
+            .map_err(DatabaseError::from)?;
+
+        let rows = stmt
+            .query_map(params![flight_id], |row| {
+                Ok(TelemetryPoint {
+                    timestamp_ms: row.get(0)?,
+                    latitude: row.get(1)?,
+                    longitude: row.get(2)?,
+                    altitude: row.get(3)?,
+                    height: row.get(4)?,
+                    vps_height: row.get(5)?,
+                    altitude_abs: row.get(6)?,
+                    speed: row.get(7)?,
+                    velocity_x: row.get(8)?,
+                    velocity_y: row.get(9)?,
+                    velocity_z: row.get(10)?,
+                    pitch: row.get(11)?,
+                    roll: row.get(12)?,
+                    yaw: row.get(13)?,
+                    gimbal_pitch: row.get(14)?,
+                    gimbal_roll: row.get(15)?,
+                    gimbal_yaw: row.get(16)?,
+                    battery_percent: row.get(17)?,
+                    battery_voltage: row.get(18)?,
+                    battery_current: row.get(19)?,
+                    battery_temp: row.get(20)?,
+                    battery_cell_voltages: cell_voltages(row, 27)?,
+                    battery_full_capacity: row.get(28)?,
+                    battery_remaining_capacity: row.get(29)?,
+                    battery_voltage_warning: row.get(30)?,
+                    battery_cells_estimated: row.get(31)?,
+                    flight_mode: row.get(21)?,
+                    gps_signal: row.get(22)?,
+                    satellites: row.get(23)?,
+                    rc_signal: row.get(24)?,
+                    rc_uplink: row.get(25)?,
+                    rc_downlink: row.get(26)?,
+                })
+            })
+            .map_err(DatabaseError::from)?;
+
+        for row in rows {
+            f(row.map_err(DatabaseError::from)?)?;
+        }
 
-        log::info!("Deleted all flights and telemetry");
         Ok(())
     }
 
-    /// Get overview stats across all flights
-    pub fn get_overview_stats(&self) -> Result<OverviewStats, DatabaseError> {
+    /// Get the camera events of a flight in time order
+    pub fn get_camera_events(&self, flight_id: i64) -> Result<Vec<CameraEvent>, DatabaseError> {
         let conn = self.conn.lock().unwrap();
 
-        // Basic aggregate stats
-        let (total_flights, total_distance, total_duration, total_points, max_altitude): (i64, f64, f64, i64, f64) =
-            conn.query_row(
-                r#"
-                SELECT
-                    COUNT(*)::BIGINT,
-                    COALESCE(SUM(total_distance), 0)::DOUBLE,
-                    COALESCE(SUM(duration_secs), 0)::DOUBLE,
-                    COALESCE(SUM(point_count), 0)::BIGINT,
-                    COALESCE(MAX(max_altitude), 0)::DOUBLE
-                FROM flights
-                "#,
-                [],
-                |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?, row.get(3)?, row.get(4)?)),
-            )?;
-
-        // Calculate max distance from home using haversine formula in SQL
-        // This gives us the max straight-line distance from home point for each flight
-        let max_distance_from_home: f64 = conn.query_row(
-            r#"
-            SELECT COALESCE(MAX(
-                CASE WHEN home_lat IS NOT NULL AND home_lon IS NOT NULL 
-                     AND t.latitude IS NOT NULL AND t.longitude IS NOT NULL
-                THEN 
-                    6371000 * 2 * ASIN(SQRT(
-                        POWER(SIN(RADIANS(t.latitude - f.home_lat) / 2), 2) +
-                        COS(RADIANS(f.home_lat)) * COS(RADIANS(t.latitude)) *
-                        POWER(SIN(RADIANS(t.longitude - f.home_lon) / 2), 2)
-                    ))
-                ELSE 0 END
-            ), 0)::DOUBLE
-            FROM flights f
-            LEFT JOIN telemetry t ON f.id = t.flight_id
-            WHERE f.home_lat IS NOT NULL AND f.home_lon IS NOT NULL
-            "#,
-            [],
-            |row| row.get(0),
-        ).unwrap_or(0.0);
-
-        // Battery usage with total duration
         let mut stmt = conn.prepare(
             r#"
-            SELECT battery_serial, COUNT(*)::BIGINT AS flight_count, COALESCE(SUM(duration_secs), 0)::DOUBLE AS total_duration
-            FROM flights
-            WHERE battery_serial IS NOT NULL AND battery_serial <> ''
-            GROUP BY battery_serial
-            ORDER BY flight_count DESC
+            SELECT timestamp_ms, event_type, latitude, longitude, altitude
+            FROM camera_events
+            WHERE flight_id = ?
+            ORDER BY timestamp_ms ASC
             "#,
         )?;
 
-        let batteries_used = stmt
-            .query_map([], |row| {
-                Ok(BatteryUsage {
-                    battery_serial: row.get(0)?,
-                    flight_count: row.get(1)?,
-                    total_duration_secs: row.get(2)?,
+        let events = stmt
+            .query_map(params![flight_id], |row| {
+                Ok(CameraEvent {
+                    timestamp_ms: row.get(0)?,
+                    event_type: row.get(1)?,
+                    latitude: row.get(2)?,
+                    longitude: row.get(3)?,
+                    altitude: row.get(4)?,
                 })
             })?
             .collect::<Result<Vec<_>, _>>()?;
 
-        // Drone usage stats
-        let mut stmt = conn.prepare(
-            r#"
-            SELECT 
-                COALESCE(drone_model, 'Unknown') AS drone_model, 
-                drone_serial,
-                aircraft_name,
-                COUNT(*)::BIGINT AS flight_count
-            FROM flights
-            GROUP BY drone_model, drone_serial, aircraft_name
-            ORDER BY flight_count DESC
-            "#,
-        )?;
+        Ok(events)
+    }
 
-        let drones_used = stmt
-            .query_map([], |row| {
-                Ok(DroneUsage {
-                    drone_model: row.get(0)?,
-                    drone_serial: row.get(1)?,
-                    aircraft_name: row.get(2)?,
-                    flight_count: row.get(3)?,
-                })
-            })?
-            .collect::<Result<Vec<_>, _>>()?;
+    /// Get the home point and RTH events of a flight in time order
+    pub fn get_home_events(&self, flight_id: i64) -> Result<Vec<HomeEvent>, DatabaseError> {
+        let conn = self.conn.lock().unwrap();
 
-        // Flights by date for activity heatmap (last 365 days)
         let mut stmt = conn.prepare(
             r#"
-            SELECT 
-                CAST(DATE_TRUNC('day', start_time) AS DATE)::VARCHAR AS flight_date,
-                COUNT(*)::BIGINT AS count
-            FROM flights
-            WHERE start_time IS NOT NULL 
-              AND start_time >= CURRENT_DATE - INTERVAL '365 days'
-            GROUP BY DATE_TRUNC('day', start_time)
-            ORDER BY flight_date ASC
+            SELECT timestamp_ms, event_type, latitude, longitude, altitude, detail
+            FROM home_events
+            WHERE flight_id = ?
+            ORDER BY timestamp_ms ASC
             "#,
         )?;
 
-        let flights_by_date = stmt
-            .query_map([], |row| {
-                Ok(FlightDateCount {
-                    date: row.get(0)?,
-                    count: row.get(1)?,
+        let events = stmt
+            .query_map(params![flight_id], |row| {
+                Ok(HomeEvent {
+                    timestamp_ms: row.get(0)?,
+                    event_type: row.get(1)?,
+                    latitude: row.get(2)?,
+                    longitude: row.get(3)?,
+                    altitude: row.get(4)?,
+                    detail: row.get(5)?,
                 })
             })?
             .collect::<Result<Vec<_>, _>>()?;
 
-        // Top 3 longest flights
+        Ok(events)
+    }
+
+    /// Get the app tips and warnings of a flight in time order
+    pub fn get_flight_events(&self, flight_id: i64) -> Result<Vec<FlightEvent>, DatabaseError> {
+        let conn = self.conn.lock().unwrap();
+
         let mut stmt = conn.prepare(
             r#"
-            SELECT 
-                id,
-                COALESCE(display_name, file_name) AS display_name,
-                COALESCE(duration_secs, 0)::DOUBLE AS duration_secs,
-                CAST(start_time AS VARCHAR) AS start_time
-            FROM flights
-            WHERE duration_secs IS NOT NULL
-            ORDER BY duration_secs DESC
-            LIMIT 3
+            SELECT timestamp_ms, severity, category, message
+            FROM flight_events
+            WHERE flight_id = ?
+            ORDER BY timestamp_ms ASC
             "#,
         )?;
 
-        let top_flights = stmt
-            .query_map([], |row| {
-                Ok(TopFlight {
-                    id: row.get(0)?,
-                    display_name: row.get(1)?,
-                    duration_secs: row.get(2)?,
-                    start_time: row.get(3)?,
+        let events = stmt
+            .query_map(params![flight_id], |row| {
+                Ok(FlightEvent {
+                    timestamp_ms: row.get(0)?,
+                    severity: row.get(1)?,
+                    category: row.get(2)?,
+                    message: row.get(3)?,
                 })
             })?
             .collect::<Result<Vec<_>, _>>()?;
 
-        // Max distance from home per flight (for top furthest calculation)
+        Ok(events)
+    }
+
+    /// Get the anomaly findings of a flight in time order
+    pub fn get_flight_findings(&self, flight_id: i64) -> Result<Vec<Finding>, DatabaseError> {
+        let conn = self.conn.lock().unwrap();
+
         let mut stmt = conn.prepare(
             r#"
-            SELECT
-                f.id,
-                COALESCE(f.display_name, f.file_name) AS display_name,
-                COALESCE(MAX(
-                    CASE WHEN f.home_lat IS NOT NULL AND f.home_lon IS NOT NULL
-                         AND t.latitude IS NOT NULL AND t.longitude IS NOT NULL
-                    THEN
-                        6371000 * 2 * ASIN(SQRT(
-                            POWER(SIN(RADIANS(t.latitude - f.home_lat) / 2), 2) +
-                            COS(RADIANS(f.home_lat)) * COS(RADIANS(t.latitude)) *
-                            POWER(SIN(RADIANS(t.longitude - f.home_lon) / 2), 2)
-                        ))
-                    ELSE 0 END
-                ), 0)::DOUBLE AS max_distance_from_home_m,
-                CAST(f.start_time AS VARCHAR) AS start_time
-            FROM flights f
-            LEFT JOIN telemetry t ON f.id = t.flight_id
-            GROUP BY f.id, f.display_name, f.file_name, f.start_time
-            ORDER BY max_distance_from_home_m DESC
+            SELECT timestamp_ms, duration_ms, kind, severity, message, value
+            FROM flight_findings
+            WHERE flight_id = ?
+            ORDER BY timestamp_ms ASC
             "#,
         )?;
 
-        let top_distance_flights = stmt
-            .query_map([], |row| {
-                Ok(TopDistanceFlight {
-                    id: row.get(0)?,
-                    display_name: row.get(1)?,
-                    max_distance_from_home_m: row.get(2)?,
-                    start_time: row.get(3)?,
+        let findings = stmt
+            .query_map(params![flight_id], |row| {
+                Ok(Finding {
+                    timestamp_ms: row.get(0)?,
+                    duration_ms: row.get(1)?,
+                    kind: row.get(2)?,
+                    severity: row.get(3)?,
+                    message: row.get(4)?,
+                    value: row.get(5)?,
                 })
             })?
             .collect::<Result<Vec<_>, _>>()?;
 
-        // Battery health points (delta % / minute) per flight
+        Ok(findings)
+    }
+
+    /// Query app tips and warnings across all flights, newest flights first
+    pub fn query_flight_events(
+        &self,
+        query: &FlightEventQuery,
+    ) -> Result<Vec<FlightEventEntry>, DatabaseError> {
+        let conn = self.conn.lock().unwrap();
+
         let mut stmt = conn.prepare(
             r#"
             SELECT
-                f.id,
-                f.battery_serial,
+                e.flight_id,
+                COALESCE(f.display_name, f.file_name) AS display_name,
                 CAST(f.start_time AS VARCHAR) AS start_time,
-                COALESCE(f.duration_secs, 0)::DOUBLE AS duration_secs,
-                (MAX(t.battery_percent) - MIN(t.battery_percent))::DOUBLE AS delta_percent
-            FROM flights f
-            JOIN telemetry t ON f.id = t.flight_id
-            WHERE f.battery_serial IS NOT NULL AND f.battery_serial <> ''
-              AND t.battery_percent IS NOT NULL
-            GROUP BY f.id, f.battery_serial, f.start_time, f.duration_secs
-            ORDER BY f.start_time ASC
+                e.timestamp_ms, e.severity, e.category, e.message
+            FROM flight_events e
+            JOIN flights f ON f.id = e.flight_id
+            WHERE (?1 IS NULL OR e.severity = ?1)
+              AND (?2 IS NULL OR e.category = ?2)
+              AND (?3 IS NULL OR e.message ILIKE '%' || ?3 || '%')
+            ORDER BY f.start_time DESC, e.timestamp_ms ASC
+            LIMIT ?4
             "#,
         )?;
 
-        let battery_health_points = stmt
-            .query_map([], |row| {
-                let duration_secs: f64 = row.get(3)?;
-                let duration_mins = if duration_secs > 0.0 { duration_secs / 60.0 } else { 0.0 };
-                let delta_percent: f64 = row.get(4)?;
-                let rate_per_min = if duration_mins > 0.0 { delta_percent / duration_mins } else { 0.0 };
-
-                Ok(BatteryHealthPoint {
-                    flight_id: row.get(0)?,
-                    battery_serial: row.get(1)?,
-                    start_time: row.get(2)?,
-                    duration_mins,
-                    delta_percent,
-                    rate_per_min,
-                })
-            })?
+        let limit = query.limit.map(|l| l as i64).unwrap_or(i64::MAX);
+        let entries = stmt
+            .query_map(
+                params![query.severity, query.category, query.search, limit],
+                |row| {
+                    Ok(FlightEventEntry {
+                        flight_id: row.get(0)?,
+                        display_name: row.get(1)?,
+                        start_time: row.get(2)?,
+                        event: FlightEvent {
+                            timestamp_ms: row.get(3)?,
+                            severity: row.get(4)?,
+                            category: row.get(5)?,
+                            message: row.get(6)?,
+                        },
+                    })
+                },
+            )?
             .collect::<Result<Vec<_>, _>>()?;
 
-        Ok(OverviewStats {
-            total_flights,
-            total_distance_m: total_distance,
-            total_duration_secs: total_duration,
-            total_points,
-            max_altitude_m: max_altitude,
-            max_distance_from_home_m: max_distance_from_home,
-            batteries_used,
-            drones_used,
-            flights_by_date,
-            top_flights,
-            top_distance_flights,
-            battery_health_points,
-        })
+        Ok(entries)
     }
 
-    /// Update the display name for a flight
-    pub fn update_flight_name(&self, flight_id: i64, display_name: &str) -> Result<(), DatabaseError> {
-        let conn = self.conn.lock().unwrap();
+    /// Delete a flight and all associated telemetry data
+    pub fn delete_flight(&self, flight_id: i64) -> Result<(), DatabaseError> {
+        let mut conn = self.conn.lock().unwrap();
+        let tx = conn.transaction()?;
 
-        conn.execute(
-            "UPDATE flights SET display_name = ? WHERE id = ?",
-            params![display_name, flight_id],
+        tx.execute(
+            "DELETE FROM telemetry WHERE flight_id = ?",
+            params![flight_id],
+        )?;
+        Self::delete_events(&tx, flight_id)?;
+        tx.execute(
+            "DELETE FROM flight_tags WHERE flight_id = ?",
+            params![flight_id],
+        )?;
+        tx.execute(
+            "DELETE FROM flight_custom_fields WHERE flight_id = ?",
+            params![flight_id],
+        )?;
+        tx.execute(
+            "DELETE FROM battery_flight_stats WHERE flight_id = ?",
+            params![flight_id],
         )?;
+        tx.execute("DELETE FROM flights WHERE id = ?", params![flight_id])?;
 
+        tx.commit()?;
+
+        log::info!("Deleted flight {}", flight_id);
         Ok(())
     }
 
-    /// Check if a file has already been imported (by hash)
-    pub fn is_file_imported(&self, file_hash: &str) -> Result<bool, DatabaseError> {
-        let conn = self.conn.lock().unwrap();
+    /// Delete all flights and associated telemetry
+    pub fn delete_all_flights(&self) -> Result<(), DatabaseError> {
+        let mut conn = self.conn.lock().unwrap();
+        let tx = conn.transaction()?;
 
-        let count: i64 = conn.query_row(
-            "SELECT COUNT(*) FROM flights WHERE file_hash = ?",
-            params![file_hash],
-            |row| row.get(0),
-        )?;
+        tx.execute("DELETE FROM telemetry", params![])?;
+        tx.execute("DELETE FROM camera_events", params![])?;
+        tx.execute("DELETE FROM home_events", params![])?;
+        tx.execute("DELETE FROM flight_events", params![])?;
+        tx.execute("DELETE FROM flight_findings", params![])?;
+        tx.execute("DELETE FROM flight_tags", params![])?;
+        tx.execute("DELETE FROM flight_custom_fields", params![])?;
+        tx.execute("DELETE FROM battery_flight_stats", params![])?;
+        tx.execute("DELETE FROM flights", params![])?;
 
-        Ok(count > 0)
+        tx.commit()?;
+
+        log::info!("Deleted all flights and telemetry");
+        Ok(())
     }
 
-    /// Store an encryption key for a drone serial number
-    pub fn store_keychain(&self, serial: &str, key: &str) -> Result<(), DatabaseError> {
+    /// Export the logbook to a directory of Parquet files
+    ///
+    /// `flights.parquet` always holds all flight rows; the per-flight tables
+    /// are written as single files or hive-partitioned directories, which
+    /// Polars, pandas and DuckDB read directly. Returns the number of flights.
+    pub fn export_parquet(
+        &self,
+        dir: &Path,
+        partition: ParquetPartition,
+    ) -> Result<usize, DatabaseError> {
+        fs::create_dir_all(dir)?;
         let conn = self.conn.lock().unwrap();
 
-        conn.execute(
-            r#"
-            INSERT INTO keychains (serial_number, encryption_key)
-            VALUES (?, ?)
-            ON CONFLICT (serial_number) DO UPDATE SET 
-                encryption_key = excluded.encryption_key,
-                fetched_at = CURRENT_TIMESTAMP
-            "#,
-            params![serial, key],
-        )?;
+        for table in std::iter::once("flights").chain(FLIGHT_DATA_TABLES.iter().copied()) {
+            // Replace the output of an earlier export into the same directory
+            let file = dir.join(format!("{}.parquet", table));
+            let partitioned = dir.join(table);
+            if file.exists() {
+                fs::remove_file(&file)?;
+            }
+            if partitioned.exists() {
+                fs::remove_dir_all(&partitioned)?;
+            }
 
-        Ok(())
+            let sql = match partition {
+                _ if table == "flights" => {
+                    format!("COPY flights TO {} (FORMAT PARQUET)", sql_path(&file))
+                }
+                ParquetPartition::None => {
+                    format!("COPY {} TO {} (FORMAT PARQUET)", table, sql_path(&file))
+                }
+                ParquetPartition::Flight => format!(
+                    "COPY {} TO {} (FORMAT PARQUET, PARTITION_BY (flight_id))",
+                    table,
+                    sql_path(&partitioned)
+                ),
+                ParquetPartition::Month => format!(
+                    r#"
+                    COPY (
+                        SELECT d.*, COALESCE(strftime(f.start_time, '%Y-%m'), 'unknown') AS month
+                        FROM {} d
+                        LEFT JOIN flights f ON f.id = d.flight_id
+                    ) TO {} (FORMAT PARQUET, PARTITION_BY (month))
+                    "#,
+                    table,
+                    sql_path(&partitioned)
+                ),
+            };
+            conn.execute_batch(&sql)?;
+        }
+
+        let flight_count: i64 =
+            conn.query_row("SELECT COUNT(*) FROM flights", [], |row| row.get(0))?;
+
+        log::info!("Exported {} flights as Parquet to {:?}", flight_count, dir);
+        Ok(flight_count as usize)
     }
 
-    /// Retrieve a cached encryption key
-    pub fn get_keychain(&self, serial: &str) -> Result<Option<String>, DatabaseError> {
-        let conn = self.conn.lock().unwrap();
+    /// Import a Parquet export written by [`export_parquet`](Self::export_parquet)
+    ///
+    /// Flights whose file hash is already in the library are skipped; flight
+    /// ids that clash with existing flights are reassigned. Everything is
+    /// imported in one transaction.
+    pub fn import_parquet(&self, dir: &Path) -> Result<ParquetImportReport, DatabaseError> {
+        let flights_file = dir.join("flights.parquet");
+        if !flights_file.is_file() {
+            return Err(DatabaseError::InvalidParquetExport(format!(
+                "{} not found",
+                flights_file.display()
+            )));
+        }
 
-        let result = conn.query_row(
-            "SELECT encryption_key FROM keychains WHERE serial_number = ?",
-            params![serial],
-            |row| row.get::<_, String>(0),
+        let mut conn = self.conn.lock().unwrap();
+        let tx = conn.transaction()?;
+        let outcome = Self::merge_flights(
+            &tx,
+            &format!("read_parquet({})", sql_path(&flights_file)),
+            |_, table| parquet_source(dir, table),
+        )?;
+        tx.commit()?;
+
+        log::info!(
+            "Imported {} of {} flights from Parquet export {:?}",
+            outcome.imported.len(),
+            outcome.source_flights,
+            dir
         );
 
-        match result {
-            Ok(key) => Ok(Some(key)),
-            Err(duckdb::Error::QueryReturnedNoRows) => Ok(None),
-            Err(e) => Err(e.into()),
-        }
+        Ok(ParquetImportReport {
+            flights_imported: outcome.imported.len(),
+            flights_skipped: outcome.source_flights - outcome.imported.len(),
+            telemetry_rows: outcome.telemetry_rows,
+        })
     }
-}
 
+    /// Merge the flights of another library file (e.g. from a backup) into
+    /// this one, skipping flights whose file hash is already present
+    pub fn merge_database(&self, source: &Path) -> Result<MergeOutcome, DatabaseError> {
+        let mut conn = self.conn.lock().unwrap();
+        conn.execute_batch(&format!(
+            "ATTACH {} AS merge_source (READ_ONLY)",
+            sql_path(source)
+        ))?;
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-    use tempfile::tempdir;
+        let result = conn.transaction().map_err(DatabaseError::from).and_then(|tx| {
+            let outcome = Self::merge_flights(&tx, "merge_source.flights", |conn, table| {
+                let exists: bool = conn.query_row(
+                    "SELECT COUNT(*) > 0 FROM duckdb_tables() WHERE database_name = 'merge_source' AND table_name = ?",
+                    params![table],
+                    |row| row.get(0),
+                )?;
+                Ok(exists.then(|| format!("merge_source.{}", table)))
+            })?;
+            tx.commit()?;
+            Ok(outcome)
+        });
 
-    #[test]
-    fn test_database_initialization() {
-        let temp_dir = tempdir().unwrap();
-        let db = Database::new(temp_dir.path().to_path_buf()).unwrap();
+        conn.execute_batch("DETACH merge_source")?;
 
-        // Verify directories were created
-        assert!(temp_dir.path().join("raw_logs").exists());
-        assert!(temp_dir.path().join("keychains").exists());
-        assert!(temp_dir.path().join("flights.db").exists());
+        if let Ok(outcome) = &result {
+            log::info!(
+                "Merged {} of {} flights from {:?}",
+                outcome.imported.len(),
+                outcome.source_flights,
+                source
+            );
+        }
+        result
+    }
 
-        // Verify we can get flights (empty)
-        let flights = db.get_all_flights().unwrap();
-        assert!(flights.is_empty());
+    /// Insert the flights of `flights_source` whose file hash is new (first
+    /// copy only), and their rows of each per-flight table from `table_source`.
+    /// Flights without a hash are matched on start time, aircraft serial and
+    /// point count instead.
+    ///
+    /// Ids that clash with existing flights are moved past the highest id in
+    /// use. Archive paths are not carried over: archived logs are located by
+    /// hash once they are in raw_logs. Neither are pilots, whose ids differ
+    /// between libraries; this library's aircraft rules assign them instead.
+    fn merge_flights<F>(
+        conn: &Connection,
+        flights_source: &str,
+        table_source: F,
+    ) -> Result<MergeOutcome, DatabaseError>
+    where
+        F: Fn(&Connection, &str) -> Result<Option<String>, DatabaseError>,
+    {
+        conn.execute_batch(&format!(
+            r#"
+            CREATE OR REPLACE TEMP TABLE merge_flights AS
+                SELECT * FROM {};
+
+            -- New flights get ids from this library's sequence
+            CREATE OR REPLACE TEMP TABLE merge_ids AS
+            SELECT b.id AS old_id, nextval('flight_id_seq') AS new_id
+            FROM (
+                SELECT b.id
+                FROM merge_flights b
+                WHERE (b.file_hash IS NOT NULL
+                       AND b.file_hash NOT IN (SELECT file_hash FROM flights WHERE file_hash IS NOT NULL)
+                       AND b.id = (SELECT MIN(d.id) FROM merge_flights d WHERE d.file_hash = b.file_hash))
+                   OR (b.file_hash IS NULL
+                       AND NOT EXISTS (
+                           SELECT 1 FROM flights f
+                           WHERE f.start_time IS NOT DISTINCT FROM b.start_time
+                             AND f.drone_serial IS NOT DISTINCT FROM b.drone_serial
+                             AND f.point_count = b.point_count
+                       )
+                       AND b.id = (
+                           SELECT MIN(d.id) FROM merge_flights d
+                           WHERE d.file_hash IS NULL
+                             AND d.start_time IS NOT DISTINCT FROM b.start_time
+                             AND d.drone_serial IS NOT DISTINCT FROM b.drone_serial
+                             AND d.point_count = b.point_count
+                       ))
+                ORDER BY b.id
+            ) b;
+            "#,
+            flights_source
+        ))?;
+
+        let source_flights: i64 =
+            conn.query_row("SELECT COUNT(*) FROM merge_flights", [], |row| row.get(0))?;
+
+        let columns = shared_columns(conn, "flights", "merge_flights")?;
+        let values: Vec<String> = columns
+            .iter()
+            .map(|c| match c.as_str() {
+                "id" => "m.new_id".to_string(),
+                "archive_path" | "pilot_id" => "NULL".to_string(),
+                _ => format!("b.{}", c),
+            })
+            .collect();
+        conn.execute(
+            &format!(
+                "INSERT INTO flights ({}) SELECT {} FROM merge_flights b JOIN merge_ids m ON m.old_id = b.id",
+                columns.join(", "),
+                values.join(", ")
+            ),
+            [],
+        )?;
+
+        let mut stmt = conn.prepare("SELECT old_id, new_id FROM merge_ids ORDER BY old_id")?;
+        let imported = stmt
+            .query_map([], |row| Ok((row.get(0)?, row.get(1)?)))?
+            .collect::<Result<Vec<(i64, i64)>, _>>()?;
+
+        let mut telemetry_rows = 0;
+        for table in FLIGHT_DATA_TABLES {
+            let Some(source) = table_source(conn, table)? else {
+                continue;
+            };
+            let columns = shared_columns(conn, table, &source)?;
+            let values: Vec<String> = columns
+                .iter()
+                .map(|c| match c.as_str() {
+                    "flight_id" => "m.new_id".to_string(),
+                    _ => format!("b.{}", c),
+                })
+                .collect();
+            let rows = conn.execute(
+                &format!(
+                    "INSERT INTO {} ({}) SELECT {} FROM {} b JOIN merge_ids m ON m.old_id = b.flight_id",
+                    table,
+                    columns.join(", "),
+                    values.join(", "),
+                    source
+                ),
+                [],
+            )?;
+            if *table == "telemetry" {
+                telemetry_rows = rows;
+            }
+        }
+
+        // Custom fields used by imported flights become defined here too
+        conn.execute(
+            "INSERT OR IGNORE INTO custom_fields (name) SELECT DISTINCT field FROM flight_custom_fields",
+            [],
+        )?;
+
+        // Battery stats and light conditions are derived, so they are rebuilt
+        // rather than copied (older libraries have no light conditions)
+        Self::refresh_battery_stats(conn, "f.id IN (SELECT new_id FROM merge_ids)")?;
+        Self::refresh_daylight(conn, "f.id IN (SELECT new_id FROM merge_ids)")?;
+
+        // Pilot ids belong to the source library; this one's rules apply instead
+        conn.execute(
+            r#"
+            UPDATE flights SET pilot_id = r.pilot_id
+            FROM pilot_aircraft r
+            WHERE flights.drone_serial = r.drone_serial
+              AND flights.id IN (SELECT new_id FROM merge_ids)
+            "#,
+            [],
+        )?;
+
+        conn.execute_batch("DROP TABLE merge_ids; DROP TABLE merge_flights;")?;
+
+        Ok(MergeOutcome {
+            source_flights: source_flights as usize,
+            imported,
+            telemetry_rows,
+        })
+    }
+
+    /// Write a consistent copy of the library file to `destination`
+    ///
+    /// The WAL is checkpointed into the database file first, and the
+    /// connection stays locked while copying so no write can interleave.
+    pub fn checkpoint_copy(&self, destination: &Path) -> Result<(), DatabaseError> {
+        let conn = self.conn.lock().unwrap();
+        Self::copy_checkpointed(&conn, &self.data_dir.join("flights.db"), destination)
+    }
+
+    /// Number of flights in a library file, opened read-only
+    ///
+    /// Used to validate a database before it replaces or is merged into
+    /// the current library. Libraries from a newer app are refused.
+    pub fn count_flights_in(path: &Path) -> Result<usize, DatabaseError> {
+        let conn = Connection::open_in_memory()?;
+        conn.execute_batch(&format!(
+            "ATTACH {} AS inspected (READ_ONLY)",
+            sql_path(path)
+        ))?;
+
+        let version = Self::schema_version_of(&conn, Some("inspected"))?;
+        if version > SCHEMA_VERSION {
+            return Err(DatabaseError::UnsupportedSchemaVersion(
+                version,
+                SCHEMA_VERSION,
+            ));
+        }
+
+        let count: i64 = conn.query_row("SELECT COUNT(*) FROM inspected.flights", [], |row| {
+            row.get(0)
+        })?;
+        Ok(count as usize)
+    }
+
+    /// Replace the library file with `source`, reopening the connection
+    ///
+    /// The current database is kept next to it as `flights.db.bak.<timestamp>`,
+    /// whose path is returned. If the new file cannot be put in place, opened
+    /// or migrated, the previous database is moved back and reopened.
+    pub fn replace_database(&self, source: &Path) -> Result<PathBuf, DatabaseError> {
+        let db_path = self.data_dir.join("flights.db");
+
+        let backup_path = {
+            let mut conn = self.conn.lock().unwrap();
+            conn.execute_batch("CHECKPOINT")?;
+
+            // Close the library file before moving it
+            *conn = Connection::open_in_memory()?;
+            let backup_path = match Self::backup_db(&db_path) {
+                Ok(path) => path,
+                Err(e) => {
+                    // The library was not moved, so it is simply reopened
+                    Self::reopen_library(&mut conn, &db_path)?;
+                    return Err(e);
+                }
+            };
+
+            let installed = fs::copy(source, &db_path)
+                .map_err(DatabaseError::from)
+                .and_then(|_| Self::reopen_library(&mut conn, &db_path));
+            if let Err(e) = installed {
+                Self::restore_library(&mut conn, &db_path, &backup_path)?;
+                return Err(e);
+            }
+            backup_path
+        };
+
+        // Bring a library from an older version up to the current schema
+        if let Err(e) = self.init_schema() {
+            let mut conn = self.conn.lock().unwrap();
+            Self::restore_library(&mut conn, &db_path, &backup_path)?;
+            return Err(e);
+        }
+
+        log::info!(
+            "Replaced library with {:?}, previous kept at {:?}",
+            source,
+            backup_path
+        );
+        Ok(backup_path)
+    }
+
+    /// Open the library file in place of the current connection
+    fn reopen_library(conn: &mut Connection, db_path: &Path) -> Result<(), DatabaseError> {
+        *conn = Connection::open(db_path)?;
+        Self::configure_connection(conn)?;
+        Ok(())
+    }
+
+    /// Put a library moved aside by [`backup_db`](Self::backup_db) back in
+    /// place of a failed replacement, and reopen it
+    fn restore_library(
+        conn: &mut Connection,
+        db_path: &Path,
+        backup_path: &Path,
+    ) -> Result<(), DatabaseError> {
+        *conn = Connection::open_in_memory()?;
+        if backup_path != db_path {
+            if db_path.exists() {
+                fs::remove_file(db_path)?;
+            }
+            let _ = fs::remove_file(db_path.with_extension("db.wal"));
+            fs::rename(backup_path, db_path)?;
+        }
+        Self::reopen_library(conn, db_path)
+    }
+
+    /// Get overview stats across the flights matching a query
+    ///
+    /// Every aggregate, including the telemetry-based distance from home and
+    /// battery health, is computed over the same filtered set of flights.
+    /// Sorting and paging in the query are ignored.
+    pub fn get_overview_stats(&self, query: &FlightQuery) -> Result<OverviewStats, DatabaseError> {
+        let conn = self.conn.lock().unwrap();
+
+        // Each statement below reads the matching flights from this CTE
+        let (where_clause, values) = Self::flight_filter(query);
+        let scope = format!("scope AS (SELECT f.* FROM flights f {})", where_clause);
+        let scope_params = || duckdb::params_from_iter(values.iter());
+
+        // Basic aggregate stats
+        let (total_flights, total_distance, total_duration, total_points, max_altitude): (
+            i64,
+            f64,
+            f64,
+            i64,
+            f64,
+        ) = conn.query_row(
+            &format!(
+                r#"
+                    WITH {}
+                    SELECT
+                        COUNT(*)::BIGINT,
+                        COALESCE(SUM(total_distance), 0)::DOUBLE,
+                        COALESCE(SUM(duration_secs), 0)::DOUBLE,
+                        COALESCE(SUM(point_count), 0)::BIGINT,
+                        COALESCE(MAX(max_altitude), 0)::DOUBLE
+                    FROM scope
+                    "#,
+                scope
+            ),
+            scope_params(),
+            |row| {
+                Ok((
+                    row.get(0)?,
+                    row.get(1)?,
+                    row.get(2)?,
+                    row.get(3)?,
+                    row.get(4)?,
+                ))
+            },
+        )?;
+
+        // Calculate max distance from home using haversine formula in SQL
+        // This gives us the max straight-line distance from home point for each flight
+        let max_distance_from_home: f64 = conn
+            .query_row(
+                &format!(
+                    r#"
+                WITH {}, distances AS ({})
+                SELECT COALESCE(MAX(distance_m), 0)::DOUBLE FROM distances
+                "#,
+                    scope, HOME_DISTANCE_SQL
+                ),
+                scope_params(),
+                |row| row.get(0),
+            )
+            .unwrap_or(0.0);
+
+        // Flights by light conditions
+        let (day_flights, civil_twilight_flights, night_flights): (i64, i64, i64) = conn
+            .query_row(
+                &format!(
+                    r#"
+                WITH {}
+                SELECT
+                    COUNT(*) FILTER (WHERE daylight = 'day')::BIGINT,
+                    COUNT(*) FILTER (WHERE daylight = 'civilTwilight')::BIGINT,
+                    COUNT(*) FILTER (WHERE daylight = 'night')::BIGINT
+                FROM scope
+                "#,
+                    scope
+                ),
+                scope_params(),
+                |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)),
+            )?;
+
+        // Battery usage with total duration
+        let mut stmt = conn.prepare(&format!(
+            r#"
+            WITH {}
+            SELECT battery_serial, COUNT(*)::BIGINT AS flight_count, COALESCE(SUM(duration_secs), 0)::DOUBLE AS total_duration
+            FROM scope
+            WHERE battery_serial IS NOT NULL AND battery_serial <> ''
+            GROUP BY battery_serial
+            ORDER BY flight_count DESC
+            "#,
+            scope
+        ))?;
+
+        let batteries_used = stmt
+            .query_map(scope_params(), |row| {
+                Ok(BatteryUsage {
+                    battery_serial: row.get(0)?,
+                    flight_count: row.get(1)?,
+                    total_duration_secs: row.get(2)?,
+                })
+            })?
+            .collect::<Result<Vec<_>, _>>()?;
+
+        // Drone usage stats
+        let mut stmt = conn.prepare(&format!(
+            r#"
+            WITH {}
+            SELECT 
+                COALESCE(drone_model, 'Unknown') AS drone_model, 
+                drone_serial,
+                aircraft_name,
+                COUNT(*)::BIGINT AS flight_count
+            FROM scope
+            GROUP BY drone_model, drone_serial, aircraft_name
+            ORDER BY flight_count DESC
+            "#,
+            scope
+        ))?;
+
+        let drones_used = stmt
+            .query_map(scope_params(), |row| {
+                Ok(DroneUsage {
+                    drone_model: row.get(0)?,
+                    drone_serial: row.get(1)?,
+                    aircraft_name: row.get(2)?,
+                    flight_count: row.get(3)?,
+                })
+            })?
+            .collect::<Result<Vec<_>, _>>()?;
+
+        // Flights by date for activity heatmap (last 365 days). Dates come
+        // from plain timestamps: time zone functions need the ICU extension,
+        // which is not bundled and cannot be loaded offline.
+        let mut stmt = conn.prepare(&format!(
+            r#"
+            WITH {},
+            days AS (
+                SELECT CAST(CAST(start_time AS TIMESTAMP) AS DATE) AS day
+                FROM scope
+                WHERE start_time IS NOT NULL
+            )
+            SELECT
+                day::VARCHAR AS flight_date,
+                COUNT(*)::BIGINT AS count
+            FROM days
+            WHERE day >= CAST(CAST(CURRENT_TIMESTAMP AS TIMESTAMP) AS DATE) - INTERVAL 365 DAY
+            GROUP BY day
+            ORDER BY day ASC
+            "#,
+            scope
+        ))?;
+
+        let flights_by_date = stmt
+            .query_map(scope_params(), |row| {
+                Ok(FlightDateCount {
+                    date: row.get(0)?,
+                    count: row.get(1)?,
+                })
+            })?
+            .collect::<Result<Vec<_>, _>>()?;
+
+        // Top 3 longest flights
+        let mut stmt = conn.prepare(&format!(
+            r#"
+            WITH {}
+            SELECT 
+                id,
+                COALESCE(display_name, file_name) AS display_name,
+                COALESCE(duration_secs, 0)::DOUBLE AS duration_secs,
+                CAST(start_time AS VARCHAR) AS start_time
+            FROM scope
+            WHERE duration_secs IS NOT NULL
+            ORDER BY duration_secs DESC
+            LIMIT 3
+            "#,
+            scope
+        ))?;
+
+        let top_flights = stmt
+            .query_map(scope_params(), |row| {
+                Ok(TopFlight {
+                    id: row.get(0)?,
+                    display_name: row.get(1)?,
+                    duration_secs: row.get(2)?,
+                    start_time: row.get(3)?,
+                })
+            })?
+            .collect::<Result<Vec<_>, _>>()?;
+
+        // Max distance from home per flight (for top furthest calculation)
+        let mut stmt = conn.prepare(&format!(
+            r#"
+            WITH {}, distances AS ({})
+            SELECT
+                f.id,
+                COALESCE(f.display_name, f.file_name) AS display_name,
+                COALESCE(MAX(d.distance_m), 0)::DOUBLE AS max_distance_from_home_m,
+                CAST(f.start_time AS VARCHAR) AS start_time
+            FROM scope f
+            LEFT JOIN distances d ON f.id = d.flight_id
+            GROUP BY f.id, f.display_name, f.file_name, f.start_time
+            ORDER BY max_distance_from_home_m DESC
+            "#,
+            scope, HOME_DISTANCE_SQL
+        ))?;
+
+        let top_distance_flights = stmt
+            .query_map(scope_params(), |row| {
+                Ok(TopDistanceFlight {
+                    id: row.get(0)?,
+                    display_name: row.get(1)?,
+                    max_distance_from_home_m: row.get(2)?,
+                    start_time: row.get(3)?,
+                })
+            })?
+            .collect::<Result<Vec<_>, _>>()?;
+
+        // Battery health points (delta % / minute) per flight
+        let mut stmt = conn.prepare(&format!(
+            r#"
+            WITH {}
+            SELECT
+                f.id,
+                f.battery_serial,
+                CAST(f.start_time AS VARCHAR) AS start_time,
+                COALESCE(f.duration_secs, 0)::DOUBLE AS duration_secs,
+                (MAX(t.battery_percent) - MIN(t.battery_percent))::DOUBLE AS delta_percent
+            FROM scope f
+            JOIN telemetry t ON f.id = t.flight_id
+            WHERE f.battery_serial IS NOT NULL AND f.battery_serial <> ''
+              AND t.battery_percent IS NOT NULL
+            GROUP BY f.id, f.battery_serial, f.start_time, f.duration_secs
+            ORDER BY f.start_time ASC
+            "#,
+            scope
+        ))?;
+
+        let battery_health_points = stmt
+            .query_map(scope_params(), |row| {
+                let duration_secs: f64 = row.get(3)?;
+                let duration_mins = if duration_secs > 0.0 {
+                    duration_secs / 60.0
+                } else {
+                    0.0
+                };
+                let delta_percent: f64 = row.get(4)?;
+                let rate_per_min = if duration_mins > 0.0 {
+                    delta_percent / duration_mins
+                } else {
+                    0.0
+                };
+
+                Ok(BatteryHealthPoint {
+                    flight_id: row.get(0)?,
+                    battery_serial: row.get(1)?,
+                    start_time: row.get(2)?,
+                    duration_mins,
+                    delta_percent,
+                    rate_per_min,
+                })
+            })?
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(OverviewStats {
+            total_flights,
+            total_distance_m: total_distance,
+            total_duration_secs: total_duration,
+            total_points,
+            max_altitude_m: max_altitude,
+            max_distance_from_home_m: max_distance_from_home,
+            batteries_used,
+            drones_used,
+            flights_by_date,
+            top_flights,
+            top_distance_flights,
+            battery_health_points,
+            day_flights,
+            civil_twilight_flights,
+            night_flights,
+        })
+    }
+
+    /// Update the display name for a flight
+    pub fn update_flight_name(
+        &self,
+        flight_id: i64,
+        display_name: &str,
+    ) -> Result<(), DatabaseError> {
+        let conn = self.conn.lock().unwrap();
+
+        conn.execute(
+            "UPDATE flights SET display_name = ? WHERE id = ?",
+            params![display_name, flight_id],
+        )?;
+
+        Ok(())
+    }
+
+    /// Set the notes of a flight; blank notes are cleared
+    pub fn update_flight_notes(&self, flight_id: i64, notes: &str) -> Result<(), DatabaseError> {
+        let conn = self.conn.lock().unwrap();
+
+        let notes = Some(notes.trim()).filter(|n| !n.is_empty());
+        let updated = conn.execute(
+            "UPDATE flights SET notes = ? WHERE id = ?",
+            params![notes, flight_id],
+        )?;
+
+        if updated == 0 {
+            return Err(DatabaseError::FlightNotFound(flight_id));
+        }
+        Ok(())
+    }
+
+    /// Replace the tags of a flight
+    ///
+    /// Tags are trimmed, blank ones dropped and duplicates (ignoring case)
+    /// collapsed. Returns the stored tags, sorted.
+    pub fn set_flight_tags(
+        &self,
+        flight_id: i64,
+        tags: &[String],
+    ) -> Result<Vec<String>, DatabaseError> {
+        let mut conn = self.conn.lock().unwrap();
+        Self::ensure_flight_exists(&conn, flight_id)?;
+
+        let mut wanted: Vec<String> = Vec::new();
+        for tag in tags.iter().map(|t| t.trim()).filter(|t| !t.is_empty()) {
+            if !wanted.iter().any(|w| w.eq_ignore_ascii_case(tag)) {
+                wanted.push(tag.to_string());
+            }
+        }
+        wanted.sort_by_key(|t| t.to_lowercase());
+
+        let tx = conn.transaction()?;
+        let existing: Vec<String> = tx
+            .prepare("SELECT tag FROM flight_tags WHERE flight_id = ?")?
+            .query_map(params![flight_id], |row| row.get(0))?
+            .collect::<Result<_, _>>()?;
+
+        // Only touch changed rows
+        for tag in existing.iter().filter(|t| !wanted.contains(t)) {
+            tx.execute(
+                "DELETE FROM flight_tags WHERE flight_id = ? AND tag = ?",
+                params![flight_id, tag],
+            )?;
+        }
+        for tag in wanted.iter().filter(|t| !existing.contains(t)) {
+            tx.execute(
+                "INSERT INTO flight_tags (flight_id, tag) VALUES (?, ?)",
+                params![flight_id, tag],
+            )?;
+        }
+        tx.commit()?;
+
+        Ok(wanted)
+    }
+
+    /// Every tag in use, sorted, for autocompletion and filtering
+    pub fn get_all_tags(&self) -> Result<Vec<String>, DatabaseError> {
+        let conn = self.conn.lock().unwrap();
+
+        let mut stmt = conn.prepare("SELECT DISTINCT tag FROM flight_tags ORDER BY lower(tag)")?;
+        let tags = stmt
+            .query_map([], |row| row.get(0))?
+            .collect::<Result<Vec<String>, _>>()?;
+
+        Ok(tags)
+    }
+
+    /// Names of the defined custom fields, in creation order
+    pub fn get_custom_fields(&self) -> Result<Vec<String>, DatabaseError> {
+        let conn = self.conn.lock().unwrap();
+
+        let mut stmt = conn.prepare("SELECT name FROM custom_fields ORDER BY created_at, name")?;
+        let names = stmt
+            .query_map([], |row| row.get(0))?
+            .collect::<Result<Vec<String>, _>>()?;
+
+        Ok(names)
+    }
+
+    /// Define a custom field; defining an existing field is a no-op
+    pub fn add_custom_field(&self, name: &str) -> Result<(), DatabaseError> {
+        let conn = self.conn.lock().unwrap();
+
+        let name = name.trim();
+        if name.is_empty() {
+            return Err(DatabaseError::EmptyCustomFieldName);
+        }
+        conn.execute(
+            "INSERT OR IGNORE INTO custom_fields (name) VALUES (?)",
+            params![name],
+        )?;
+
+        Ok(())
+    }
+
+    /// Remove a custom field and its values on every flight
+    pub fn delete_custom_field(&self, name: &str) -> Result<(), DatabaseError> {
+        let mut conn = self.conn.lock().unwrap();
+        let tx = conn.transaction()?;
+
+        tx.execute(
+            "DELETE FROM flight_custom_fields WHERE field = ?",
+            params![name],
+        )?;
+        tx.execute("DELETE FROM custom_fields WHERE name = ?", params![name])?;
+        tx.commit()?;
+
+        Ok(())
+    }
+
+    /// Set a custom field value on a flight; a blank value clears it
+    pub fn set_flight_custom_field(
+        &self,
+        flight_id: i64,
+        field: &str,
+        value: &str,
+    ) -> Result<(), DatabaseError> {
+        let conn = self.conn.lock().unwrap();
+        Self::ensure_flight_exists(&conn, flight_id)?;
+
+        let defined: bool = conn.query_row(
+            "SELECT COUNT(*) > 0 FROM custom_fields WHERE name = ?",
+            params![field],
+            |row| row.get(0),
+        )?;
+        if !defined {
+            return Err(DatabaseError::UnknownCustomField(field.to_string()));
+        }
+
+        let value = value.trim();
+        if value.is_empty() {
+            conn.execute(
+                "DELETE FROM flight_custom_fields WHERE flight_id = ? AND field = ?",
+                params![flight_id, field],
+            )?;
+        } else {
+            conn.execute(
+                "INSERT OR REPLACE INTO flight_custom_fields (flight_id, field, value) VALUES (?, ?, ?)",
+                params![flight_id, field, value],
+            )?;
+        }
+
+        Ok(())
+    }
+
+    fn ensure_flight_exists(conn: &Connection, flight_id: i64) -> Result<(), DatabaseError> {
+        let exists: bool = conn.query_row(
+            "SELECT COUNT(*) > 0 FROM flights WHERE id = ?",
+            params![flight_id],
+            |row| row.get(0),
+        )?;
+        if exists {
+            Ok(())
+        } else {
+            Err(DatabaseError::FlightNotFound(flight_id))
+        }
+    }
+
+    /// Get every battery pack seen in a flight or named in the registry,
+    /// packs in service first
+    pub fn get_batteries(&self) -> Result<Vec<BatteryPack>, DatabaseError> {
+        let conn = self.conn.lock().unwrap();
+
+        let mut packs = Self::battery_registry(&conn, None)?;
+        for (serial, flights) in Self::battery_flights(&conn, None)? {
+            let pack = packs.entry(serial.clone()).or_insert_with(|| BatteryPack {
+                serial,
+                ..Default::default()
+            });
+            battery::summarize(pack, &flights);
+        }
+
+        let mut packs: Vec<BatteryPack> = packs.into_values().collect();
+        packs.sort_by_key(|p| p.retired_at.is_some());
+        Ok(packs)
+    }
+
+    /// Get a battery pack with its per-flight history
+    pub fn get_battery(&self, serial: &str) -> Result<BatteryDetail, DatabaseError> {
+        let conn = self.conn.lock().unwrap();
+
+        Self::ensure_battery_exists(&conn, serial)?;
+
+        let mut pack = Self::battery_registry(&conn, Some(serial))?
+            .remove(serial)
+            .unwrap_or_else(|| BatteryPack {
+                serial: serial.to_string(),
+                ..Default::default()
+            });
+        let flights = Self::battery_flights(&conn, Some(serial))?
+            .remove(serial)
+            .unwrap_or_default();
+        battery::summarize(&mut pack, &flights);
+        Ok(BatteryDetail { pack, flights })
+    }
+
+    /// Name a battery pack; a blank name clears it
+    pub fn rename_battery(&self, serial: &str, name: &str) -> Result<(), DatabaseError> {
+        let conn = self.conn.lock().unwrap();
+        let name = Some(name.trim()).filter(|n| !n.is_empty());
+        Self::upsert_battery(&conn, serial, "name", name)
+    }
+
+    /// Set the notes of a battery pack; blank notes are cleared
+    pub fn update_battery_notes(&self, serial: &str, notes: &str) -> Result<(), DatabaseError> {
+        let conn = self.conn.lock().unwrap();
+        let notes = Some(notes.trim()).filter(|n| !n.is_empty());
+        Self::upsert_battery(&conn, serial, "notes", notes)
+    }
+
+    /// Retire a battery pack, or put it back in service
+    ///
+    /// Retiring an already retired pack keeps the original date.
+    pub fn set_battery_retired(&self, serial: &str, retired: bool) -> Result<(), DatabaseError> {
+        let conn = self.conn.lock().unwrap();
+        let already_retired: bool = conn.query_row(
+            "SELECT COUNT(*) > 0 FROM batteries WHERE serial = ? AND retired_at IS NOT NULL",
+            params![serial],
+            |row| row.get(0),
+        )?;
+        if retired && already_retired {
+            return Ok(());
+        }
+        let retired_at = retired.then(|| chrono::Utc::now().to_rfc3339());
+        Self::upsert_battery(&conn, serial, "retired_at", retired_at.as_deref())
+    }
+
+    /// Set one column of a registry entry, creating the entry if needed
+    fn upsert_battery(
+        conn: &Connection,
+        serial: &str,
+        column: &str,
+        value: Option<&str>,
+    ) -> Result<(), DatabaseError> {
+        Self::ensure_battery_exists(conn, serial)?;
+        conn.execute(
+            &format!(
+                "INSERT INTO batteries (serial, {0}) VALUES (?, ?) ON CONFLICT (serial) DO UPDATE SET {0} = excluded.{0}",
+                column
+            ),
+            params![serial, value],
+        )?;
+        Ok(())
+    }
+
+    /// A battery is known once it is registered or flown in a flight
+    fn ensure_battery_exists(conn: &Connection, serial: &str) -> Result<(), DatabaseError> {
+        let known: bool = conn.query_row(
+            r#"
+            SELECT EXISTS (SELECT 1 FROM batteries WHERE serial = ?1)
+                OR EXISTS (SELECT 1 FROM flights WHERE battery_serial = ?1)
+            "#,
+            params![serial],
+            |row| row.get(0),
+        )?;
+        if !known {
+            return Err(DatabaseError::BatteryNotFound(serial.to_string()));
+        }
+        Ok(())
+    }
+
+    /// Registry entries (name, notes, retirement) by serial
+    fn battery_registry(
+        conn: &Connection,
+        serial: Option<&str>,
+    ) -> Result<BTreeMap<String, BatteryPack>, DatabaseError> {
+        let mut stmt = conn.prepare(
+            r#"
+            SELECT serial, name, notes, CAST(retired_at AS VARCHAR)
+            FROM batteries
+            WHERE ?1 IS NULL OR serial = ?1
+            "#,
+        )?;
+        let packs = stmt
+            .query_map(params![serial], |row| {
+                Ok(BatteryPack {
+                    serial: row.get(0)?,
+                    name: row.get(1)?,
+                    notes: row.get(2)?,
+                    retired_at: row.get(3)?,
+                    ..Default::default()
+                })
+            })?
+            .map(|pack| pack.map(|p| (p.serial.clone(), p)))
+            .collect::<Result<BTreeMap<_, _>, _>>()?;
+        Ok(packs)
+    }
+
+    /// Per-flight battery history of each pack, oldest flight first, with
+    /// cycles and health scores filled in
+    fn battery_flights(
+        conn: &Connection,
+        serial: Option<&str>,
+    ) -> Result<BTreeMap<String, Vec<BatteryFlight>>, DatabaseError> {
+        let mut stmt = conn.prepare(
+            r#"
+            SELECT
+                s.battery_serial,
+                s.flight_id,
+                COALESCE(f.display_name, f.file_name) AS display_name,
+                CAST(f.start_time AS VARCHAR) AS start_time,
+                f.duration_secs,
+                s.discharged_percent,
+                s.min_voltage,
+                s.cell_count,
+                s.min_cell_voltage,
+                s.max_temp,
+                s.internal_resistance_mohm,
+                s.max_cell_imbalance
+            FROM battery_flight_stats s
+            JOIN flights f ON f.id = s.flight_id
+            WHERE ?1 IS NULL OR s.battery_serial = ?1
+            ORDER BY f.start_time ASC NULLS FIRST, f.id ASC
+            "#,
+        )?;
+        let rows = stmt.query_map(params![serial], |row| {
+            Ok((
+                row.get::<_, String>(0)?,
+                BatteryFlight {
+                    flight_id: row.get(1)?,
+                    display_name: row.get(2)?,
+                    start_time: row.get(3)?,
+                    duration_secs: row.get(4)?,
+                    discharged_percent: row.get(5)?,
+                    min_voltage: row.get(6)?,
+                    cell_count: row.get(7)?,
+                    min_cell_voltage: row.get(8)?,
+                    max_temp: row.get(9)?,
+                    internal_resistance_mohm: row.get(10)?,
+                    max_cell_imbalance: row.get(11)?,
+                    ..Default::default()
+                },
+            ))
+        })?;
+
+        let mut histories: BTreeMap<String, Vec<BatteryFlight>> = BTreeMap::new();
+        for row in rows {
+            let (serial, flight) = row?;
+            histories.entry(serial).or_default().push(flight);
+        }
+        for flights in histories.values_mut() {
+            battery::apply_lifecycle(flights);
+        }
+        Ok(histories)
+    }
+
+    /// Get every aircraft seen in a flight or named in the registry, with
+    /// airframe hours, firmware and maintenance status, most flown first
+    pub fn get_aircraft(&self) -> Result<Vec<Aircraft>, DatabaseError> {
+        let conn = self.conn.lock().unwrap();
+        let mut aircraft: Vec<Aircraft> = Self::aircraft_registry(&conn, None)?
+            .into_values()
+            .collect();
+        aircraft.sort_by(|a, b| b.total_duration_secs.total_cmp(&a.total_duration_secs));
+        Ok(aircraft)
+    }
+
+    /// Get one aircraft of the registry
+    pub fn get_aircraft_by_serial(&self, serial: &str) -> Result<Aircraft, DatabaseError> {
+        let conn = self.conn.lock().unwrap();
+        Self::ensure_aircraft_exists(&conn, serial)?;
+        Self::aircraft_registry(&conn, Some(serial))?
+            .remove(serial)
+            .ok_or_else(|| DatabaseError::AircraftNotFound(serial.to_string()))
+    }
+
+    /// Name an aircraft; a blank name clears it
+    pub fn rename_aircraft(&self, serial: &str, name: &str) -> Result<(), DatabaseError> {
+        let conn = self.conn.lock().unwrap();
+        let name = Some(name.trim()).filter(|n| !n.is_empty());
+        Self::upsert_aircraft(&conn, serial, "name", name)
+    }
+
+    /// Set the notes of an aircraft; blank notes are cleared
+    pub fn update_aircraft_notes(&self, serial: &str, notes: &str) -> Result<(), DatabaseError> {
+        let conn = self.conn.lock().unwrap();
+        let notes = Some(notes.trim()).filter(|n| !n.is_empty());
+        Self::upsert_aircraft(&conn, serial, "notes", notes)
+    }
+
+    /// Add a maintenance item to an aircraft, returning its id
+    ///
+    /// At least one positive interval is required. Until the item is marked
+    /// done, hours and flights count from the aircraft's first flight.
+    pub fn add_maintenance_item(
+        &self,
+        serial: &str,
+        name: &str,
+        interval_hours: Option<f64>,
+        interval_flights: Option<i64>,
+    ) -> Result<i64, DatabaseError> {
+        let name = name.trim();
+        if name.is_empty() {
+            return Err(DatabaseError::InvalidMaintenanceItem(
+                "name cannot be empty",
+            ));
+        }
+        if interval_hours.is_some_and(|h| h <= 0.0) || interval_flights.is_some_and(|f| f <= 0) {
+            return Err(DatabaseError::InvalidMaintenanceItem(
+                "intervals must be positive",
+            ));
+        }
+        if interval_hours.is_none() && interval_flights.is_none() {
+            return Err(DatabaseError::InvalidMaintenanceItem(
+                "an hour or flight interval is required",
+            ));
+        }
+
+        let conn = self.conn.lock().unwrap();
+        Self::ensure_aircraft_exists(&conn, serial)?;
+        let id = conn.query_row(
+            r#"
+            INSERT INTO maintenance_items (drone_serial, name, interval_hours, interval_flights)
+            VALUES (?, ?, ?, ?)
+            RETURNING id
+            "#,
+            params![serial, name, interval_hours, interval_flights],
+            |row| row.get(0),
+        )?;
+        Ok(id)
+    }
+
+    /// Record that a maintenance item was done, now or at `done_at`
+    /// (a date or timestamp, e.g. when catching up from a paper log)
+    pub fn complete_maintenance_item(
+        &self,
+        item_id: i64,
+        done_at: Option<&str>,
+    ) -> Result<(), DatabaseError> {
+        let conn = self.conn.lock().unwrap();
+        let updated = conn.execute(
+            r#"
+            UPDATE maintenance_items
+            SET last_done_at = COALESCE(CAST(?1 AS TIMESTAMP WITH TIME ZONE), CURRENT_TIMESTAMP)
+            WHERE id = ?2
+            "#,
+            params![done_at, item_id],
+        )?;
+        if updated == 0 {
+            return Err(DatabaseError::MaintenanceItemNotFound(item_id));
+        }
+        Ok(())
+    }
+
+    /// Remove a maintenance item
+    pub fn delete_maintenance_item(&self, item_id: i64) -> Result<(), DatabaseError> {
+        let conn = self.conn.lock().unwrap();
+        let deleted = conn.execute(
+            "DELETE FROM maintenance_items WHERE id = ?",
+            params![item_id],
+        )?;
+        if deleted == 0 {
+            return Err(DatabaseError::MaintenanceItemNotFound(item_id));
+        }
+        Ok(())
+    }
+
+    /// Set one column of an aircraft registry entry, creating the entry if needed
+    fn upsert_aircraft(
+        conn: &Connection,
+        serial: &str,
+        column: &str,
+        value: Option<&str>,
+    ) -> Result<(), DatabaseError> {
+        Self::ensure_aircraft_exists(conn, serial)?;
+        conn.execute(
+            &format!(
+                "INSERT INTO aircraft (serial, {0}) VALUES (?, ?) ON CONFLICT (serial) DO UPDATE SET {0} = excluded.{0}",
+                column
+            ),
+            params![serial, value],
+        )?;
+        Ok(())
+    }
+
+    /// An aircraft is known once it is registered or flown in a flight
+    fn ensure_aircraft_exists(conn: &Connection, serial: &str) -> Result<(), DatabaseError> {
+        let known: bool = conn.query_row(
+            r#"
+            SELECT EXISTS (SELECT 1 FROM aircraft WHERE serial = ?1)
+                OR EXISTS (SELECT 1 FROM flights WHERE drone_serial = ?1)
+            "#,
+            params![serial],
+            |row| row.get(0),
+        )?;
+        if !known {
+            return Err(DatabaseError::AircraftNotFound(serial.to_string()));
+        }
+        Ok(())
+    }
+
+    /// Registry entries merged with the figures accumulated from flights,
+    /// by serial
+    fn aircraft_registry(
+        conn: &Connection,
+        serial: Option<&str>,
+    ) -> Result<BTreeMap<String, Aircraft>, DatabaseError> {
+        let mut stmt = conn.prepare(
+            r#"
+            WITH flown AS (
+                SELECT
+                    drone_serial AS serial,
+                    ARG_MAX(drone_model, COALESCE(start_time, imported_at))
+                        FILTER (WHERE drone_model IS NOT NULL) AS drone_model,
+                    ARG_MAX(aircraft_name, COALESCE(start_time, imported_at))
+                        FILTER (WHERE aircraft_name IS NOT NULL) AS aircraft_name,
+                    COUNT(*)::BIGINT AS flight_count,
+                    COALESCE(SUM(duration_secs), 0)::DOUBLE AS total_duration_secs,
+                    CAST(MIN(start_time) AS VARCHAR) AS first_flight,
+                    CAST(MAX(start_time) AS VARCHAR) AS last_flight
+                FROM flights
+                WHERE drone_serial IS NOT NULL AND drone_serial <> ''
+                GROUP BY drone_serial
+            )
+            SELECT
+                COALESCE(a.serial, fl.serial) AS serial,
+                a.name, a.notes,
+                fl.drone_model, fl.aircraft_name,
+                COALESCE(fl.flight_count, 0), COALESCE(fl.total_duration_secs, 0),
+                fl.first_flight, fl.last_flight
+            FROM aircraft a
+            FULL OUTER JOIN flown fl ON fl.serial = a.serial
+            WHERE ?1 IS NULL OR COALESCE(a.serial, fl.serial) = ?1
+            "#,
+        )?;
+        let mut aircraft = stmt
+            .query_map(params![serial], |row| {
+                Ok(Aircraft {
+                    serial: row.get(0)?,
+                    name: row.get(1)?,
+                    notes: row.get(2)?,
+                    drone_model: row.get(3)?,
+                    aircraft_name: row.get(4)?,
+                    flight_count: row.get(5)?,
+                    total_duration_secs: row.get(6)?,
+                    first_flight: row.get(7)?,
+                    last_flight: row.get(8)?,
+                    ..Default::default()
+                })
+            })?
+            .map(|a| a.map(|a| (a.serial.clone(), a)))
+            .collect::<Result<BTreeMap<_, _>, _>>()?;
+
+        // Firmware versions in the order they first appeared
+        let mut stmt = conn.prepare(
+            r#"
+            SELECT drone_serial, firmware_version
+            FROM flights
+            WHERE firmware_version IS NOT NULL AND (?1 IS NULL OR drone_serial = ?1)
+            GROUP BY drone_serial, firmware_version
+            ORDER BY drone_serial, MIN(start_time) ASC NULLS FIRST, firmware_version
+            "#,
+        )?;
+        let firmware = stmt.query_map(params![serial], |row| {
+            Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?))
+        })?;
+        for row in firmware {
+            let (serial, version) = row?;
+            if let Some(entry) = aircraft.get_mut(&serial) {
+                entry.firmware_versions.push(version);
+            }
+        }
+
+        for item in Self::maintenance_items(conn, serial)? {
+            if let Some(entry) = aircraft.get_mut(&item.drone_serial) {
+                entry.maintenance.push(item);
+            }
+        }
+        Ok(aircraft)
+    }
+
+    /// Maintenance items with the hours and flights flown since each was
+    /// last done, and their due status
+    fn maintenance_items(
+        conn: &Connection,
+        serial: Option<&str>,
+    ) -> Result<Vec<MaintenanceItem>, DatabaseError> {
+        let mut stmt = conn.prepare(
+            r#"
+            SELECT
+                m.id, m.drone_serial, m.name, m.interval_hours, m.interval_flights,
+                CAST(m.last_done_at AS VARCHAR), m.notes,
+                (COALESCE(SUM(f.duration_secs), 0) / 3600.0)::DOUBLE AS hours_since,
+                COUNT(f.id)::BIGINT AS flights_since
+            FROM maintenance_items m
+            LEFT JOIN flights f
+                ON f.drone_serial = m.drone_serial
+                AND (m.last_done_at IS NULL OR f.start_time > m.last_done_at)
+            WHERE ?1 IS NULL OR m.drone_serial = ?1
+            GROUP BY ALL
+            ORDER BY m.id
+            "#,
+        )?;
+        let items = stmt
+            .query_map(params![serial], |row| {
+                let mut item = MaintenanceItem {
+                    id: row.get(0)?,
+                    drone_serial: row.get(1)?,
+                    name: row.get(2)?,
+                    interval_hours: row.get(3)?,
+                    interval_flights: row.get(4)?,
+                    last_done_at: row.get(5)?,
+                    notes: row.get(6)?,
+                    hours_since: row.get(7)?,
+                    flights_since: row.get(8)?,
+                    ..Default::default()
+                };
+                maintenance::apply_status(&mut item);
+                Ok(item)
+            })?
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(items)
+    }
+
+    /// Get every pilot with flight, night and 90-day totals, by name
+    pub fn get_pilots(&self) -> Result<Vec<Pilot>, DatabaseError> {
+        let conn = self.conn.lock().unwrap();
+
+        let mut stmt = conn.prepare("SELECT id, name, license_number, notes FROM pilots")?;
+        let mut pilots = stmt
+            .query_map([], |row| {
+                Ok(Pilot {
+                    id: row.get(0)?,
+                    name: row.get(1)?,
+                    license_number: row.get(2)?,
+                    notes: row.get(3)?,
+                    ..Default::default()
+                })
+            })?
+            .map(|pilot| pilot.map(|p| (p.id, p)))
+            .collect::<Result<BTreeMap<_, _>, _>>()?;
+
+        let mut stmt = conn
+            .prepare("SELECT pilot_id, drone_serial FROM pilot_aircraft ORDER BY drone_serial")?;
+        let rules = stmt.query_map([], |row| {
+            Ok((row.get::<_, i64>(0)?, row.get::<_, String>(1)?))
+        })?;
+        for rule in rules {
+            let (pilot_id, serial) = rule?;
+            if let Some(pilot) = pilots.get_mut(&pilot_id) {
+                pilot.aircraft_serials.push(serial);
+            }
+        }
+
+        let entries = Self::logbook_entries(&conn, "WHERE f.pilot_id IS NOT NULL", &[])?;
+        let now = chrono::Utc::now();
+        for pilot in pilots.values_mut() {
+            let flown: Vec<&LogbookEntry> = entries
+                .iter()
+                .filter(|e| e.pilot_id == Some(pilot.id))
+                .collect();
+            logbook::tally(pilot, &flown, now);
+        }
+
+        let mut pilots: Vec<Pilot> = pilots.into_values().collect();
+        pilots.sort_by_key(|p| p.name.to_lowercase());
+        Ok(pilots)
+    }
+
+    /// Add a pilot, returning its id
+    pub fn add_pilot(&self, name: &str, license_number: &str) -> Result<i64, DatabaseError> {
+        let name = name.trim();
+        if name.is_empty() {
+            return Err(DatabaseError::EmptyPilotName);
+        }
+        let license_number = Some(license_number.trim()).filter(|l| !l.is_empty());
+
+        let conn = self.conn.lock().unwrap();
+        let id = conn.query_row(
+            "INSERT INTO pilots (name, license_number) VALUES (?, ?) RETURNING id",
+            params![name, license_number],
+            |row| row.get(0),
+        )?;
+        Ok(id)
+    }
+
+    /// Update a pilot's name, licence number and notes; blank optional
+    /// values are cleared
+    pub fn update_pilot(
+        &self,
+        pilot_id: i64,
+        name: &str,
+        license_number: &str,
+        notes: &str,
+    ) -> Result<(), DatabaseError> {
+        let name = name.trim();
+        if name.is_empty() {
+            return Err(DatabaseError::EmptyPilotName);
+        }
+        let license_number = Some(license_number.trim()).filter(|l| !l.is_empty());
+        let notes = Some(notes.trim()).filter(|n| !n.is_empty());
+
+        let conn = self.conn.lock().unwrap();
+        let updated = conn.execute(
+            "UPDATE pilots SET name = ?, license_number = ?, notes = ? WHERE id = ?",
+            params![name, license_number, notes, pilot_id],
+        )?;
+        if updated == 0 {
+            return Err(DatabaseError::PilotNotFound(pilot_id));
+        }
+        Ok(())
+    }
+
+    /// Delete a pilot; their flights become unassigned
+    pub fn delete_pilot(&self, pilot_id: i64) -> Result<(), DatabaseError> {
+        let mut conn = self.conn.lock().unwrap();
+        let tx = conn.transaction()?;
+
+        tx.execute(
+            "UPDATE flights SET pilot_id = NULL WHERE pilot_id = ?",
+            params![pilot_id],
+        )?;
+        tx.execute(
+            "DELETE FROM pilot_aircraft WHERE pilot_id = ?",
+            params![pilot_id],
+        )?;
+        let deleted = tx.execute("DELETE FROM pilots WHERE id = ?", params![pilot_id])?;
+        if deleted == 0 {
+            return Err(DatabaseError::PilotNotFound(pilot_id));
+        }
+
+        tx.commit()?;
+        Ok(())
+    }
+
+    /// Assign a flight to a pilot, or unassign it
+    pub fn set_flight_pilot(
+        &self,
+        flight_id: i64,
+        pilot_id: Option<i64>,
+    ) -> Result<(), DatabaseError> {
+        let conn = self.conn.lock().unwrap();
+        if let Some(pilot_id) = pilot_id {
+            Self::ensure_pilot_exists(&conn, pilot_id)?;
+        }
+
+        let updated = conn.execute(
+            "UPDATE flights SET pilot_id = ? WHERE id = ?",
+            params![pilot_id, flight_id],
+        )?;
+        if updated == 0 {
+            return Err(DatabaseError::FlightNotFound(flight_id));
+        }
+        Ok(())
+    }
+
+    /// Assign flights of an aircraft to a pilot from now on (None removes
+    /// the rule)
+    ///
+    /// Already imported flights of the aircraft without a pilot are assigned
+    /// too; returns how many.
+    pub fn set_aircraft_pilot(
+        &self,
+        serial: &str,
+        pilot_id: Option<i64>,
+    ) -> Result<usize, DatabaseError> {
+        let mut conn = self.conn.lock().unwrap();
+        let tx = conn.transaction()?;
+
+        let Some(pilot_id) = pilot_id else {
+            tx.execute(
+                "DELETE FROM pilot_aircraft WHERE drone_serial = ?",
+                params![serial],
+            )?;
+            tx.commit()?;
+            return Ok(0);
+        };
+
+        Self::ensure_pilot_exists(&tx, pilot_id)?;
+        Self::ensure_aircraft_exists(&tx, serial)?;
+        tx.execute(
+            r#"
+            INSERT INTO pilot_aircraft (drone_serial, pilot_id) VALUES (?, ?)
+            ON CONFLICT (drone_serial) DO UPDATE SET pilot_id = excluded.pilot_id
+            "#,
+            params![serial, pilot_id],
+        )?;
+        let assigned = tx.execute(
+            "UPDATE flights SET pilot_id = ? WHERE drone_serial = ? AND pilot_id IS NULL",
+            params![pilot_id, serial],
+        )?;
+
+        tx.commit()?;
+        Ok(assigned)
+    }
+
+    /// Logbook rows of the flights matching a query, oldest first
+    pub fn get_logbook(&self, query: &FlightQuery) -> Result<Vec<LogbookEntry>, DatabaseError> {
+        let conn = self.conn.lock().unwrap();
+        let (where_clause, values) = Self::flight_filter(query);
+        Self::logbook_entries(&conn, &where_clause, &values)
+    }
+
+    fn ensure_pilot_exists(conn: &Connection, pilot_id: i64) -> Result<(), DatabaseError> {
+        let exists: bool = conn.query_row(
+            "SELECT COUNT(*) > 0 FROM pilots WHERE id = ?",
+            params![pilot_id],
+            |row| row.get(0),
+        )?;
+        if !exists {
+            return Err(DatabaseError::PilotNotFound(pilot_id));
+        }
+        Ok(())
+    }
+
+    /// Logbook rows of the flights matching a WHERE clause on `flights f`
+    fn logbook_entries(
+        conn: &Connection,
+        where_clause: &str,
+        values: &[Value],
+    ) -> Result<Vec<LogbookEntry>, DatabaseError> {
+        let mut stmt = conn.prepare(&format!(
+            r#"
+            SELECT
+                f.id, COALESCE(f.display_name, f.file_name) AS display_name,
+                f.pilot_id, p.name AS pilot_name,
+                f.drone_model, f.drone_serial, COALESCE(a.name, f.aircraft_name) AS aircraft_name,
+                epoch_ms(f.start_time) AS takeoff_ms,
+                COALESCE(f.duration_secs, 0)::DOUBLE AS duration_secs,
+                f.home_lat, f.home_lon, f.max_altitude, f.total_distance, f.notes,
+                epoch_ms(f.end_time) AS landing_ms, f.daylight
+            FROM flights f
+            LEFT JOIN pilots p ON p.id = f.pilot_id
+            LEFT JOIN aircraft a ON a.serial = f.drone_serial
+            {}
+            ORDER BY f.start_time ASC NULLS FIRST, f.id ASC
+            "#,
+            where_clause
+        ))?;
+
+        let entries = stmt
+            .query_map(duckdb::params_from_iter(values.iter()), |row| {
+                let to_time = |ms: Option<i64>| {
+                    ms.and_then(|ms| chrono::Utc.timestamp_millis_opt(ms).single())
+                };
+                let takeoff_time = to_time(row.get(7)?);
+                let duration_secs: f64 = row.get(8)?;
+                let landing_time = to_time(row.get(14)?).or_else(|| {
+                    takeoff_time.map(|t| {
+                        t + chrono::Duration::milliseconds((duration_secs * 1000.0) as i64)
+                    })
+                });
+
+                Ok(LogbookEntry {
+                    flight_id: row.get(0)?,
+                    display_name: row.get(1)?,
+                    pilot_id: row.get(2)?,
+                    pilot_name: row.get(3)?,
+                    drone_model: row.get(4)?,
+                    drone_serial: row.get(5)?,
+                    aircraft_name: row.get(6)?,
+                    takeoff_time,
+                    landing_time,
+                    duration_secs,
+                    home_lat: row.get(9)?,
+                    home_lon: row.get(10)?,
+                    max_altitude: row.get(11)?,
+                    total_distance: row.get(12)?,
+                    daylight: row
+                        .get::<_, Option<String>>(15)?
+                        .as_deref()
+                        .and_then(Daylight::from_db),
+                    notes: row.get(13)?,
+                })
+            })?
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(entries)
+    }
+
+    /// Check if a file has already been imported (by hash)
+    pub fn is_file_imported(&self, file_hash: &str) -> Result<bool, DatabaseError> {
+        let conn = self.conn.lock().unwrap();
+
+        let count: i64 = conn.query_row(
+            "SELECT COUNT(*) FROM flights WHERE file_hash = ?",
+            params![file_hash],
+            |row| row.get(0),
+        )?;
+
+        Ok(count > 0)
+    }
+
+    /// Store fetched V13+ keychains (serialized as JSON) under a cache key
+    pub fn store_keychain(
+        &self,
+        cache_key: &str,
+        keychains_json: &str,
+    ) -> Result<(), DatabaseError> {
+        let conn = self.conn.lock().unwrap();
+
+        conn.execute(
+            r#"
+            INSERT INTO keychains (serial_number, encryption_key)
+            VALUES (?, ?)
+            ON CONFLICT (serial_number) DO UPDATE SET 
+                encryption_key = excluded.encryption_key,
+                fetched_at = now()
+            "#,
+            params![cache_key, keychains_json],
+        )?;
+
+        Ok(())
+    }
+
+    /// Retrieve cached keychains JSON for a cache key
+    pub fn get_keychain(&self, cache_key: &str) -> Result<Option<String>, DatabaseError> {
+        let conn = self.conn.lock().unwrap();
+
+        let result = conn.query_row(
+            "SELECT encryption_key FROM keychains WHERE serial_number = ?",
+            params![cache_key],
+            |row| row.get::<_, String>(0),
+        );
+
+        match result {
+            Ok(key) => Ok(Some(key)),
+            Err(duckdb::Error::QueryReturnedNoRows) => Ok(None),
+            Err(e) => Err(e.into()),
+        }
+    }
+}
+
+/// LIKE pattern matching `text` anywhere, with its wildcards taken literally
+/// (used with `ESCAPE '\'`)
+fn contains_pattern(text: &str) -> String {
+    let escaped = text
+        .replace('\\', "\\\\")
+        .replace('%', "\\%")
+        .replace('_', "\\_");
+    format!("%{}%", escaped)
+}
+
+/// Quote a path as a SQL string literal (COPY targets cannot be bound parameters)
+fn sql_path(path: &Path) -> String {
+    format!("'{}'", path.to_string_lossy().replace('\'', "''"))
+}
+
+/// `read_parquet(...)` expression for a table of a Parquet export, single
+/// file or hive-partitioned directory; `None` if the export has no rows for it
+fn parquet_source(dir: &Path, table: &str) -> Result<Option<String>, DatabaseError> {
+    let file = dir.join(format!("{}.parquet", table));
+    if file.is_file() {
+        return Ok(Some(format!("read_parquet({})", sql_path(&file))));
+    }
+
+    let partitioned = dir.join(table);
+    if partitioned.is_dir() && fs::read_dir(&partitioned)?.next().is_some() {
+        let pattern = partitioned.join("**").join("*.parquet");
+        return Ok(Some(format!(
+            "read_parquet({}, hive_partitioning = true)",
+            sql_path(&pattern)
+        )));
+    }
+
+    Ok(None)
+}
+
+/// DuckDB list literal for a pack's cell voltages, NULL when none are
+/// reported; the Appender casts it to the `DOUBLE[]` column, since duckdb-rs
+/// cannot append list values
+fn cell_voltages_literal(cells: &[f64]) -> Option<String> {
+    if cells.is_empty() {
+        return None;
+    }
+    let values: Vec<String> = cells.iter().map(|v| v.to_string()).collect();
+    Some(format!("[{}]", values.join(", ")))
+}
+
+/// Cell voltages from a `DOUBLE[]` column, empty when NULL
+fn cell_voltages(row: &duckdb::Row<'_>, idx: usize) -> DuckResult<Vec<f64>> {
+    let invalid = || {
+        duckdb::Error::FromSqlConversionFailure(
+            idx,
+            Type::List(Box::new(Type::Double)),
+            "expected a list of cell voltages".into(),
+        )
+    };
+
+    match row.get::<_, Value>(idx)? {
+        Value::Null => Ok(Vec::new()),
+        Value::List(items) => items
+            .into_iter()
+            .map(|item| match item {
+                Value::Double(volts) => Ok(volts),
+                _ => Err(invalid()),
+            })
+            .collect(),
+        _ => Err(invalid()),
+    }
+}
+
+/// Columns of `table` that are also present in `source`, in table order
+///
+/// Lets exports from older or newer versions import whatever overlaps.
+fn shared_columns(
+    conn: &Connection,
+    table: &str,
+    source: &str,
+) -> Result<Vec<String>, DatabaseError> {
+    let mut stmt = conn.prepare(&format!("DESCRIBE SELECT * FROM {}", source))?;
+    let available: HashSet<String> = stmt
+        .query_map([], |row| row.get::<_, String>(0))?
+        .collect::<Result<_, _>>()?;
+
+    let mut stmt = conn.prepare(&format!("PRAGMA table_info('{}')", table))?;
+    let columns = stmt
+        .query_map([], |row| row.get::<_, String>(1))?
+        .collect::<Result<Vec<_>, _>>()?;
+
+    Ok(columns
+        .into_iter()
+        .filter(|c| available.contains(c))
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{GeoBounds, MaintenanceStatus};
+    use crate::test_support::sample_flight;
+    use chrono::{TimeZone, Utc};
+    use tempfile::tempdir;
+
+    /// Telemetry columns in schema order; the Appender writes rows in this order
+    const TELEMETRY_COLUMNS: &[&str] = &[
+        "flight_id",
+        "timestamp_ms",
+        "latitude",
+        "longitude",
+        "altitude",
+        "height",
+        "vps_height",
+        "altitude_abs",
+        "speed",
+        "velocity_x",
+        "velocity_y",
+        "velocity_z",
+        "pitch",
+        "roll",
+        "yaw",
+        "gimbal_pitch",
+        "gimbal_roll",
+        "gimbal_yaw",
+        "battery_percent",
+        "battery_voltage",
+        "battery_current",
+        "battery_temp",
+        "flight_mode",
+        "gps_signal",
+        "satellites",
+        "rc_signal",
+        "rc_uplink",
+        "rc_downlink",
+        "battery_cell_voltages",
+        "battery_full_capacity",
+        "battery_remaining_capacity",
+        "battery_voltage_warning",
+        "battery_cells_estimated",
+    ];
+
+    #[test]
+    fn test_database_initialization() {
+        let temp_dir = tempdir().unwrap();
+        let db = Database::new(temp_dir.path().to_path_buf()).unwrap();
+
+        // Verify directories were created
+        assert!(temp_dir.path().join("raw_logs").exists());
+        assert!(temp_dir.path().join("keychains").exists());
+        assert!(temp_dir.path().join("flights.db").exists());
+
+        // Verify we can get flights (empty)
+        let flights = db.get_all_flights().unwrap();
+        assert!(flights.is_empty());
+    }
+
+    fn sample_points() -> Vec<TelemetryPoint> {
+        (0..2)
+            .map(|i| TelemetryPoint {
+                timestamp_ms: i * 100,
+                latitude: Some(47.0),
+                longitude: Some(8.0),
+                ..Default::default()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_insert_flight_with_telemetry_is_atomic() {
+        let temp_dir = tempdir().unwrap();
+        let db = Database::new(temp_dir.path().to_path_buf()).unwrap();
+
+        let (flight_id, inserted) = db
+            .insert_flight_with_telemetry(
+                &sample_flight(1, "hash-a"),
+                &sample_points(),
+                &FlightEvents::default(),
+            )
+            .unwrap();
+        assert_eq!(flight_id, 1);
+        assert_eq!(inserted, 2);
+
+        // Duplicate file hash violates the unique constraint: nothing is stored
+        let result = db.insert_flight_with_telemetry(
+            &sample_flight(2, "hash-a"),
+            &sample_points(),
+            &FlightEvents::default(),
+        );
+        assert!(result.is_err());
+
+        let flights = db.get_all_flights().unwrap();
+        assert_eq!(flights.len(), 1);
+        assert!(db.get_flight_points(2).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_parallel_inserts_get_distinct_ids() {
+        let temp_dir = tempdir().unwrap();
+        let db = std::sync::Arc::new(Database::new(temp_dir.path().to_path_buf()).unwrap());
+
+        let handles: Vec<_> = (0..8)
+            .map(|n| {
+                let db = db.clone();
+                std::thread::spawn(move || {
+                    db.insert_flight_with_telemetry(
+                        &sample_flight(n, &format!("hash-{}", n)),
+                        &sample_points(),
+                        &FlightEvents::default(),
+                    )
+                    .unwrap()
+                    .0
+                })
+            })
+            .collect();
+
+        let ids: HashSet<i64> = handles.into_iter().map(|h| h.join().unwrap()).collect();
+        assert_eq!(ids.len(), 8);
+        assert_eq!(db.get_all_flights().unwrap().len(), 8);
+    }
+
+    #[test]
+    fn test_camera_events_replaced_on_reprocess() {
+        let temp_dir = tempdir().unwrap();
+        let db = Database::new(temp_dir.path().to_path_buf()).unwrap();
+
+        let photo = |timestamp_ms| CameraEvent {
+            timestamp_ms,
+            event_type: CameraEvent::PHOTO.to_string(),
+            latitude: Some(47.0),
+            longitude: Some(8.0),
+            altitude: Some(30.0),
+        };
+        let events = FlightEvents {
+            camera: vec![photo(100), photo(0)],
+            ..Default::default()
+        };
+
+        let flight = sample_flight(1, "hash-a");
+        db.insert_flight_with_telemetry(&flight, &sample_points(), &events)
+            .unwrap();
+
+        let stored = db.get_camera_events(1).unwrap();
+        assert_eq!(stored.len(), 2);
+        assert_eq!(stored[0].timestamp_ms, 0);
+
+        let events = FlightEvents {
+            camera: vec![photo(50)],
+            ..Default::default()
+        };
+        db.replace_flight_data(1, &flight, &sample_points(), &events)
+            .unwrap();
+        assert_eq!(db.get_camera_events(1).unwrap().len(), 1);
+
+        db.delete_flight(1).unwrap();
+        assert!(db.get_camera_events(1).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_distance_from_home_uses_recorded_home_point() {
+        let temp_dir = tempdir().unwrap();
+        let db = Database::new(temp_dir.path().to_path_buf()).unwrap();
+
+        // Home point recorded ~76m east of the telemetry points
+        let events = FlightEvents {
+            home: vec![HomeEvent {
+                timestamp_ms: 0,
+                event_type: HomeEvent::HOME_SET.to_string(),
+                latitude: Some(47.0),
+                longitude: Some(8.001),
+                altitude: None,
+                detail: None,
+            }],
+            ..Default::default()
+        };
+        db.insert_flight_with_telemetry(&sample_flight(1, "hash-a"), &sample_points(), &events)
+            .unwrap();
+
+        let stats = db.get_overview_stats(&FlightQuery::default()).unwrap();
+        assert!((stats.max_distance_from_home_m - 76.0).abs() < 1.0);
+    }
+
+    #[test]
+    fn test_overview_stats_apply_filters_to_every_aggregate() {
+        let temp_dir = tempdir().unwrap();
+        let db = Database::new(temp_dir.path().to_path_buf()).unwrap();
+
+        // Homes ~76m and ~760m east of the telemetry points
+        for (n, battery, home_lon) in [(1, "BAT-A", 8.001), (2, "BAT-B", 8.01)] {
+            let mut flight = sample_flight(n, &format!("hash-{}", n));
+            flight.battery_serial = Some(battery.to_string());
+            flight.home_lat = Some(47.0);
+            flight.home_lon = Some(home_lon);
+            flight.total_distance = Some(100.0 * n as f64);
+            let points: Vec<TelemetryPoint> = sample_points()
+                .into_iter()
+                .map(|p| TelemetryPoint {
+                    battery_percent: Some(90 - p.timestamp_ms as i32 / 10),
+                    ..p
+                })
+                .collect();
+            db.insert_flight_with_telemetry(&flight, &points, &FlightEvents::default())
+                .unwrap();
+        }
+
+        let all = db.get_overview_stats(&FlightQuery::default()).unwrap();
+        assert_eq!(all.total_flights, 2);
+        assert!((all.max_distance_from_home_m - 760.0).abs() < 10.0);
+
+        let stats = db
+            .get_overview_stats(&FlightQuery {
+                battery_serial: Some("BAT-A".into()),
+                ..Default::default()
+            })
+            .unwrap();
+        assert_eq!(stats.total_flights, 1);
+        assert_eq!(stats.total_distance_m, 100.0);
+        assert!((stats.max_distance_from_home_m - 76.0).abs() < 1.0);
+        assert_eq!(stats.batteries_used.len(), 1);
+        assert_eq!(stats.top_distance_flights.len(), 1);
+        assert_eq!(stats.battery_health_points.len(), 1);
+        assert_eq!(stats.battery_health_points[0].battery_serial, "BAT-A");
+    }
+
+    #[test]
+    fn test_battery_registry_tracks_cycles_and_edits() {
+        let temp_dir = tempdir().unwrap();
+        let db = Database::new(temp_dir.path().to_path_buf()).unwrap();
+
+        // 90% -> 60% in 5-sample steps, sagging 20 mOhm under alternating load
+        let points: Vec<TelemetryPoint> = (0..20)
+            .map(|i| {
+                let current = if i % 2 == 0 { 5.0 } else { 10.0 };
+                TelemetryPoint {
+                    timestamp_ms: i * 100,
+                    battery_percent: Some(90 - (i as i32 / 5) * 10),
+                    battery_voltage: Some(16.0 - 0.02 * current),
+                    battery_current: Some(current),
+                    ..Default::default()
+                }
+            })
+            .collect();
+        let mut ids = Vec::new();
+        for n in 1..=2 {
+            let mut flight = sample_flight(n, &format!("hash-{}", n));
+            flight.battery_serial = Some("BAT-A".to_string());
+            ids.push(
+                db.insert_flight_with_telemetry(&flight, &points, &FlightEvents::default())
+                    .unwrap()
+                    .0,
+            );
+        }
+
+        let detail = db.get_battery("BAT-A").unwrap();
+        assert_eq!(detail.flights.len(), 2);
+        assert!((detail.pack.cycles - 0.6).abs() < 1e-9);
+        assert_eq!(detail.flights[0].cell_count, Some(4));
+        let resistance = detail.pack.internal_resistance_mohm.unwrap();
+        assert!((resistance - 20.0).abs() < 0.1);
+
+        db.rename_battery("BAT-A", "  Pack 1 ").unwrap();
+        db.set_battery_retired("BAT-A", true).unwrap();
+        let packs = db.get_batteries().unwrap();
+        assert_eq!(packs.len(), 1);
+        assert_eq!(packs[0].name.as_deref(), Some("Pack 1"));
+        assert!(packs[0].retired_at.is_some());
+
+        db.delete_flight(ids[1]).unwrap();
+        let detail = db.get_battery("BAT-A").unwrap();
+        assert_eq!(detail.flights.len(), 1);
+        assert!((detail.pack.cycles - 0.3).abs() < 1e-9);
+
+        assert!(matches!(
+            db.rename_battery("UNKNOWN", "x"),
+            Err(DatabaseError::BatteryNotFound(_))
+        ));
+    }
+
+    #[test]
+    fn test_aircraft_registry_tracks_hours_firmware_and_maintenance() {
+        let temp_dir = tempdir().unwrap();
+        let db = Database::new(temp_dir.path().to_path_buf()).unwrap();
+
+        // Three half-hour flights on May 1-3, firmware updated before the last
+        for n in 1..=3 {
+            let mut flight = sample_flight(n, &format!("hash-{}", n));
+            flight.drone_serial = Some("AC-1".to_string());
+            flight.drone_model = Some("Mavic3".to_string());
+            flight.firmware_version = Some(if n < 3 { "01.00" } else { "01.01" }.to_string());
+            flight.start_time = Utc.with_ymd_and_hms(2024, 5, n as u32, 12, 0, 0).single();
+            flight.duration_secs = Some(1800.0);
+            db.insert_flight_with_telemetry(&flight, &sample_points(), &FlightEvents::default())
+                .unwrap();
+        }
+
+        let props = db
+            .add_maintenance_item("AC-1", "Propellers", Some(1.0), None)
+            .unwrap();
+        let imu = db
+            .add_maintenance_item("AC-1", "IMU calibration", None, Some(10))
+            .unwrap();
+        db.rename_aircraft("AC-1", " Survey bird ").unwrap();
+
+        let aircraft = db.get_aircraft().unwrap();
+        assert_eq!(aircraft.len(), 1);
+        let ac = &aircraft[0];
+        assert_eq!(ac.name.as_deref(), Some("Survey bird"));
+        assert_eq!(ac.drone_model.as_deref(), Some("Mavic3"));
+        assert_eq!(ac.flight_count, 3);
+        assert_eq!(ac.total_duration_secs, 5400.0);
+        assert_eq!(ac.firmware_versions, ["01.00", "01.01"]);
+        assert_eq!(ac.maintenance[0].status, MaintenanceStatus::Overdue);
+        assert_eq!(ac.maintenance[1].flights_remaining, Some(7));
+
+        // Only the flight after the service counts towards the next one
+        db.complete_maintenance_item(props, Some("2024-05-02 18:00:00+00"))
+            .unwrap();
+        let ac = db.get_aircraft_by_serial("AC-1").unwrap();
+        assert_eq!(ac.maintenance[0].flights_since, 1);
+        assert!((ac.maintenance[0].hours_since - 0.5).abs() < 1e-9);
+        assert_eq!(ac.maintenance[0].status, MaintenanceStatus::Ok);
+
+        db.delete_maintenance_item(imu).unwrap();
+        assert_eq!(
+            db.get_aircraft_by_serial("AC-1").unwrap().maintenance.len(),
+            1
+        );
+
+        assert!(matches!(
+            db.add_maintenance_item("AC-1", "Motors", None, None),
+            Err(DatabaseError::InvalidMaintenanceItem(_))
+        ));
+        assert!(matches!(
+            db.add_maintenance_item("UNKNOWN", "Motors", Some(10.0), None),
+            Err(DatabaseError::AircraftNotFound(_))
+        ));
+        assert!(matches!(
+            db.complete_maintenance_item(imu, None),
+            Err(DatabaseError::MaintenanceItemNotFound(_))
+        ));
+    }
+
+    #[test]
+    fn test_pilots_assigned_by_rule_and_totalled() {
+        let temp_dir = tempdir().unwrap();
+        let db = Database::new(temp_dir.path().to_path_buf()).unwrap();
+
+        // Zurich, 2024-12-01: a day flight at 14:00 UTC and a night one at 16:40
+        let import = |n: i64, serial: &str, hour: u32, minute: u32| {
+            let mut flight = sample_flight(n, &format!("hash-{}", n));
+            flight.drone_serial = Some(serial.to_string());
+            flight.start_time = Utc.with_ymd_and_hms(2024, 12, 1, hour, minute, 0).single();
+            flight.duration_secs = Some(600.0);
+            flight.home_lat = Some(47.37);
+            flight.home_lon = Some(8.54);
+            db.insert_flight_with_telemetry(&flight, &sample_points(), &FlightEvents::default())
+                .unwrap()
+                .0
+        };
+
+        let ann = db.add_pilot(" Ann ", "CH-RP-123").unwrap();
+        let bob = db.add_pilot("Bob", "").unwrap();
+
+        import(1, "AC-1", 14, 0);
+        assert_eq!(db.set_aircraft_pilot("AC-1", Some(ann)).unwrap(), 1);
+        import(2, "AC-1", 16, 40);
+        let other = import(3, "AC-2", 14, 0);
+        db.set_flight_pilot(other, Some(bob)).unwrap();
+
+        let pilots = db.get_pilots().unwrap();
+        assert_eq!(pilots.len(), 2);
+        assert_eq!(pilots[0].name, "Ann");
+        assert_eq!(pilots[0].aircraft_serials, ["AC-1"]);
+        assert_eq!(pilots[0].flight_count, 2);
+        assert_eq!(pilots[0].night_flights, 1);
+        assert_eq!(pilots[0].total_duration_secs, 1200.0);
+        assert_eq!(pilots[1].flight_count, 1);
+
+        let query = FlightQuery {
+            pilot_id: Some(ann),
+            ..Default::default()
+        };
+        let page = db.query_flights(&query).unwrap();
+        assert_eq!(page.total_count, 2);
+        assert_eq!(page.flights[0].pilot_name.as_deref(), Some("Ann"));
+
+        let logbook = db.get_logbook(&query).unwrap();
+        assert_eq!(
+            logbook.iter().map(|e| e.daylight).collect::<Vec<_>>(),
+            [Some(Daylight::Day), Some(Daylight::Night)]
+        );
+
+        // Deleting a pilot unassigns their flights and drops their rules
+        db.delete_pilot(ann).unwrap();
+        let page = db.query_flights(&FlightQuery::default()).unwrap();
+        assert_eq!(
+            page.flights.iter().filter(|f| f.pilot_id.is_some()).count(),
+            1
+        );
+        assert!(matches!(
+            db.set_flight_pilot(other, Some(ann)),
+            Err(DatabaseError::PilotNotFound(_))
+        ));
+        assert!(matches!(
+            db.add_pilot("  ", ""),
+            Err(DatabaseError::EmptyPilotName)
+        ));
+    }
+
+    #[test]
+    fn test_daylight_classified_filtered_and_backfilled() {
+        let temp_dir = tempdir().unwrap();
+        let db = Database::new(temp_dir.path().to_path_buf()).unwrap();
+
+        // Zurich, 2024-12-01: sunset ~15:40 UTC, civil dusk ~16:15
+        for (n, minute) in [(1, 14 * 60), (2, 15 * 60 + 50), (3, 16 * 60 + 40)] {
+            let mut flight = sample_flight(n, &format!("hash-{}", n));
+            let start = Utc.with_ymd_and_hms(2024, 12, 1, 0, 0, 0).unwrap()
+                + chrono::Duration::minutes(minute);
+            flight.start_time = Some(start);
+            flight.end_time = Some(start + chrono::Duration::minutes(5));
+            flight.home_lat = Some(47.37);
+            flight.home_lon = Some(8.54);
+            db.insert_flight_with_telemetry(&flight, &sample_points(), &FlightEvents::default())
+                .unwrap();
+        }
+        // No home point: unknown
+        db.insert_flight_with_telemetry(
+            &sample_flight(4, "hash-4"),
+            &sample_points(),
+            &FlightEvents::default(),
+        )
+        .unwrap();
+
+        let daylight = |query: FlightQuery| -> Vec<Option<Daylight>> {
+            let mut flights = db.query_flights(&query).unwrap().flights;
+            flights.sort_by_key(|f| f.id);
+            flights.iter().map(|f| f.daylight).collect()
+        };
+        assert_eq!(
+            daylight(FlightQuery::default()),
+            [
+                Some(Daylight::Day),
+                Some(Daylight::CivilTwilight),
+                Some(Daylight::Night),
+                None
+            ]
+        );
+        assert_eq!(
+            daylight(FlightQuery {
+                daylight: Some(Daylight::Night),
+                ..Default::default()
+            }),
+            [Some(Daylight::Night)]
+        );
+
+        let stats = db.get_overview_stats(&FlightQuery::default()).unwrap();
+        assert_eq!(
+            (
+                stats.day_flights,
+                stats.civil_twilight_flights,
+                stats.night_flights
+            ),
+            (1, 1, 1)
+        );
+
+        // Flights stored before the classification existed are backfilled
+        let conn = db.conn.lock().unwrap();
+        conn.execute("UPDATE flights SET daylight = NULL", [])
+            .unwrap();
+        Database::refresh_daylight(&conn, "TRUE").unwrap();
+        let night: i64 = conn
+            .query_row(
+                "SELECT COUNT(*) FROM flights WHERE daylight = 'night'",
+                [],
+                |row| row.get(0),
+            )
+            .unwrap();
+        assert_eq!(night, 1);
+    }
+
+    #[test]
+    fn test_cell_voltages_stored_and_imbalance_derived() {
+        let temp_dir = tempdir().unwrap();
+        let db = Database::new(temp_dir.path().to_path_buf()).unwrap();
+
+        let mut flight = sample_flight(1, "hash-a");
+        flight.battery_serial = Some("BAT-A".to_string());
+        let points: Vec<TelemetryPoint> = sample_points()
+            .into_iter()
+            .enumerate()
+            .map(|(i, p)| TelemetryPoint {
+                battery_voltage: Some(15.3),
+                battery_cell_voltages: if i == 0 {
+                    vec![3.85, 3.8, 3.85, 3.8]
+                } else {
+                    vec![3.8; 4]
+                },
+                battery_full_capacity: Some(5000),
+                battery_remaining_capacity: Some(4000 - 100 * i as i32),
+                battery_voltage_warning: Some(0),
+                ..p
+            })
+            .collect();
+        let (flight_id, _) = db
+            .insert_flight_with_telemetry(&flight, &points, &FlightEvents::default())
+            .unwrap();
+
+        let stored = db.get_flight_points(flight_id).unwrap();
+        assert_eq!(stored[0].battery_cell_voltages, [3.85, 3.8, 3.85, 3.8]);
+        assert_eq!(stored[1].battery_remaining_capacity, Some(3900));
+
+        let conn = db.conn.lock().unwrap();
+        let records = db.query_raw_telemetry(&conn, flight_id).unwrap();
+        let imbalance: Vec<f64> = records
+            .iter()
+            .filter_map(|r| r.battery_cell_imbalance)
+            .collect();
+        assert_eq!(imbalance.len(), 2);
+        assert!((imbalance[0] - 0.05).abs() < 1e-9);
+        drop(conn);
+
+        let detail = db.get_battery("BAT-A").unwrap();
+        assert_eq!(detail.flights[0].cell_count, Some(4));
+        assert_eq!(detail.flights[0].min_cell_voltage, Some(3.8));
+        assert!((detail.flights[0].max_cell_imbalance.unwrap() - 0.05).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_query_flight_events_across_flights() {
+        let temp_dir = tempdir().unwrap();
+        let db = Database::new(temp_dir.path().to_path_buf()).unwrap();
+
+        let event = |severity: &str, category: &str, message: &str| FlightEvent {
+            timestamp_ms: 0,
+            severity: severity.to_string(),
+            category: category.to_string(),
+            message: message.to_string(),
+        };
+        for (id, hash) in [(1, "hash-a"), (2, "hash-b")] {
+            let events = FlightEvents {
+                messages: vec![
+                    event(
+                        FlightEvent::WARNING,
+                        "battery",
+                        "Low battery. Returning home",
+                    ),
+                    event(FlightEvent::INFO, "weather", "Strong wind"),
+                ],
+                ..Default::default()
+            };
+            db.insert_flight_with_telemetry(&sample_flight(id, hash), &sample_points(), &events)
+                .unwrap();
+        }
+
+        assert_eq!(db.get_flight_events(1).unwrap().len(), 2);
+
+        let warnings = db
+            .query_flight_events(&FlightEventQuery {
+                severity: Some(FlightEvent::WARNING.to_string()),
+                ..Default::default()
+            })
+            .unwrap();
+        assert_eq!(warnings.len(), 2);
+
+        let wind = db
+            .query_flight_events(&FlightEventQuery {
+                search: Some("WIND".to_string()),
+                limit: Some(1),
+                ..Default::default()
+            })
+            .unwrap();
+        assert_eq!(wind.len(), 1);
+        assert_eq!(wind[0].event.category, "weather");
+    }
+
+    #[test]
+    fn test_notes_tags_and_custom_fields_filter_flights() {
+        let temp_dir = tempdir().unwrap();
+        let db = Database::new(temp_dir.path().to_path_buf()).unwrap();
+        let ids: Vec<i64> = [(1, "hash-a"), (2, "hash-b")]
+            .into_iter()
+            .map(|(n, hash)| {
+                db.insert_flight_with_telemetry(
+                    &sample_flight(n, hash),
+                    &sample_points(),
+                    &FlightEvents::default(),
+                )
+                .unwrap()
+                .0
+            })
+            .collect();
+
+        db.update_flight_notes(ids[0], "  Roof inspection, north side  ")
+            .unwrap();
+        let tags = db
+            .set_flight_tags(
+                ids[0],
+                &[
+                    "job-42".to_string(),
+                    " Survey".to_string(),
+                    "JOB-42".to_string(),
+                ],
+            )
+            .unwrap();
+        assert_eq!(tags, ["job-42", "Survey"]);
+        db.set_flight_tags(ids[1], &["survey".to_string()]).unwrap();
+
+        db.add_custom_field("Client").unwrap();
+        db.set_flight_custom_field(ids[0], "Client", "ACME Roofing")
+            .unwrap();
+        assert!(matches!(
+            db.set_flight_custom_field(ids[0], "Invoice", "7"),
+            Err(DatabaseError::UnknownCustomField(_))
+        ));
+
+        let find = |query: FlightQuery| -> Vec<i64> {
+            db.query_flights(&query)
+                .unwrap()
+                .flights
+                .iter()
+                .map(|f| f.id)
+                .collect()
+        };
+        assert_eq!(
+            find(FlightQuery {
+                search: Some("north".into()),
+                ..Default::default()
+            }),
+            [ids[0]]
+        );
+        assert_eq!(
+            find(FlightQuery {
+                search: Some("acme".into()),
+                ..Default::default()
+            }),
+            [ids[0]]
+        );
+        // Wildcards in the search text match literally
+        assert!(find(FlightQuery {
+            search: Some("%".into()),
+            ..Default::default()
+        })
+        .is_empty());
+        assert_eq!(
+            find(FlightQuery {
+                tags: vec!["SURVEY".into()],
+                ..Default::default()
+            })
+            .len(),
+            2
+        );
+        assert_eq!(
+            find(FlightQuery {
+                tags: vec!["survey".into(), "job-42".into()],
+                ..Default::default()
+            }),
+            [ids[0]]
+        );
+        assert_eq!(
+            find(FlightQuery {
+                custom_fields: [("Client".to_string(), "acme roofing".to_string())].into(),
+                ..Default::default()
+            }),
+            [ids[0]]
+        );
+
+        let flight = db.get_flight(ids[0]).unwrap();
+        assert_eq!(flight.notes.as_deref(), Some("Roof inspection, north side"));
+        assert_eq!(
+            flight.custom_fields.get("Client").map(String::as_str),
+            Some("ACME Roofing")
+        );
+
+        // Removing a field clears its values; tags go with their flight
+        db.delete_custom_field("Client").unwrap();
+        db.delete_flight(ids[0]).unwrap();
+        assert_eq!(db.get_all_tags().unwrap(), ["survey"]);
+        assert!(db.get_custom_fields().unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_query_flights_filters_sorts_and_pages() {
+        let temp_dir = tempdir().unwrap();
+        let db = Database::new(temp_dir.path().to_path_buf()).unwrap();
+        // Flight n starts on day n, lasts n minutes and flies n km from (47, 8 + n)
+        for n in 1..=5 {
+            let mut flight = sample_flight(n, &format!("hash-{}", n));
+            flight.start_time = Utc.with_ymd_and_hms(2024, 5, n as u32, 12, 0, 0).single();
+            flight.duration_secs = Some(60.0 * n as f64);
+            flight.total_distance = Some(1000.0 * n as f64);
+            flight.battery_serial = Some(if n % 2 == 0 { "EVEN" } else { "ODD" }.to_string());
+            flight.home_lat = Some(47.0);
+            flight.home_lon = Some(8.0 + n as f64);
+            db.insert_flight_with_telemetry(&flight, &sample_points(), &FlightEvents::default())
+                .unwrap();
+        }
+        let names = |page: &FlightPage| -> Vec<String> {
+            page.flights
+                .iter()
+                .map(|f| f.display_name.clone())
+                .collect()
+        };
+
+        // Newest first by default, paged with totals over every match
+        let page = db
+            .query_flights(&FlightQuery {
+                limit: Some(2),
+                offset: Some(2),
+                ..Default::default()
+            })
+            .unwrap();
+        assert_eq!(names(&page), ["Flight 3", "Flight 2"]);
+        assert_eq!(page.total_count, 5);
+        assert_eq!(page.total_duration_secs, 900.0);
+
+        let page = db
+            .query_flights(&FlightQuery {
+                date_from: Some("2024-05-02".into()),
+                date_to: Some("2024-05-04".into()),
+                battery_serial: Some("EVEN".into()),
+                sort_by: FlightSortKey::Distance,
+                sort_direction: SortDirection::Asc,
+                ..Default::default()
+            })
+            .unwrap();
+        assert_eq!(names(&page), ["Flight 2", "Flight 4"]);
+        assert_eq!(page.total_distance_m, 6000.0);
+
+        let page = db
+            .query_flights(&FlightQuery {
+                min_duration_secs: Some(120.0),
+                bounds: Some(GeoBounds {
+                    min_lat: 46.0,
+                    min_lon: 9.5,
+                    max_lat: 48.0,
+                    max_lon: 12.5,
+                }),
+                ..Default::default()
+            })
+            .unwrap();
+        assert_eq!(names(&page), ["Flight 4", "Flight 3", "Flight 2"]);
+
+        let id = page.flights[0].id;
+        assert_eq!(db.get_flight(id).unwrap().display_name, "Flight 4");
+        assert!(matches!(
+            db.get_flight(999),
+            Err(DatabaseError::FlightNotFound(999))
+        ));
+    }
+
+    #[test]
+    fn test_keychain_cache_roundtrip() {
+        let temp_dir = tempdir().unwrap();
+        let db = Database::new(temp_dir.path().to_path_buf()).unwrap();
+
+        assert_eq!(db.get_keychain("abc").unwrap(), None);
+
+        db.store_keychain("abc", "[[]]").unwrap();
+        assert_eq!(db.get_keychain("abc").unwrap(), Some("[[]]".to_string()));
+
+        // Re-fetching replaces the cached entry
+        db.store_keychain("abc", "[[{}]]").unwrap();
+        assert_eq!(db.get_keychain("abc").unwrap(), Some("[[{}]]".to_string()));
+    }
+
+    #[test]
+    fn test_parquet_export_import_merges_by_hash() {
+        let source_dir = tempdir().unwrap();
+        let source = Database::new(source_dir.path().to_path_buf()).unwrap();
+        for (id, hash) in [(1, "hash-a"), (2, "hash-b")] {
+            source
+                .insert_flight_with_telemetry(
+                    &sample_flight(id, hash),
+                    &sample_points(),
+                    &FlightEvents::default(),
+                )
+                .unwrap();
+        }
+
+        let export_dir = tempdir().unwrap();
+        let exported = source
+            .export_parquet(export_dir.path(), ParquetPartition::Flight)
+            .unwrap();
+        assert_eq!(exported, 2);
+
+        // hash-a is already in the target library; ids 1 and 2 are taken
+        let target_dir = tempdir().unwrap();
+        let target = Database::new(target_dir.path().to_path_buf()).unwrap();
+        let mut existing = sample_flight(1, "hash-a");
+        existing.display_name = "Local copy".to_string();
+        for flight in [existing, sample_flight(3, "hash-c")] {
+            target
+                .insert_flight_with_telemetry(&flight, &sample_points(), &FlightEvents::default())
+                .unwrap();
+        }
+
+        let report = target.import_parquet(export_dir.path()).unwrap();
+        assert_eq!(report.flights_imported, 1);
+        assert_eq!(report.flights_skipped, 1);
+        assert_eq!(report.telemetry_rows, 2);
+
+        let flights = target.get_all_flights().unwrap();
+        assert_eq!(flights.len(), 3);
+        let imported = flights
+            .iter()
+            .find(|f| f.display_name == "Flight 2")
+            .unwrap();
+        assert_eq!(imported.id, 3);
+        assert_eq!(target.get_flight_points(3).unwrap().len(), 2);
+
+        // Importing the same export again adds nothing
+        let again = target.import_parquet(export_dir.path()).unwrap();
+        assert_eq!(again.flights_imported, 0);
+    }
+
+    #[test]
+    fn test_parquet_import_dedupes_flights_without_hash() {
+        let source_dir = tempdir().unwrap();
+        let source = Database::new(source_dir.path().to_path_buf()).unwrap();
+        for id in 1..=3 {
+            let mut flight = sample_flight(id, "");
+            flight.file_hash = None;
+            flight.drone_serial = Some("SN1".to_string());
+            // Flights 2 and 3 are the same flight stored twice
+            flight.start_time = Utc
+                .with_ymd_and_hms(2024, 5, id.min(2) as u32, 12, 0, 0)
+                .single();
+            source
+                .insert_flight_with_telemetry(&flight, &sample_points(), &FlightEvents::default())
+                .unwrap();
+        }
+
+        let export_dir = tempdir().unwrap();
+        source
+            .export_parquet(export_dir.path(), ParquetPartition::Flight)
+            .unwrap();
+
+        let target_dir = tempdir().unwrap();
+        let target = Database::new(target_dir.path().to_path_buf()).unwrap();
+        let report = target.import_parquet(export_dir.path()).unwrap();
+        assert_eq!(report.flights_imported, 2);
+        assert_eq!(report.flights_skipped, 1);
+
+        let again = target.import_parquet(export_dir.path()).unwrap();
+        assert_eq!(again.flights_imported, 0);
+        assert_eq!(target.get_all_flights().unwrap().len(), 2);
+    }
+
+    #[test]
+    fn test_failed_replace_restores_library() {
+        let dir = tempdir().unwrap();
+        let db = Database::new(dir.path().to_path_buf()).unwrap();
+        db.insert_flight_with_telemetry(
+            &sample_flight(1, "hash-a"),
+            &sample_points(),
+            &FlightEvents::default(),
+        )
+        .unwrap();
+
+        let source = dir.path().join("not-a-library.db");
+        fs::write(&source, b"not a database").unwrap();
+        assert!(db.replace_database(&source).is_err());
+
+        // The previous library is back in place and open
+        assert_eq!(db.get_all_flights().unwrap().len(), 1);
+    }
+
+    fn backup_files(dir: &Path) -> usize {
+        fs::read_dir(dir)
+            .unwrap()
+            .filter_map(|e| e.ok())
+            .filter(|e| e.file_name().to_string_lossy().contains(".bak."))
+            .count()
+    }
+
+    #[test]
+    fn test_migrations_run_once_and_newer_schema_is_refused() {
+        let temp_dir = tempdir().unwrap();
+        let db = Database::new(temp_dir.path().to_path_buf()).unwrap();
+        {
+            let conn = db.conn.lock().unwrap();
+            assert_eq!(
+                Database::schema_version_of(&conn, None).unwrap(),
+                SCHEMA_VERSION
+            );
+            conn.execute(
+                "INSERT INTO schema_version (version, description) VALUES (?, 'From the future')",
+                params![SCHEMA_VERSION + 1],
+            )
+            .unwrap();
+        }
+        drop(db);
+        assert_eq!(backup_files(temp_dir.path()), 0);
+
+        let result = Database::new(temp_dir.path().to_path_buf());
+        assert!(matches!(
+            result,
+            Err(DatabaseError::UnsupportedSchemaVersion(found, supported))
+                if found == SCHEMA_VERSION + 1 && supported == SCHEMA_VERSION
+        ));
+    }
+
+    #[test]
+    fn test_unversioned_database_is_backed_up_and_migrated() {
+        let temp_dir = tempdir().unwrap();
+        {
+            // Layout of an early release: no height columns, rc_signal before altitude_abs
+            let conn = Connection::open(temp_dir.path().join("flights.db")).unwrap();
+            conn.execute_batch(
+                r#"
+                CREATE TABLE flights (id BIGINT PRIMARY KEY, file_name VARCHAR NOT NULL,
+                    file_hash VARCHAR UNIQUE, drone_model VARCHAR, drone_serial VARCHAR,
+                    start_time TIMESTAMP WITH TIME ZONE, end_time TIMESTAMP WITH TIME ZONE,
+                    duration_secs DOUBLE, total_distance DOUBLE, max_altitude DOUBLE,
+                    max_speed DOUBLE, home_lat DOUBLE, home_lon DOUBLE, point_count INTEGER,
+                    imported_at TIMESTAMP WITH TIME ZONE DEFAULT CURRENT_TIMESTAMP, notes VARCHAR);
+                CREATE TABLE telemetry (flight_id BIGINT NOT NULL, timestamp_ms BIGINT NOT NULL,
+                    latitude DOUBLE, longitude DOUBLE, altitude DOUBLE, rc_signal INTEGER,
+                    altitude_abs DOUBLE);
+                CREATE INDEX idx_telemetry_flight_time ON telemetry(flight_id, timestamp_ms);
+                INSERT INTO flights (id, file_name, file_hash) VALUES (1, 'a.txt', 'hash-a');
+                INSERT INTO telemetry VALUES (1, 0, 47.0, 8.0, 10.0, 90, 510.0),
+                    (1, 0, 47.0, 8.0, 10.0, 90, 510.0), (1, 100, 47.1, 8.1, 12.0, 80, 512.0);
+                "#,
+            )
+            .unwrap();
+        }
+
+        let db = Database::new(temp_dir.path().to_path_buf()).unwrap();
+        assert_eq!(backup_files(temp_dir.path()), 1);
+
+        let points = db.get_flight_points(1).unwrap();
+        assert_eq!(points.len(), 2);
+        assert_eq!(points[1].altitude_abs, Some(512.0));
+        assert_eq!(points[1].rc_signal, Some(80));
+        assert_eq!(points[1].height, None);
+
+        let conn = db.conn.lock().unwrap();
+        let mut stmt = conn
+            .prepare("SELECT name FROM pragma_table_info('telemetry')")
+            .unwrap();
+        let columns: Vec<String> = stmt
+            .query_map([], |row| row.get(0))
+            .unwrap()
+            .collect::<Result<_, _>>()
+            .unwrap();
+        assert!(columns
+            .iter()
+            .map(String::as_str)
+            .eq(TELEMETRY_COLUMNS.iter().copied()));
     }
 }
@@ -0,0 +1,744 @@
+//! Export module for writing flights to common interchange formats.
+//!
+//! Exports are produced from full-resolution telemetry stored in DuckDB,
+//! not from the downsampled series sent to the frontend. Telemetry rows are
+//! streamed straight from the query into the writer, one flight at a time,
+//! so exporting many long flights does not buffer them in memory.
+
+use std::io::{self, Write};
+use std::str::FromStr;
+
+use thiserror::Error;
+
+use crate::database::{Database, DatabaseError};
+use crate::models::{CameraEvent, Flight, TelemetryPoint};
+use crate::parser::haversine_distance;
+
+/// Namespace for the per-point GPX extensions
+const GPX_EXTENSION_NS: &str = "urn:dji-logviewer:gpx-extensions:1";
+
+#[derive(Error, Debug)]
+pub enum ExportError {
+    #[error("Database error: {0}")]
+    Database(#[from] DatabaseError),
+
+    #[error("IO error: {0}")]
+    Io(#[from] io::Error),
+
+    #[error("JSON error: {0}")]
+    Json(#[from] serde_json::Error),
+
+    #[error("Flight not found: {0}")]
+    FlightNotFound(i64),
+
+    #[error("Unsupported export format: {0}")]
+    UnsupportedFormat(String),
+
+    #[error("Unsupported altitude mode: {0}")]
+    UnsupportedAltitudeMode(String),
+
+    #[error("No flights selected for export")]
+    NoFlights,
+}
+
+/// Supported export formats
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExportFormat {
+    Csv,
+    Gpx,
+    Kml,
+    GeoJson,
+    Json,
+}
+
+impl ExportFormat {
+    /// File extension used for this format
+    pub fn extension(&self) -> &'static str {
+        match self {
+            ExportFormat::Csv => "csv",
+            ExportFormat::Gpx => "gpx",
+            ExportFormat::Kml => "kml",
+            ExportFormat::GeoJson => "geojson",
+            ExportFormat::Json => "json",
+        }
+    }
+}
+
+impl FromStr for ExportFormat {
+    type Err = ExportError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_lowercase().as_str() {
+            "csv" => Ok(ExportFormat::Csv),
+            "gpx" => Ok(ExportFormat::Gpx),
+            "kml" => Ok(ExportFormat::Kml),
+            "geojson" => Ok(ExportFormat::GeoJson),
+            "json" => Ok(ExportFormat::Json),
+            other => Err(ExportError::UnsupportedFormat(other.to_string())),
+        }
+    }
+}
+
+/// Altitude reference for KML coordinates
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum AltitudeMode {
+    /// Height above the takeoff point (the aircraft's own height reading)
+    #[default]
+    RelativeToGround,
+    /// Altitude above sea level as reported by the aircraft
+    Absolute,
+    /// Track draped on the terrain
+    ClampToGround,
+}
+
+impl AltitudeMode {
+    fn kml_value(&self) -> &'static str {
+        match self {
+            AltitudeMode::RelativeToGround => "relativeToGround",
+            AltitudeMode::Absolute => "absolute",
+            AltitudeMode::ClampToGround => "clampToGround",
+        }
+    }
+
+    fn elevation(&self, p: &TelemetryPoint) -> f64 {
+        match self {
+            AltitudeMode::RelativeToGround => point_elevation(p).unwrap_or(0.0),
+            AltitudeMode::Absolute => p.altitude_abs.or(p.altitude).unwrap_or(0.0),
+            AltitudeMode::ClampToGround => 0.0,
+        }
+    }
+}
+
+impl FromStr for AltitudeMode {
+    type Err = ExportError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_lowercase().as_str() {
+            "relative" | "relativetoground" => Ok(AltitudeMode::RelativeToGround),
+            "absolute" => Ok(AltitudeMode::Absolute),
+            "clamp" | "clamptoground" => Ok(AltitudeMode::ClampToGround),
+            other => Err(ExportError::UnsupportedAltitudeMode(other.to_string())),
+        }
+    }
+}
+
+/// Format-specific export settings
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ExportOptions {
+    /// Altitude reference for KML tracks and placemarks
+    pub altitude_mode: AltitudeMode,
+}
+
+/// Export a single flight in the requested format
+pub fn export_flight<W: Write>(
+    db: &Database,
+    flight_id: i64,
+    format: ExportFormat,
+    writer: &mut W,
+) -> Result<(), ExportError> {
+    export_flights(db, &[flight_id], format, &ExportOptions::default(), writer)
+}
+
+/// Export one or more flights into a single document.
+///
+/// GPX gets one track per flight, KML one folder per flight, GeoJSON one
+/// feature collection, CSV one table with a `flight_id` column. JSON holds a
+/// single `{ exportedAt, flight, telemetry }` document for one flight and a
+/// `flights` array of them for several.
+pub fn export_flights<W: Write>(
+    db: &Database,
+    flight_ids: &[i64],
+    format: ExportFormat,
+    options: &ExportOptions,
+    writer: &mut W,
+) -> Result<(), ExportError> {
+    if flight_ids.is_empty() {
+        return Err(ExportError::NoFlights);
+    }
+
+    let flights = flight_ids
+        .iter()
+        .map(|&id| match db.get_flight(id) {
+            Err(DatabaseError::FlightNotFound(_)) => Err(ExportError::FlightNotFound(id)),
+            other => other.map_err(ExportError::from),
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+
+    match format {
+        ExportFormat::Csv => {
+            writeln!(writer, "{}", CSV_HEADERS.join(","))?;
+            for flight in &flights {
+                db.for_each_flight_point(flight.id, |p| write_csv_row(flight, &p, writer))?;
+            }
+        }
+        ExportFormat::Gpx => {
+            write_gpx_header(&flights, writer)?;
+            for flight in &flights {
+                write_gpx_track(db, flight, writer)?;
+            }
+            writeln!(writer, "</gpx>")?;
+        }
+        ExportFormat::Kml => {
+            write_kml_header(&flights, writer)?;
+            for flight in &flights {
+                write_kml_folder(db, flight, options.altitude_mode, writer)?;
+            }
+            writeln!(writer, "  </Document>")?;
+            writeln!(writer, "</kml>")?;
+        }
+        ExportFormat::GeoJson => {
+            write!(writer, r#"{{"type":"FeatureCollection","features":["#)?;
+            for (index, flight) in flights.iter().enumerate() {
+                if index > 0 {
+                    write!(writer, ",")?;
+                }
+                write_geojson_features(db, flight, writer)?;
+            }
+            writeln!(writer, "\n]}}")?;
+        }
+        ExportFormat::Json => {
+            let exported_at = serde_json::to_string(&chrono::Utc::now().to_rfc3339())?;
+            if let [flight] = flights.as_slice() {
+                write!(writer, r#"{{"exportedAt":{},"#, exported_at)?;
+                write_json_flight_fields(db, flight, writer)?;
+                writeln!(writer, "}}")?;
+            } else {
+                write!(writer, r#"{{"exportedAt":{},"flights":["#, exported_at)?;
+                for (index, flight) in flights.iter().enumerate() {
+                    write!(writer, "{}\n{{", if index > 0 { "," } else { "" })?;
+                    write_json_flight_fields(db, flight, writer)?;
+                    write!(writer, "}}")?;
+                }
+                writeln!(writer, "\n]}}")?;
+            }
+        }
+    }
+
+    Ok(())
+}
+
+const CSV_HEADERS: &[&str] = &[
+    "flight_id",
+    "time_s",
+    "lat",
+    "lng",
+    "altitude_m",
+    "height_m",
+    "vps_height_m",
+    "altitude_abs_m",
+    "distance_to_home_m",
+    "speed_ms",
+    "velocity_x_ms",
+    "velocity_y_ms",
+    "velocity_z_ms",
+    "pitch_deg",
+    "roll_deg",
+    "yaw_deg",
+    "gimbal_pitch_deg",
+    "gimbal_roll_deg",
+    "gimbal_yaw_deg",
+    "battery_percent",
+    "battery_voltage_v",
+    "battery_current_a",
+    "battery_temp_c",
+    "battery_cell_voltages_v",
+    "battery_cell_imbalance_v",
+    "battery_full_capacity_mah",
+    "battery_remaining_capacity_mah",
+    "battery_voltage_warning",
+    "flight_mode",
+    "gps_signal",
+    "satellites",
+    "rc_signal",
+    "rc_uplink",
+    "rc_downlink",
+];
+
+fn write_csv_row<W: Write>(
+    flight: &Flight,
+    p: &TelemetryPoint,
+    writer: &mut W,
+) -> Result<(), ExportError> {
+    let distance_to_home = match (flight.home_lat, flight.home_lon, p.latitude, p.longitude) {
+        (Some(home_lat), Some(home_lon), Some(lat), Some(lon)) => {
+            Some(haversine_distance(home_lat, home_lon, lat, lon))
+        }
+        _ => None,
+    };
+
+    let values = [
+        flight.id.to_string(),
+        format!("{}", p.timestamp_ms as f64 / 1000.0),
+        opt(p.latitude),
+        opt(p.longitude),
+        opt(p.altitude),
+        opt(p.height),
+        opt(p.vps_height),
+        opt(p.altitude_abs),
+        opt(distance_to_home),
+        opt(p.speed),
+        opt(p.velocity_x),
+        opt(p.velocity_y),
+        opt(p.velocity_z),
+        opt(p.pitch),
+        opt(p.roll),
+        opt(p.yaw),
+        opt(p.gimbal_pitch),
+        opt(p.gimbal_roll),
+        opt(p.gimbal_yaw),
+        opt(p.battery_percent),
+        opt(p.battery_voltage),
+        opt(p.battery_current),
+        opt(p.battery_temp),
+        // Cell count varies by pack, so all cells share one column
+        p.battery_cell_voltages
+            .iter()
+            .map(|v| v.to_string())
+            .collect::<Vec<_>>()
+            .join(";"),
+        opt(p.cell_imbalance()),
+        opt(p.battery_full_capacity),
+        opt(p.battery_remaining_capacity),
+        opt(p.battery_voltage_warning),
+        escape_csv(p.flight_mode.as_deref().unwrap_or("")),
+        opt(p.gps_signal),
+        opt(p.satellites),
+        opt(p.rc_signal),
+        opt(p.rc_uplink),
+        opt(p.rc_downlink),
+    ];
+    writeln!(writer, "{}", values.join(","))?;
+    Ok(())
+}
+
+fn write_gpx_header<W: Write>(flights: &[Flight], writer: &mut W) -> io::Result<()> {
+    writeln!(writer, r#"<?xml version="1.0" encoding="UTF-8"?>"#)?;
+    writeln!(
+        writer,
+        r#"<gpx version="1.1" creator="DJI Log Viewer" xmlns="http://www.topografix.com/GPX/1/1" xmlns:dji="{}">"#,
+        GPX_EXTENSION_NS
+    )?;
+    writeln!(writer, "  <metadata>")?;
+    writeln!(
+        writer,
+        "    <name>{}</name>",
+        escape_xml(&document_name(flights))
+    )?;
+    writeln!(
+        writer,
+        "    <time>{}</time>",
+        chrono::Utc::now().to_rfc3339()
+    )?;
+    writeln!(writer, "  </metadata>")?;
+    Ok(())
+}
+
+fn write_gpx_track<W: Write>(
+    db: &Database,
+    flight: &Flight,
+    writer: &mut W,
+) -> Result<(), ExportError> {
+    writeln!(writer, "  <trk>")?;
+    writeln!(
+        writer,
+        "    <name>{}</name>",
+        escape_xml(&flight.display_name)
+    )?;
+    if let Some(model) = &flight.drone_model {
+        writeln!(writer, "    <desc>{}</desc>", escape_xml(model))?;
+    }
+    writeln!(writer, "    <trkseg>")?;
+
+    let start = flight_start(flight);
+    db.for_each_flight_point(flight.id, |p| {
+        let (Some(lat), Some(lon)) = (p.latitude, p.longitude) else {
+            return Ok(());
+        };
+        write!(writer, r#"      <trkpt lat="{}" lon="{}">"#, lat, lon)?;
+        if let Some(ele) = point_elevation(&p) {
+            write!(writer, "<ele>{}</ele>", ele)?;
+        }
+        if let Some(start) = start {
+            let time = start + chrono::Duration::milliseconds(p.timestamp_ms);
+            write!(writer, "<time>{}</time>", time.to_rfc3339())?;
+        }
+        if let Some(sats) = p.satellites {
+            write!(writer, "<sat>{}</sat>", sats)?;
+        }
+        write_gpx_extensions(&p, writer)?;
+        writeln!(writer, "</trkpt>")?;
+        Ok::<_, ExportError>(())
+    })?;
+
+    writeln!(writer, "    </trkseg>")?;
+    writeln!(writer, "  </trk>")?;
+    Ok(())
+}
+
+/// Per-point telemetry that GPX has no element for
+fn write_gpx_extensions<W: Write>(p: &TelemetryPoint, writer: &mut W) -> io::Result<()> {
+    let fields = [
+        ("height", p.height),
+        ("speed", p.speed),
+        ("vspeed", p.velocity_z),
+        ("pitch", p.pitch),
+        ("roll", p.roll),
+        ("yaw", p.yaw),
+        ("gimbalPitch", p.gimbal_pitch),
+        ("batteryPercent", p.battery_percent.map(f64::from)),
+        ("batteryVoltage", p.battery_voltage),
+        ("batteryTemp", p.battery_temp),
+        ("cellImbalance", p.cell_imbalance()),
+        (
+            "remainingCapacity",
+            p.battery_remaining_capacity.map(f64::from),
+        ),
+        ("rcSignal", p.rc_signal.map(f64::from)),
+    ];
+
+    write!(writer, "<extensions>")?;
+    for (name, value) in fields {
+        if let Some(value) = value {
+            write!(writer, "<dji:{0}>{1}</dji:{0}>", name, value)?;
+        }
+    }
+    if let Some(mode) = &p.flight_mode {
+        write!(
+            writer,
+            "<dji:flightMode>{}</dji:flightMode>",
+            escape_xml(mode)
+        )?;
+    }
+    write!(writer, "</extensions>")
+}
+
+fn write_kml_header<W: Write>(flights: &[Flight], writer: &mut W) -> io::Result<()> {
+    writeln!(writer, r#"<?xml version="1.0" encoding="UTF-8"?>"#)?;
+    writeln!(writer, r#"<kml xmlns="http://www.opengis.net/kml/2.2">"#)?;
+    writeln!(writer, "  <Document>")?;
+    writeln!(
+        writer,
+        "    <name>{}</name>",
+        escape_xml(&document_name(flights))
+    )?;
+
+    // Colors are aabbggrr; matches the map's yellow-to-red track and markers
+    writeln!(
+        writer,
+        r#"    <Style id="track"><LineStyle><color>ff15ccfa</color><width>4</width></LineStyle><PolyStyle><color>4015ccfa</color></PolyStyle></Style>"#
+    )?;
+    for (id, icon) in [
+        ("start", "paddle/ylw-circle.png"),
+        ("end", "paddle/red-circle.png"),
+        ("home", "paddle/grn-circle.png"),
+        ("photo", "shapes/camera.png"),
+    ] {
+        writeln!(
+            writer,
+            r#"    <Style id="{}"><IconStyle><Icon><href>http://maps.google.com/mapfiles/kml/{}</href></Icon></IconStyle></Style>"#,
+            id, icon
+        )?;
+    }
+    Ok(())
+}
+
+fn write_kml_folder<W: Write>(
+    db: &Database,
+    flight: &Flight,
+    altitude_mode: AltitudeMode,
+    writer: &mut W,
+) -> Result<(), ExportError> {
+    let name = escape_xml(&flight.display_name);
+    let mode = altitude_mode.kml_value();
+
+    writeln!(writer, "    <Folder>")?;
+    writeln!(writer, "      <name>{}</name>", name)?;
+    if let Some(start_time) = &flight.start_time {
+        writeln!(
+            writer,
+            "      <description>{}</description>",
+            escape_xml(start_time)
+        )?;
+    }
+    writeln!(writer, "      <Placemark>")?;
+    writeln!(writer, "        <name>{}</name>", name)?;
+    writeln!(writer, "        <styleUrl>#track</styleUrl>")?;
+    writeln!(writer, "        <LineString>")?;
+    writeln!(writer, "          <tessellate>1</tessellate>")?;
+    writeln!(writer, "          <altitudeMode>{}</altitudeMode>", mode)?;
+    write!(writer, "          <coordinates>")?;
+
+    let mut first: Option<[f64; 3]> = None;
+    let mut last: Option<[f64; 3]> = None;
+    db.for_each_flight_point(flight.id, |p| {
+        if let (Some(lat), Some(lon)) = (p.latitude, p.longitude) {
+            let coordinate = [lon, lat, altitude_mode.elevation(&p)];
+            write!(
+                writer,
+                "{},{},{} ",
+                coordinate[0], coordinate[1], coordinate[2]
+            )?;
+            first.get_or_insert(coordinate);
+            last = Some(coordinate);
+        }
+        Ok::<_, ExportError>(())
+    })?;
+
+    writeln!(writer, "</coordinates>")?;
+    writeln!(writer, "        </LineString>")?;
+    writeln!(writer, "      </Placemark>")?;
+
+    let mut placemarks: Vec<(String, &str, [f64; 3])> = Vec::new();
+    if let Some(coordinate) = first {
+        placemarks.push(("Start".to_string(), "start", coordinate));
+    }
+    if let Some(coordinate) = last {
+        placemarks.push(("End".to_string(), "end", coordinate));
+    }
+    if let (Some(lat), Some(lon)) = (flight.home_lat, flight.home_lon) {
+        placemarks.push(("Home".to_string(), "home", [lon, lat, 0.0]));
+    }
+    for (index, photo) in photo_positions(db, flight.id)?.into_iter().enumerate() {
+        placemarks.push((format!("Photo {}", index + 1), "photo", photo));
+    }
+
+    for (label, style, [lon, lat, ele]) in placemarks {
+        writeln!(writer, "      <Placemark>")?;
+        writeln!(writer, "        <name>{}</name>", label)?;
+        writeln!(writer, "        <styleUrl>#{}</styleUrl>", style)?;
+        writeln!(
+            writer,
+            "        <Point><altitudeMode>{}</altitudeMode><coordinates>{},{},{}</coordinates></Point>",
+            mode, lon, lat, ele
+        )?;
+        writeln!(writer, "      </Placemark>")?;
+    }
+
+    writeln!(writer, "    </Folder>")?;
+    Ok(())
+}
+
+/// Track as a LineString feature followed by one Point feature per photo
+fn write_geojson_features<W: Write>(
+    db: &Database,
+    flight: &Flight,
+    writer: &mut W,
+) -> Result<(), ExportError> {
+    write!(
+        writer,
+        "\n{{\"type\":\"Feature\",\"properties\":{},\"geometry\":{{\"type\":\"LineString\",\"coordinates\":[",
+        serde_json::to_string(flight)?
+    )?;
+
+    let mut first = true;
+    db.for_each_flight_point(flight.id, |p| {
+        if let (Some(lat), Some(lon)) = (p.latitude, p.longitude) {
+            let separator = if first { "" } else { "," };
+            write!(
+                writer,
+                "{}[{},{},{}]",
+                separator,
+                lon,
+                lat,
+                point_elevation(&p).unwrap_or(0.0)
+            )?;
+            first = false;
+        }
+        Ok::<_, ExportError>(())
+    })?;
+    write!(writer, "]}}}}")?;
+
+    for event in db.get_camera_events(flight.id)? {
+        let (Some(lat), Some(lon)) = (event.latitude, event.longitude) else {
+            continue;
+        };
+        if event.event_type != CameraEvent::PHOTO {
+            continue;
+        }
+        let feature = serde_json::json!({
+            "type": "Feature",
+            "properties": {
+                "flightId": flight.id,
+                "eventType": event.event_type,
+                "timestampMs": event.timestamp_ms,
+            },
+            "geometry": {
+                "type": "Point",
+                "coordinates": [lon, lat, event.altitude.unwrap_or(0.0)],
+            },
+        });
+        write!(writer, ",\n{}", feature)?;
+    }
+
+    Ok(())
+}
+
+/// `"flight": {...}, "telemetry": [...]` members of a JSON flight document
+fn write_json_flight_fields<W: Write>(
+    db: &Database,
+    flight: &Flight,
+    writer: &mut W,
+) -> Result<(), ExportError> {
+    write!(
+        writer,
+        r#""flight":{},"telemetry":["#,
+        serde_json::to_string(flight)?
+    )?;
+
+    let mut first = true;
+    db.for_each_flight_point(flight.id, |p| {
+        write!(
+            writer,
+            "{}\n{}",
+            if first { "" } else { "," },
+            serde_json::to_string(&p)?
+        )?;
+        first = false;
+        Ok::<_, ExportError>(())
+    })?;
+
+    write!(writer, "\n]")?;
+    Ok(())
+}
+
+/// Positions of the photos taken during a flight
+fn photo_positions(db: &Database, flight_id: i64) -> Result<Vec<[f64; 3]>, ExportError> {
+    Ok(db
+        .get_camera_events(flight_id)?
+        .into_iter()
+        .filter(|e| e.event_type == CameraEvent::PHOTO)
+        .filter_map(|e| Some([e.longitude?, e.latitude?, e.altitude.unwrap_or(0.0)]))
+        .collect())
+}
+
+fn document_name(flights: &[Flight]) -> String {
+    match flights {
+        [flight] => flight.display_name.clone(),
+        _ => format!("{} flights", flights.len()),
+    }
+}
+
+/// Height used for track elevation, matching the map track fallback order
+fn point_elevation(p: &TelemetryPoint) -> Option<f64> {
+    p.height.or(p.vps_height).or(p.altitude)
+}
+
+fn flight_start(flight: &Flight) -> Option<chrono::DateTime<chrono::Utc>> {
+    let raw = flight.start_time.as_deref()?;
+    chrono::DateTime::parse_from_rfc3339(raw)
+        .or_else(|_| chrono::DateTime::parse_from_str(raw, "%Y-%m-%d %H:%M:%S%.f%#z"))
+        .ok()
+        .map(|t| t.with_timezone(&chrono::Utc))
+}
+
+fn opt<T: ToString>(value: Option<T>) -> String {
+    value.map(|v| v.to_string()).unwrap_or_default()
+}
+
+pub(crate) fn escape_csv(value: &str) -> String {
+    if value.contains(',') || value.contains('"') || value.contains('\n') || value.contains('\r') {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+pub(crate) fn escape_xml(value: &str) -> String {
+    value
+        .replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&apos;")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{FlightEvents, FlightMetadata};
+    use crate::test_support::sample_flight;
+    use tempfile::tempdir;
+
+    fn insert_sample_flight(db: &Database, number: i64) {
+        let flight = FlightMetadata {
+            display_name: format!("Flight <{}>", number),
+            max_altitude: Some(10.0),
+            home_lat: Some(47.0),
+            home_lon: Some(8.0),
+            point_count: 3,
+            ..sample_flight(number, &format!("hash-{}", number))
+        };
+        let points: Vec<TelemetryPoint> = (0..3)
+            .map(|i| TelemetryPoint {
+                timestamp_ms: i * 100,
+                latitude: Some(47.0 + i as f64 * 0.0001),
+                longitude: Some(8.0),
+                height: Some(10.0),
+                ..Default::default()
+            })
+            .collect();
+        db.insert_flight_with_telemetry(&flight, &points, &FlightEvents::default())
+            .unwrap();
+    }
+
+    fn export_to_string(db: &Database, ids: &[i64], format: ExportFormat) -> String {
+        let mut out = Vec::new();
+        export_flights(db, ids, format, &ExportOptions::default(), &mut out).unwrap();
+        String::from_utf8(out).unwrap()
+    }
+
+    #[test]
+    fn test_geojson_and_json_exports_are_valid() {
+        let temp_dir = tempdir().unwrap();
+        let db = Database::new(temp_dir.path().to_path_buf()).unwrap();
+        insert_sample_flight(&db, 1);
+        insert_sample_flight(&db, 2);
+
+        let geojson: serde_json::Value =
+            serde_json::from_str(&export_to_string(&db, &[1, 2], ExportFormat::GeoJson)).unwrap();
+        let features = geojson["features"].as_array().unwrap();
+        assert_eq!(features.len(), 2);
+        assert_eq!(
+            features[0]["geometry"]["coordinates"]
+                .as_array()
+                .unwrap()
+                .len(),
+            3
+        );
+
+        let single: serde_json::Value =
+            serde_json::from_str(&export_to_string(&db, &[1], ExportFormat::Json)).unwrap();
+        assert_eq!(single["telemetry"].as_array().unwrap().len(), 3);
+
+        let multiple: serde_json::Value =
+            serde_json::from_str(&export_to_string(&db, &[1, 2], ExportFormat::Json)).unwrap();
+        assert_eq!(multiple["flights"].as_array().unwrap().len(), 2);
+    }
+
+    #[test]
+    fn test_multi_flight_gpx_and_csv() {
+        let temp_dir = tempdir().unwrap();
+        let db = Database::new(temp_dir.path().to_path_buf()).unwrap();
+        insert_sample_flight(&db, 1);
+        insert_sample_flight(&db, 2);
+
+        let gpx = export_to_string(&db, &[1, 2], ExportFormat::Gpx);
+        assert_eq!(gpx.matches("<trk>").count(), 2);
+        assert!(gpx.contains("<name>Flight &lt;1&gt;</name>"));
+        assert!(gpx.contains("<dji:height>10</dji:height>"));
+
+        let csv = export_to_string(&db, &[1, 2], ExportFormat::Csv);
+        assert_eq!(csv.lines().count(), 1 + 6);
+        assert!(csv.lines().nth(1).unwrap().starts_with("1,0,"));
+
+        let mut out = Vec::new();
+        let missing = export_flights(
+            &db,
+            &[1, 99],
+            ExportFormat::Kml,
+            &ExportOptions::default(),
+            &mut out,
+        );
+        assert!(matches!(missing, Err(ExportError::FlightNotFound(99))));
+    }
+}
@@ -0,0 +1,573 @@
+//! ArduPilot DataFlash (`.bin`) log import.
+//!
+//! DataFlash logs are self-describing: `FMT` messages declare the layout of
+//! every other message type. Points are emitted at the GPS rate, carrying the
+//! most recent attitude (`ATT`), battery (`BAT`/`CURR`), relative altitude
+//! (`POS`/`CTUN`) and flight mode (`MODE`). Text messages (`MSG`) become
+//! flight events.
+
+use std::collections::HashMap;
+use std::path::Path;
+
+use chrono::{DateTime, Duration, TimeZone, Utc};
+
+use super::{LogFormat, ReadFuture};
+use crate::models::{FlightEvent, FlightEvents, TelemetryPoint};
+use crate::parser::{message_category, LogParser, ParsedLog, ParserError};
+
+/// Message header bytes
+const HEAD: [u8; 2] = [0xA3, 0x95];
+
+/// Message type of `FMT` (format definition) messages
+const FMT_TYPE: u8 = 128;
+
+/// Total length of a `FMT` message including its header
+const FMT_LENGTH: usize = 89;
+
+/// GPS time is ahead of UTC by the leap seconds since 1980
+const GPS_LEAP_SECONDS: i64 = 18;
+
+/// Minimum GPS status for a usable position (3D fix)
+const GPS_FIX_3D: f64 = 3.0;
+
+/// Words in a `MSG` text that mark it as a warning
+const WARNING_KEYWORDS: &[&str] = &[
+    "error",
+    "fail",
+    "bad",
+    "lost",
+    "crash",
+    "emergency",
+    "warning",
+    "low",
+];
+
+/// ArduPilot DataFlash binary logs
+pub struct DataFlashFormat;
+
+impl LogFormat for DataFlashFormat {
+    fn name(&self) -> &'static str {
+        "ArduPilot DataFlash"
+    }
+
+    fn extensions(&self) -> &'static [&'static str] {
+        &["bin"]
+    }
+
+    fn detect(&self, _path: &Path, data: &[u8]) -> bool {
+        data.len() >= 3 && data[..2] == HEAD && data[2] == FMT_TYPE
+    }
+
+    fn read<'a>(&'a self, _parser: &'a LogParser<'_>, data: Vec<u8>) -> ReadFuture<'a> {
+        Box::pin(async move { parse_dataflash(&data) })
+    }
+}
+
+/// Layout of one message type, from its `FMT` definition
+struct MessageFormat {
+    name: String,
+    length: usize,
+    /// Type character and payload offset of each column
+    fields: HashMap<String, (u8, usize)>,
+}
+
+impl MessageFormat {
+    fn new(name: String, length: usize, types: &[u8], columns: &str) -> Self {
+        let mut fields = HashMap::new();
+        let mut offset = 0;
+        for (ty, column) in types.iter().zip(columns.split(',')) {
+            fields.insert(column.to_string(), (*ty, offset));
+            offset += field_size(*ty);
+        }
+
+        Self {
+            name,
+            length,
+            fields,
+        }
+    }
+
+    fn field(&self, column: &str) -> Option<(u8, usize)> {
+        self.fields.get(column).copied()
+    }
+}
+
+/// A decoded message: its format and payload (without header)
+struct Message<'a> {
+    format: &'a MessageFormat,
+    payload: &'a [u8],
+}
+
+impl Message<'_> {
+    /// Numeric column value, with DataFlash scaling applied
+    fn number(&self, column: &str) -> Option<f64> {
+        let (ty, offset) = self.format.field(column)?;
+        let bytes = self.payload.get(offset..offset + field_size(ty))?;
+        let value = match ty {
+            b'b' => bytes[0] as i8 as f64,
+            b'B' | b'M' => bytes[0] as f64,
+            b'h' => i16::from_le_bytes([bytes[0], bytes[1]]) as f64,
+            b'H' => u16::from_le_bytes([bytes[0], bytes[1]]) as f64,
+            b'c' => i16::from_le_bytes([bytes[0], bytes[1]]) as f64 / 100.0,
+            b'C' => u16::from_le_bytes([bytes[0], bytes[1]]) as f64 / 100.0,
+            b'i' => i32::from_le_bytes(bytes.try_into().ok()?) as f64,
+            b'I' => u32::from_le_bytes(bytes.try_into().ok()?) as f64,
+            b'e' => i32::from_le_bytes(bytes.try_into().ok()?) as f64 / 100.0,
+            b'E' => u32::from_le_bytes(bytes.try_into().ok()?) as f64 / 100.0,
+            b'L' => i32::from_le_bytes(bytes.try_into().ok()?) as f64 / 1e7,
+            b'f' => f32::from_le_bytes(bytes.try_into().ok()?) as f64,
+            b'd' => f64::from_le_bytes(bytes.try_into().ok()?),
+            b'q' => i64::from_le_bytes(bytes.try_into().ok()?) as f64,
+            b'Q' => u64::from_le_bytes(bytes.try_into().ok()?) as f64,
+            _ => return None,
+        };
+        value.is_finite().then_some(value)
+    }
+
+    /// Text column value (`n`, `N` or `Z` fields)
+    fn text(&self, column: &str) -> Option<String> {
+        let (ty, offset) = self.format.field(column)?;
+        if !matches!(ty, b'n' | b'N' | b'Z') {
+            return None;
+        }
+        let bytes = self.payload.get(offset..offset + field_size(ty))?;
+        Some(c_string(bytes))
+    }
+
+    /// Message time in milliseconds since boot
+    fn time_ms(&self) -> Option<f64> {
+        self.number("TimeUS")
+            .map(|us| us / 1000.0)
+            .or_else(|| self.number("TimeMS"))
+    }
+
+    /// Only the first instance of multi-instance messages (GPS, BAT) is used
+    fn is_primary_instance(&self) -> bool {
+        self.number("I")
+            .or_else(|| self.number("Instance"))
+            .map(|i| i == 0.0)
+            .unwrap_or(true)
+    }
+}
+
+/// Latest values of the non-GPS messages, applied to each GPS point
+#[derive(Default)]
+struct HeldState {
+    roll: Option<f64>,
+    pitch: Option<f64>,
+    yaw: Option<f64>,
+    voltage: Option<f64>,
+    current: Option<f64>,
+    temperature: Option<f64>,
+    percent: Option<i32>,
+    height: Option<f64>,
+    has_position_estimate: bool,
+    mode: Option<u8>,
+}
+
+/// Parse a DataFlash log into telemetry
+pub fn parse_dataflash(data: &[u8]) -> Result<ParsedLog, ParserError> {
+    let mut formats: HashMap<u8, MessageFormat> = HashMap::new();
+    let mut held = HeldState::default();
+    let mut points: Vec<TelemetryPoint> = Vec::new();
+    let mut point_times: Vec<f64> = Vec::new();
+    let mut modes: Vec<Option<u8>> = Vec::new();
+    let mut messages: Vec<(f64, String)> = Vec::new();
+    let mut first_gps_alt: Option<f64> = None;
+    let mut start_time: Option<DateTime<Utc>> = None;
+
+    let mut pos = 0;
+    while pos + 3 <= data.len() {
+        if data[pos..pos + 2] != HEAD {
+            // Resynchronize after corrupt or truncated data
+            pos += 1;
+            continue;
+        }
+        let msg_type = data[pos + 2];
+
+        if msg_type == FMT_TYPE {
+            let Some(payload) = data.get(pos + 3..pos + FMT_LENGTH) else {
+                break;
+            };
+            let format = MessageFormat::new(
+                c_string(&payload[2..6]),
+                payload[1] as usize,
+                c_string(&payload[6..22]).as_bytes(),
+                &c_string(&payload[22..86]),
+            );
+            formats.insert(payload[0], format);
+            pos += FMT_LENGTH;
+            continue;
+        }
+
+        let Some(format) = formats.get(&msg_type) else {
+            pos += 1;
+            continue;
+        };
+        let Some(payload) = data.get(pos + 3..pos + format.length.max(3)) else {
+            break;
+        };
+        pos += format.length.max(3);
+
+        let message = Message { format, payload };
+        let Some(time_ms) = message.time_ms() else {
+            continue;
+        };
+
+        match format.name.as_str() {
+            "GPS" if message.is_primary_instance() => {
+                if message.number("Status").unwrap_or(0.0) < GPS_FIX_3D {
+                    continue;
+                }
+                let (Some(lat), Some(lon)) = (message.number("Lat"), message.number("Lng")) else {
+                    continue;
+                };
+                let altitude = message.number("Alt");
+                let first_alt = *first_gps_alt.get_or_insert(altitude.unwrap_or(0.0));
+
+                if start_time.is_none() {
+                    start_time = gps_time(message.number("GWk"), message.number("GMS")).map(|t| {
+                        let since_first = time_ms - point_times.first().copied().unwrap_or(time_ms);
+                        t - Duration::milliseconds(since_first as i64)
+                    });
+                }
+
+                point_times.push(time_ms);
+                modes.push(held.mode);
+                points.push(TelemetryPoint {
+                    latitude: Some(lat),
+                    longitude: Some(lon),
+                    altitude,
+                    height: held.height.or(altitude.map(|a| a - first_alt)),
+                    altitude_abs: altitude,
+                    speed: message.number("Spd"),
+                    pitch: held.pitch,
+                    roll: held.roll,
+                    yaw: held.yaw,
+                    battery_percent: held.percent,
+                    battery_voltage: held.voltage,
+                    battery_current: held.current,
+                    battery_temp: held.temperature,
+                    satellites: message.number("NSats").map(|n| n as i32),
+                    ..Default::default()
+                });
+            }
+            "ATT" => {
+                held.roll = message.number("Roll");
+                held.pitch = message.number("Pitch");
+                held.yaw = message.number("Yaw");
+            }
+            "BAT" | "CURR" if message.is_primary_instance() => {
+                held.voltage = message.number("Volt").or(held.voltage);
+                held.current = message.number("Curr").or(held.current);
+                held.temperature = message.number("Temp").or(held.temperature);
+                held.percent = message.number("RemPct").map(|p| p as i32).or(held.percent);
+            }
+            "POS" => {
+                held.height = message.number("RelHomeAlt").or(held.height);
+                held.has_position_estimate = true;
+            }
+            // Older logs without POS: the controller's altitude above home
+            "CTUN" if !held.has_position_estimate => held.height = message.number("Alt"),
+            "MODE" => {
+                held.mode = message
+                    .number("ModeNum")
+                    .or_else(|| message.number("Mode"))
+                    .map(|m| m as u8);
+            }
+            "MSG" => {
+                if let Some(text) = message.text("Message").filter(|t| !t.is_empty()) {
+                    messages.push((time_ms, text));
+                }
+            }
+            _ => {}
+        }
+    }
+
+    if points.is_empty() {
+        return Err(ParserError::NoTelemetryData);
+    }
+
+    // Times are relative to the first GPS fix
+    let origin_ms = point_times[0];
+    for (point, time_ms) in points.iter_mut().zip(&point_times) {
+        point.timestamp_ms = (time_ms - origin_ms).round() as i64;
+    }
+
+    let vehicle = messages
+        .iter()
+        .map(|(_, text)| text.as_str())
+        .find(|text| text.starts_with("Ardu") || text.starts_with("APM:"))
+        .map(|text| text.split(" (").next().unwrap_or(text).trim().to_string());
+
+    // The banner reads e.g. "ArduCopter V4.5.1"
+    let firmware_version = vehicle.as_deref().and_then(|banner| {
+        banner
+            .split_whitespace()
+            .find(|word| {
+                word.starts_with('V') && word[1..].starts_with(|c: char| c.is_ascii_digit())
+            })
+            .map(|word| word[1..].to_string())
+    });
+
+    for (point, mode) in points.iter_mut().zip(modes) {
+        point.flight_mode = mode.map(|m| mode_name(vehicle.as_deref(), m));
+    }
+
+    let events = FlightEvents {
+        messages: messages
+            .into_iter()
+            .map(|(time_ms, message)| {
+                let lower = message.to_lowercase();
+                let severity = if WARNING_KEYWORDS.iter().any(|k| lower.contains(k)) {
+                    FlightEvent::WARNING
+                } else {
+                    FlightEvent::INFO
+                };
+                FlightEvent {
+                    timestamp_ms: ((time_ms - origin_ms).round() as i64).max(0),
+                    severity: severity.to_string(),
+                    category: message_category(&message).to_string(),
+                    message,
+                }
+            })
+            .collect(),
+        ..Default::default()
+    };
+
+    let end_time = start_time.map(|start| {
+        start + Duration::milliseconds(points.last().map(|p| p.timestamp_ms).unwrap_or(0))
+    });
+
+    Ok(ParsedLog {
+        points,
+        events,
+        drone_model: vehicle,
+        drone_serial: None,
+        aircraft_name: None,
+        battery_serial: None,
+        firmware_version,
+        start_time,
+        end_time,
+    })
+}
+
+/// Size in bytes of a DataFlash field type character
+fn field_size(ty: u8) -> usize {
+    match ty {
+        b'b' | b'B' | b'M' => 1,
+        b'h' | b'H' | b'c' | b'C' => 2,
+        b'i' | b'I' | b'e' | b'E' | b'L' | b'f' | b'n' => 4,
+        b'd' | b'q' | b'Q' => 8,
+        b'N' => 16,
+        b'Z' | b'a' => 64,
+        _ => 0,
+    }
+}
+
+/// UTC time from a GPS week and milliseconds into the week
+fn gps_time(week: Option<f64>, week_ms: Option<f64>) -> Option<DateTime<Utc>> {
+    let (week, week_ms) = (week?, week_ms?);
+    if week <= 0.0 {
+        return None;
+    }
+    let epoch = Utc.with_ymd_and_hms(1980, 1, 6, 0, 0, 0).single()?;
+    Some(
+        epoch + Duration::weeks(week as i64) + Duration::milliseconds(week_ms as i64)
+            - Duration::seconds(GPS_LEAP_SECONDS),
+    )
+}
+
+/// Flight mode name for the vehicle type named in the firmware banner
+fn mode_name(vehicle: Option<&str>, mode: u8) -> String {
+    const COPTER: &[(u8, &str)] = &[
+        (0, "Stabilize"),
+        (1, "Acro"),
+        (2, "AltHold"),
+        (3, "Auto"),
+        (4, "Guided"),
+        (5, "Loiter"),
+        (6, "RTL"),
+        (7, "Circle"),
+        (9, "Land"),
+        (11, "Drift"),
+        (13, "Sport"),
+        (14, "Flip"),
+        (15, "AutoTune"),
+        (16, "PosHold"),
+        (17, "Brake"),
+        (18, "Throw"),
+        (19, "Avoid_ADSB"),
+        (20, "Guided_NoGPS"),
+        (21, "Smart_RTL"),
+        (22, "FlowHold"),
+        (23, "Follow"),
+        (24, "ZigZag"),
+        (25, "SystemID"),
+        (26, "Heli_Autorotate"),
+        (27, "Auto RTL"),
+    ];
+    const PLANE: &[(u8, &str)] = &[
+        (0, "Manual"),
+        (1, "Circle"),
+        (2, "Stabilize"),
+        (3, "Training"),
+        (4, "Acro"),
+        (5, "FBWA"),
+        (6, "FBWB"),
+        (7, "Cruise"),
+        (8, "Autotune"),
+        (10, "Auto"),
+        (11, "RTL"),
+        (12, "Loiter"),
+        (13, "Takeoff"),
+        (14, "Avoid_ADSB"),
+        (15, "Guided"),
+        (17, "QStabilize"),
+        (18, "QHover"),
+        (19, "QLoiter"),
+        (20, "QLand"),
+        (21, "QRTL"),
+        (22, "QAutotune"),
+        (23, "QAcro"),
+        (24, "Thermal"),
+    ];
+
+    let table = match vehicle {
+        Some(v) if v.contains("Copter") || v.contains("Heli") => COPTER,
+        Some(v) if v.contains("Plane") => PLANE,
+        _ => &[],
+    };
+
+    table
+        .iter()
+        .find(|(num, _)| *num == mode)
+        .map(|(_, name)| name.to_string())
+        .unwrap_or_else(|| format!("Mode {}", mode))
+}
+
+/// NUL-terminated, NUL-padded string field
+fn c_string(bytes: &[u8]) -> String {
+    let end = bytes.iter().position(|b| *b == 0).unwrap_or(bytes.len());
+    String::from_utf8_lossy(&bytes[..end]).trim().to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn fmt(msg_type: u8, name: &str, types: &str, columns: &str) -> Vec<u8> {
+        let length = 3 + types.bytes().map(field_size).sum::<usize>();
+        let mut out = vec![HEAD[0], HEAD[1], FMT_TYPE, msg_type, length as u8];
+        for (text, size) in [(name, 4), (types, 16), (columns, 64)] {
+            let mut field = text.as_bytes().to_vec();
+            field.resize(size, 0);
+            out.extend(field);
+        }
+        out
+    }
+
+    fn message(msg_type: u8, payload: &[&[u8]]) -> Vec<u8> {
+        let mut out = vec![HEAD[0], HEAD[1], msg_type];
+        for field in payload {
+            out.extend_from_slice(field);
+        }
+        out
+    }
+
+    fn text64(text: &str) -> Vec<u8> {
+        let mut field = text.as_bytes().to_vec();
+        field.resize(64, 0);
+        field
+    }
+
+    #[test]
+    fn test_gps_points_carry_attitude_mode_and_messages() {
+        let mut log = Vec::new();
+        log.extend(fmt(
+            129,
+            "GPS",
+            "QBIHBLLef",
+            "TimeUS,Status,GMS,GWk,NSats,Lat,Lng,Alt,Spd",
+        ));
+        log.extend(fmt(130, "ATT", "Qfff", "TimeUS,Roll,Pitch,Yaw"));
+        log.extend(fmt(131, "MODE", "QMB", "TimeUS,Mode,ModeNum"));
+        log.extend(fmt(132, "MSG", "QZ", "TimeUS,Message"));
+
+        log.extend(message(
+            132,
+            &[
+                &1_000u64.to_le_bytes(),
+                &text64("ArduCopter V4.3.6 (a1b2c3d4)"),
+            ],
+        ));
+        log.extend(message(131, &[&2_000u64.to_le_bytes(), &[5], &[5]]));
+        log.extend(message(
+            130,
+            &[
+                &3_000u64.to_le_bytes(),
+                &5f32.to_le_bytes(),
+                &(-2f32).to_le_bytes(),
+                &90f32.to_le_bytes(),
+            ],
+        ));
+        for (i, time_us) in [1_000_000u64, 1_200_000].into_iter().enumerate() {
+            log.extend(message(
+                129,
+                &[
+                    &time_us.to_le_bytes(),
+                    &[3],
+                    &(345_618_000u32 + i as u32 * 200).to_le_bytes(),
+                    &2250u16.to_le_bytes(),
+                    &[14],
+                    &470_000_000i32.to_le_bytes(),
+                    &80_000_000i32.to_le_bytes(),
+                    &(40_000i32 + i as i32 * 1_000).to_le_bytes(),
+                    &4.5f32.to_le_bytes(),
+                ],
+            ));
+        }
+        log.extend(message(
+            132,
+            &[&1_500_000u64.to_le_bytes(), &text64("Battery failsafe")],
+        ));
+
+        let parsed = parse_dataflash(&log).unwrap();
+
+        assert_eq!(parsed.points.len(), 2);
+        let p = &parsed.points[1];
+        assert_eq!(p.timestamp_ms, 200);
+        assert!((p.latitude.unwrap() - 47.0).abs() < 1e-9);
+        assert_eq!(p.altitude, Some(410.0));
+        assert_eq!(p.height, Some(10.0));
+        assert_eq!(p.yaw, Some(90.0));
+        assert_eq!(p.satellites, Some(14));
+        assert_eq!(p.flight_mode.as_deref(), Some("Loiter"));
+        assert_eq!(parsed.drone_model.as_deref(), Some("ArduCopter V4.3.6"));
+        assert!(parsed.start_time.is_some());
+
+        let warning = &parsed.events.messages[1];
+        assert_eq!(warning.timestamp_ms, 500);
+        assert_eq!(warning.severity, FlightEvent::WARNING);
+        assert_eq!(warning.category, "battery");
+    }
+
+    #[test]
+    fn test_no_gps_fix_is_no_telemetry() {
+        let mut log = fmt(129, "GPS", "QBLL", "TimeUS,Status,Lat,Lng");
+        log.extend(message(
+            129,
+            &[
+                &1u64.to_le_bytes(),
+                &[1],
+                &0i32.to_le_bytes(),
+                &0i32.to_le_bytes(),
+            ],
+        ));
+
+        assert!(matches!(
+            parse_dataflash(&log),
+            Err(ParserError::NoTelemetryData)
+        ));
+    }
+}
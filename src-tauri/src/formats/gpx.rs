@@ -0,0 +1,201 @@
+//! GPX track import.
+//!
+//! GPX only carries position, elevation and time, so points get latitude,
+//! longitude, altitude (elevation above sea level) and a height relative to
+//! the first point. Ground speed is derived from consecutive fixes.
+
+use std::path::Path;
+
+use chrono::{DateTime, Utc};
+
+use super::{has_extension, LogFormat, ReadFuture};
+use crate::models::{FlightEvents, TelemetryPoint};
+use crate::parser::{haversine_distance, LogParser, ParsedLog, ParserError};
+
+/// Interval assumed between points of a track without timestamps
+const DEFAULT_INTERVAL_MS: i64 = 1000;
+
+/// GPX 1.0 / 1.1 tracks and routes
+pub struct GpxFormat;
+
+impl LogFormat for GpxFormat {
+    fn name(&self) -> &'static str {
+        "GPX track"
+    }
+
+    fn extensions(&self) -> &'static [&'static str] {
+        &["gpx"]
+    }
+
+    fn detect(&self, path: &Path, data: &[u8]) -> bool {
+        has_extension(path, "gpx") || {
+            let head = String::from_utf8_lossy(&data[..data.len().min(512)]);
+            head.trim_start_matches('\u{feff}')
+                .trim_start()
+                .starts_with("<?xml")
+                && head.contains("<gpx")
+        }
+    }
+
+    fn read<'a>(&'a self, _parser: &'a LogParser<'_>, data: Vec<u8>) -> ReadFuture<'a> {
+        Box::pin(async move { parse_gpx(&data) })
+    }
+}
+
+/// Parse the track points (or route points if there is no track) of a GPX file
+pub fn parse_gpx(data: &[u8]) -> Result<ParsedLog, ParserError> {
+    let text = std::str::from_utf8(data)
+        .map_err(|e| ParserError::Parse(format!("GPX is not valid UTF-8: {}", e)))?;
+    let document = roxmltree::Document::parse(text.trim_start_matches('\u{feff}'))
+        .map_err(|e| ParserError::Parse(format!("Invalid GPX: {}", e)))?;
+
+    let root = document.root_element();
+    if root.tag_name().name() != "gpx" {
+        return Err(ParserError::Parse("Not a GPX document".to_string()));
+    }
+
+    let mut fixes: Vec<roxmltree::Node> = root
+        .descendants()
+        .filter(|n| n.tag_name().name() == "trkpt")
+        .collect();
+    if fixes.is_empty() {
+        fixes = root
+            .descendants()
+            .filter(|n| n.tag_name().name() == "rtept")
+            .collect();
+    }
+
+    let mut points: Vec<TelemetryPoint> = Vec::with_capacity(fixes.len());
+    let mut start_time: Option<DateTime<Utc>> = None;
+    let mut end_time: Option<DateTime<Utc>> = None;
+    let mut takeoff_elevation: Option<f64> = None;
+
+    for node in fixes {
+        let (Some(lat), Some(lon)) = (
+            node.attribute("lat")
+                .and_then(|v| v.trim().parse::<f64>().ok()),
+            node.attribute("lon")
+                .and_then(|v| v.trim().parse::<f64>().ok()),
+        ) else {
+            continue;
+        };
+
+        let elevation = child_text(node, "ele").and_then(|v| v.parse::<f64>().ok());
+        let time = child_text(node, "time")
+            .and_then(|v| DateTime::parse_from_rfc3339(v).ok())
+            .map(|t| t.with_timezone(&Utc));
+
+        let timestamp_ms = match (time, start_time) {
+            (Some(t), Some(start)) => (t - start).num_milliseconds(),
+            (Some(t), None) if points.is_empty() => {
+                start_time = Some(t);
+                0
+            }
+            _ => points
+                .last()
+                .map(|p| p.timestamp_ms + DEFAULT_INTERVAL_MS)
+                .unwrap_or(0),
+        };
+        if time.is_some() {
+            end_time = time;
+        }
+
+        if takeoff_elevation.is_none() {
+            takeoff_elevation = elevation;
+        }
+
+        let speed = points.last().and_then(|prev| {
+            let dt = (timestamp_ms - prev.timestamp_ms) as f64 / 1000.0;
+            let distance = haversine_distance(prev.latitude?, prev.longitude?, lat, lon);
+            (dt > 0.0).then(|| distance / dt)
+        });
+
+        points.push(TelemetryPoint {
+            timestamp_ms,
+            latitude: Some(lat),
+            longitude: Some(lon),
+            altitude: elevation,
+            height: elevation.zip(takeoff_elevation).map(|(e, t)| e - t),
+            altitude_abs: elevation,
+            speed,
+            ..Default::default()
+        });
+    }
+
+    if points.is_empty() {
+        return Err(ParserError::NoTelemetryData);
+    }
+
+    let track_name = root
+        .descendants()
+        .find(|n| n.tag_name().name() == "trk")
+        .and_then(|trk| child_text(trk, "name"))
+        .map(str::to_string);
+
+    Ok(ParsedLog {
+        points,
+        events: FlightEvents::default(),
+        drone_model: None,
+        drone_serial: None,
+        aircraft_name: track_name,
+        battery_serial: None,
+        firmware_version: None,
+        start_time,
+        end_time,
+    })
+}
+
+/// Trimmed text of the first child element with the given local name
+fn child_text<'a>(node: roxmltree::Node<'a, '_>, name: &str) -> Option<&'a str> {
+    node.children()
+        .find(|c| c.tag_name().name() == name)
+        .and_then(|c| c.text())
+        .map(str::trim)
+        .filter(|t| !t.is_empty())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_track_points_with_time_and_elevation() {
+        let gpx = r#"<?xml version="1.0" encoding="UTF-8"?>
+<gpx version="1.1" creator="test" xmlns="http://www.topografix.com/GPX/1/1">
+  <trk><name>Lake survey</name><trkseg>
+    <trkpt lat="47.0" lon="8.0"><ele>400.0</ele><time>2023-04-01T10:00:00Z</time></trkpt>
+    <trkpt lat="47.0009" lon="8.0"><ele>430.5</ele><time>2023-04-01T10:00:10Z</time></trkpt>
+  </trkseg></trk>
+</gpx>"#;
+
+        let log = parse_gpx(gpx.as_bytes()).unwrap();
+
+        assert_eq!(log.points.len(), 2);
+        assert_eq!(log.points[1].timestamp_ms, 10_000);
+        assert_eq!(log.points[1].altitude, Some(430.5));
+        assert_eq!(log.points[1].height, Some(30.5));
+        assert!((log.points[1].speed.unwrap() - 10.0).abs() < 0.1);
+        assert_eq!(log.aircraft_name.as_deref(), Some("Lake survey"));
+        assert_eq!(
+            log.start_time.unwrap().to_rfc3339(),
+            "2023-04-01T10:00:00+00:00"
+        );
+        assert_eq!(
+            log.end_time.unwrap().to_rfc3339(),
+            "2023-04-01T10:00:10+00:00"
+        );
+    }
+
+    #[test]
+    fn test_route_without_time_uses_default_interval() {
+        let gpx = r#"<gpx version="1.0"><rte>
+            <rtept lat="1.0" lon="2.0"/><rtept lat="1.001" lon="2.0"/>
+        </rte></gpx>"#;
+
+        let log = parse_gpx(gpx.as_bytes()).unwrap();
+
+        assert_eq!(log.points[1].timestamp_ms, DEFAULT_INTERVAL_MS);
+        assert!(log.start_time.is_none());
+        assert!(log.points[1].altitude.is_none());
+    }
+}
@@ -0,0 +1,155 @@
+//! Pluggable flight log formats.
+//!
+//! Every supported file type implements [`LogFormat`]: it recognizes its
+//! files and reads them into a [`ParsedLog`]. `LogParser` picks the format,
+//! then derives statistics, findings and metadata the same way for all of
+//! them.
+//!
+//! Built-in formats:
+//! - DJI flight records (`.txt`, the fallback for anything unrecognized)
+//! - Airdata / DJI converter CSV exports (`.csv`)
+//! - GPX tracks (`.gpx`)
+//! - ArduPilot DataFlash logs (`.bin`)
+//! - PX4 ULog files (`.ulg`)
+
+pub mod dataflash;
+pub mod gpx;
+pub mod ulog;
+
+use std::future::Future;
+use std::path::Path;
+use std::pin::Pin;
+
+use crate::csv_import;
+use crate::parser::{LogParser, ParsedLog, ParserError};
+
+/// Future returned by [`LogFormat::read`]
+pub type ReadFuture<'a> = Pin<Box<dyn Future<Output = Result<ParsedLog, ParserError>> + Send + 'a>>;
+
+/// A flight log file format that can be read into telemetry
+pub trait LogFormat: Sync {
+    /// Short name used in log messages
+    fn name(&self) -> &'static str;
+
+    /// Lowercase file extensions picked up when scanning folders
+    fn extensions(&self) -> &'static [&'static str];
+
+    /// Whether the file is in this format, from its contents or path
+    fn detect(&self, path: &Path, data: &[u8]) -> bool;
+
+    /// Read telemetry, events and aircraft details from the file contents
+    ///
+    /// The parser gives access to the database and API for formats that
+    /// need them (e.g. DJI keychains).
+    fn read<'a>(&'a self, parser: &'a LogParser<'_>, data: Vec<u8>) -> ReadFuture<'a>;
+}
+
+/// DJI flight records, read with dji-log-parser
+pub struct DjiFormat;
+
+impl LogFormat for DjiFormat {
+    fn name(&self) -> &'static str {
+        "DJI flight record"
+    }
+
+    fn extensions(&self) -> &'static [&'static str] {
+        &["txt"]
+    }
+
+    /// DJI records have no reliable magic, so this is the fallback format
+    fn detect(&self, _path: &Path, _data: &[u8]) -> bool {
+        true
+    }
+
+    fn read<'a>(&'a self, parser: &'a LogParser<'_>, data: Vec<u8>) -> ReadFuture<'a> {
+        Box::pin(parser.parse_dji_log(data))
+    }
+}
+
+/// CSV exports from Airdata and DJI log converters
+pub struct CsvFormat;
+
+impl LogFormat for CsvFormat {
+    fn name(&self) -> &'static str {
+        "CSV export"
+    }
+
+    fn extensions(&self) -> &'static [&'static str] {
+        &["csv"]
+    }
+
+    fn detect(&self, path: &Path, _data: &[u8]) -> bool {
+        csv_import::is_csv_log(path)
+    }
+
+    fn read<'a>(&'a self, _parser: &'a LogParser<'_>, data: Vec<u8>) -> ReadFuture<'a> {
+        Box::pin(async move { csv_import::parse_csv_log(&data) })
+    }
+}
+
+/// Registered formats in detection order; DJI must stay last
+static FORMATS: &[&dyn LogFormat] = &[
+    &ulog::ULogFormat,
+    &dataflash::DataFlashFormat,
+    &gpx::GpxFormat,
+    &CsvFormat,
+    &DjiFormat,
+];
+
+/// Pick the format to read a file with
+pub fn detect(path: &Path, data: &[u8]) -> &'static dyn LogFormat {
+    FORMATS
+        .iter()
+        .copied()
+        .find(|format| format.detect(path, data))
+        .unwrap_or(&DjiFormat)
+}
+
+/// Whether a file extension belongs to any supported format
+pub fn has_log_extension(path: &Path) -> bool {
+    let Some(extension) = path.extension().and_then(|e| e.to_str()) else {
+        return false;
+    };
+
+    FORMATS.iter().any(|format| {
+        format
+            .extensions()
+            .iter()
+            .any(|e| e.eq_ignore_ascii_case(extension))
+    })
+}
+
+/// Extension of a path matches the given one, ignoring case
+fn has_extension(path: &Path, extension: &str) -> bool {
+    path.extension()
+        .and_then(|e| e.to_str())
+        .map(|e| e.eq_ignore_ascii_case(extension))
+        .unwrap_or(false)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_detect_falls_back_to_dji() {
+        assert_eq!(
+            detect(Path::new("a.csv"), b"time,latitude").name(),
+            "CSV export"
+        );
+        assert_eq!(
+            detect(Path::new("a.ulg"), b"ULog\x01\x12\x35\x01").name(),
+            "PX4 ULog"
+        );
+        assert_eq!(
+            detect(Path::new("a.BIN"), &[0xA3, 0x95, 0x80]).name(),
+            "ArduPilot DataFlash"
+        );
+        assert_eq!(
+            detect(Path::new("a.txt"), b"\x00\x01\x02").name(),
+            "DJI flight record"
+        );
+        assert!(has_log_extension(Path::new("track.GPX")));
+        assert!(!has_log_extension(Path::new("notes.md")));
+    }
+}
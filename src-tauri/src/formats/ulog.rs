@@ -0,0 +1,579 @@
+//! PX4 ULog (`.ulg`) log import.
+//!
+//! ULog files define each uORB topic layout in `F` messages and log topic
+//! samples as `D` messages. Points are emitted at the GPS rate
+//! (`vehicle_gps_position` / `sensor_gps`), carrying the most recent attitude,
+//! battery state, local altitude and navigation state. Logged console
+//! messages (`L`/`C`) become flight events.
+
+use std::collections::HashMap;
+use std::path::Path;
+
+use chrono::{DateTime, Duration, Utc};
+
+use super::{LogFormat, ReadFuture};
+use crate::models::{FlightEvent, FlightEvents, TelemetryPoint};
+use crate::parser::{message_category, LogParser, ParsedLog, ParserError};
+
+/// File magic, followed by a version byte and the start timestamp
+const MAGIC: &[u8] = b"ULog\x01\x12\x35";
+
+/// Size of the file header
+const HEADER_LENGTH: usize = 16;
+
+/// Minimum `fix_type` for a usable position (3D fix)
+const GPS_FIX_3D: f64 = 3.0;
+
+/// Log levels are ASCII digits as in syslog; warnings and worse map to warnings
+const LOG_LEVEL_WARNING: u8 = b'4';
+const LOG_LEVEL_DEBUG: u8 = b'7';
+
+/// PX4 ULog files
+pub struct ULogFormat;
+
+impl LogFormat for ULogFormat {
+    fn name(&self) -> &'static str {
+        "PX4 ULog"
+    }
+
+    fn extensions(&self) -> &'static [&'static str] {
+        &["ulg"]
+    }
+
+    fn detect(&self, _path: &Path, data: &[u8]) -> bool {
+        data.starts_with(MAGIC)
+    }
+
+    fn read<'a>(&'a self, _parser: &'a LogParser<'_>, data: Vec<u8>) -> ReadFuture<'a> {
+        Box::pin(async move { parse_ulog(&data) })
+    }
+}
+
+/// Primitive ULog field types
+#[derive(Debug, Clone, Copy)]
+enum Primitive {
+    I8,
+    U8,
+    I16,
+    U16,
+    I32,
+    U32,
+    I64,
+    U64,
+    F32,
+    F64,
+    Bool,
+    Char,
+}
+
+impl Primitive {
+    fn parse(name: &str) -> Option<Self> {
+        Some(match name {
+            "int8_t" => Self::I8,
+            "uint8_t" => Self::U8,
+            "int16_t" => Self::I16,
+            "uint16_t" => Self::U16,
+            "int32_t" => Self::I32,
+            "uint32_t" => Self::U32,
+            "int64_t" => Self::I64,
+            "uint64_t" => Self::U64,
+            "float" => Self::F32,
+            "double" => Self::F64,
+            "bool" => Self::Bool,
+            "char" => Self::Char,
+            _ => return None,
+        })
+    }
+
+    fn size(self) -> usize {
+        match self {
+            Self::I8 | Self::U8 | Self::Bool | Self::Char => 1,
+            Self::I16 | Self::U16 => 2,
+            Self::I32 | Self::U32 | Self::F32 => 4,
+            Self::I64 | Self::U64 | Self::F64 => 8,
+        }
+    }
+
+    fn read(self, bytes: &[u8]) -> Option<f64> {
+        let value = match self {
+            Self::I8 => *bytes.first()? as i8 as f64,
+            Self::U8 | Self::Bool | Self::Char => *bytes.first()? as f64,
+            Self::I16 => i16::from_le_bytes(bytes.get(..2)?.try_into().ok()?) as f64,
+            Self::U16 => u16::from_le_bytes(bytes.get(..2)?.try_into().ok()?) as f64,
+            Self::I32 => i32::from_le_bytes(bytes.get(..4)?.try_into().ok()?) as f64,
+            Self::U32 => u32::from_le_bytes(bytes.get(..4)?.try_into().ok()?) as f64,
+            Self::I64 => i64::from_le_bytes(bytes.get(..8)?.try_into().ok()?) as f64,
+            Self::U64 => u64::from_le_bytes(bytes.get(..8)?.try_into().ok()?) as f64,
+            Self::F32 => f32::from_le_bytes(bytes.get(..4)?.try_into().ok()?) as f64,
+            Self::F64 => f64::from_le_bytes(bytes.get(..8)?.try_into().ok()?),
+        };
+        value.is_finite().then_some(value)
+    }
+}
+
+/// Offset and type of each top-level primitive field of a topic
+struct TopicLayout {
+    fields: HashMap<String, (usize, Primitive)>,
+}
+
+impl TopicLayout {
+    /// Lay out a topic from its format definitions; nested types are skipped over
+    fn build(name: &str, formats: &HashMap<String, String>) -> Option<Self> {
+        let mut fields = HashMap::new();
+        let mut offset = 0;
+
+        for (ty, count, field) in parse_fields(formats.get(name)?) {
+            let size = match Primitive::parse(ty) {
+                Some(primitive) if count == 1 => {
+                    fields.insert(field.to_string(), (offset, primitive));
+                    primitive.size()
+                }
+                // Array elements are addressed as `q[0]`, `q[1]`, ...
+                Some(primitive) => {
+                    for i in 0..count {
+                        let key = format!("{}[{}]", field, i);
+                        fields.insert(key, (offset + i * primitive.size(), primitive));
+                    }
+                    primitive.size()
+                }
+                None => nested_size(ty, formats)?,
+            };
+            offset += size * count;
+        }
+
+        Some(Self { fields })
+    }
+
+    fn number(&self, payload: &[u8], field: &str) -> Option<f64> {
+        let (offset, primitive) = *self.fields.get(field)?;
+        primitive.read(payload.get(offset..)?)
+    }
+
+    fn first_number(&self, payload: &[u8], fields: &[&str]) -> Option<f64> {
+        fields.iter().find_map(|f| self.number(payload, f))
+    }
+}
+
+/// Split `"uint64_t timestamp;float[4] q;"` into (type, array length, name)
+fn parse_fields(definition: &str) -> Vec<(&str, usize, &str)> {
+    definition
+        .split(';')
+        .filter_map(|field| {
+            let (ty, name) = field.trim().split_once(' ')?;
+            let (ty, count) = match ty.split_once('[') {
+                Some((base, rest)) => (base, rest.trim_end_matches(']').parse().ok()?),
+                None => (ty, 1),
+            };
+            Some((ty, count, name.trim()))
+        })
+        .collect()
+}
+
+/// Size in bytes of a nested message type
+fn nested_size(name: &str, formats: &HashMap<String, String>) -> Option<usize> {
+    parse_fields(formats.get(name)?)
+        .into_iter()
+        .map(|(ty, count, _)| {
+            let size = match Primitive::parse(ty) {
+                Some(primitive) => primitive.size(),
+                None => nested_size(ty, formats)?,
+            };
+            Some(size * count)
+        })
+        .sum()
+}
+
+/// Topics read from the log
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Topic {
+    Gps,
+    Attitude,
+    Battery,
+    LocalPosition,
+    Status,
+}
+
+impl Topic {
+    fn from_name(name: &str) -> Option<Self> {
+        Some(match name {
+            "vehicle_gps_position" | "sensor_gps" => Self::Gps,
+            "vehicle_attitude" => Self::Attitude,
+            "battery_status" => Self::Battery,
+            "vehicle_local_position" => Self::LocalPosition,
+            "vehicle_status" => Self::Status,
+            _ => return None,
+        })
+    }
+}
+
+/// Latest values of the non-GPS topics, applied to each GPS point
+#[derive(Default)]
+struct HeldState {
+    attitude: Option<(f64, f64, f64)>,
+    voltage: Option<f64>,
+    current: Option<f64>,
+    temperature: Option<f64>,
+    percent: Option<i32>,
+    height: Option<f64>,
+    nav_state: Option<u8>,
+}
+
+/// Parse a ULog file into telemetry
+pub fn parse_ulog(data: &[u8]) -> Result<ParsedLog, ParserError> {
+    if !data.starts_with(MAGIC) || data.len() < HEADER_LENGTH {
+        return Err(ParserError::Parse("Not a ULog file".to_string()));
+    }
+
+    let mut formats: HashMap<String, String> = HashMap::new();
+    let mut subscriptions: HashMap<u16, (Topic, TopicLayout)> = HashMap::new();
+    let mut info: HashMap<String, String> = HashMap::new();
+    let mut held = HeldState::default();
+    let mut points: Vec<TelemetryPoint> = Vec::new();
+    let mut point_times: Vec<u64> = Vec::new();
+    let mut messages: Vec<(u64, u8, String)> = Vec::new();
+    let mut first_gps_alt: Option<f64> = None;
+    let mut start_time: Option<DateTime<Utc>> = None;
+
+    let mut pos = HEADER_LENGTH;
+    while pos + 3 <= data.len() {
+        let size = u16::from_le_bytes([data[pos], data[pos + 1]]) as usize;
+        let msg_type = data[pos + 2];
+        let Some(payload) = data.get(pos + 3..pos + 3 + size) else {
+            break;
+        };
+        pos += 3 + size;
+
+        match msg_type {
+            b'F' => {
+                let text = String::from_utf8_lossy(payload);
+                if let Some((name, fields)) = text.split_once(':') {
+                    formats.insert(name.to_string(), fields.to_string());
+                }
+            }
+            b'I' => {
+                let Some(key_len) = payload.first().map(|l| *l as usize) else {
+                    continue;
+                };
+                let Some(key) = payload.get(1..1 + key_len) else {
+                    continue;
+                };
+                let key = String::from_utf8_lossy(key);
+                // Only string values ("char[N] name") are of interest
+                if let Some((ty, name)) = key.split_once(' ') {
+                    if ty.starts_with("char[") {
+                        let value = String::from_utf8_lossy(&payload[1 + key_len..]);
+                        info.insert(name.to_string(), value.trim_end_matches('\0').to_string());
+                    }
+                }
+            }
+            b'A' if payload.len() > 3 => {
+                let multi_id = payload[0];
+                let msg_id = u16::from_le_bytes([payload[1], payload[2]]);
+                let name = String::from_utf8_lossy(&payload[3..]);
+                let Some(topic) = Topic::from_name(&name).filter(|_| multi_id == 0) else {
+                    continue;
+                };
+                if let Some(layout) = TopicLayout::build(&name, &formats) {
+                    subscriptions.insert(msg_id, (topic, layout));
+                }
+            }
+            b'D' if payload.len() > 2 => {
+                let msg_id = u16::from_le_bytes([payload[0], payload[1]]);
+                let Some((topic, layout)) = subscriptions.get(&msg_id) else {
+                    continue;
+                };
+                let sample = &payload[2..];
+                let Some(timestamp) = layout.number(sample, "timestamp").map(|t| t as u64) else {
+                    continue;
+                };
+
+                match topic {
+                    Topic::Gps => {
+                        if layout.number(sample, "fix_type").unwrap_or(0.0) < GPS_FIX_3D {
+                            continue;
+                        }
+                        let lat = layout
+                            .number(sample, "latitude_deg")
+                            .or_else(|| layout.number(sample, "lat").map(|v| v / 1e7));
+                        let lon = layout
+                            .number(sample, "longitude_deg")
+                            .or_else(|| layout.number(sample, "lon").map(|v| v / 1e7));
+                        let (Some(lat), Some(lon)) = (lat, lon) else {
+                            continue;
+                        };
+                        let altitude = layout
+                            .number(sample, "altitude_msl_m")
+                            .or_else(|| layout.number(sample, "alt").map(|v| v / 1000.0));
+                        let first_alt = *first_gps_alt.get_or_insert(altitude.unwrap_or(0.0));
+
+                        if start_time.is_none() {
+                            let utc_us = layout.number(sample, "time_utc_usec").unwrap_or(0.0);
+                            if utc_us > 0.0 {
+                                let since_first =
+                                    timestamp - point_times.first().copied().unwrap_or(timestamp);
+                                start_time = DateTime::from_timestamp_micros(utc_us as i64)
+                                    .map(|t| t - Duration::microseconds(since_first as i64));
+                            }
+                        }
+
+                        point_times.push(timestamp);
+                        points.push(TelemetryPoint {
+                            latitude: Some(lat),
+                            longitude: Some(lon),
+                            altitude,
+                            height: held.height.or(altitude.map(|a| a - first_alt)),
+                            altitude_abs: altitude,
+                            speed: layout.number(sample, "vel_m_s"),
+                            roll: held.attitude.map(|a| a.0),
+                            pitch: held.attitude.map(|a| a.1),
+                            yaw: held.attitude.map(|a| a.2),
+                            battery_percent: held.percent,
+                            battery_voltage: held.voltage,
+                            battery_current: held.current,
+                            battery_temp: held.temperature,
+                            flight_mode: held.nav_state.map(nav_state_name),
+                            satellites: layout.number(sample, "satellites_used").map(|n| n as i32),
+                            ..Default::default()
+                        });
+                    }
+                    Topic::Attitude => {
+                        let q: Option<Vec<f64>> = (0..4)
+                            .map(|i| layout.number(sample, &format!("q[{}]", i)))
+                            .collect();
+                        held.attitude = q.map(|q| quaternion_to_euler(&q));
+                    }
+                    Topic::Battery => {
+                        held.voltage =
+                            layout.first_number(sample, &["voltage_v", "voltage_filtered_v"]);
+                        held.current = layout
+                            .first_number(sample, &["current_a", "current_filtered_a"])
+                            .filter(|c| *c >= 0.0);
+                        held.temperature = layout.number(sample, "temperature");
+                        held.percent = layout
+                            .number(sample, "remaining")
+                            .filter(|r| *r >= 0.0)
+                            .map(|r| (r * 100.0).round() as i32);
+                    }
+                    Topic::LocalPosition => {
+                        // NED frame: z is down from the local origin (home)
+                        held.height = layout.number(sample, "z").map(|z| -z);
+                    }
+                    Topic::Status => {
+                        held.nav_state = layout.number(sample, "nav_state").map(|n| n as u8);
+                    }
+                }
+            }
+            b'L' if payload.len() > 9 => {
+                let timestamp = u64::from_le_bytes(payload[1..9].try_into().unwrap_or_default());
+                let text = String::from_utf8_lossy(&payload[9..]).trim().to_string();
+                messages.push((timestamp, payload[0], text));
+            }
+            b'C' if payload.len() > 11 => {
+                let timestamp = u64::from_le_bytes(payload[3..11].try_into().unwrap_or_default());
+                let text = String::from_utf8_lossy(&payload[11..]).trim().to_string();
+                messages.push((timestamp, payload[0], text));
+            }
+            _ => {}
+        }
+    }
+
+    if points.is_empty() {
+        return Err(ParserError::NoTelemetryData);
+    }
+
+    // Times are relative to the first GPS fix
+    let origin = point_times[0];
+    for (point, timestamp) in points.iter_mut().zip(&point_times) {
+        point.timestamp_ms = (timestamp.saturating_sub(origin) / 1000) as i64;
+    }
+
+    let events = FlightEvents {
+        messages: messages
+            .into_iter()
+            .filter(|(_, level, text)| *level < LOG_LEVEL_DEBUG && !text.is_empty())
+            .map(|(timestamp, level, message)| FlightEvent {
+                timestamp_ms: (timestamp.saturating_sub(origin) / 1000) as i64,
+                severity: if level <= LOG_LEVEL_WARNING {
+                    FlightEvent::WARNING
+                } else {
+                    FlightEvent::INFO
+                }
+                .to_string(),
+                category: message_category(&message).to_string(),
+                message,
+            })
+            .collect(),
+        ..Default::default()
+    };
+
+    let drone_model = match (info.remove("sys_name"), info.remove("ver_hw")) {
+        (Some(name), Some(hardware)) => Some(format!("{} {}", name, hardware)),
+        (name, hardware) => name.or(hardware),
+    };
+
+    // Release builds log their tag as the branch; otherwise fall back to the git hash
+    let firmware_version = info
+        .remove("ver_sw_branch")
+        .filter(|branch| branch.starts_with('v'))
+        .or_else(|| info.remove("ver_sw"));
+
+    let end_time = start_time.map(|start| {
+        start + Duration::milliseconds(points.last().map(|p| p.timestamp_ms).unwrap_or(0))
+    });
+
+    Ok(ParsedLog {
+        points,
+        events,
+        drone_model,
+        drone_serial: None,
+        aircraft_name: None,
+        battery_serial: None,
+        firmware_version,
+        start_time,
+        end_time,
+    })
+}
+
+/// Roll, pitch and yaw in degrees from a `[w, x, y, z]` quaternion
+fn quaternion_to_euler(q: &[f64]) -> (f64, f64, f64) {
+    let (w, x, y, z) = (q[0], q[1], q[2], q[3]);
+    let roll = (2.0 * (w * x + y * z)).atan2(1.0 - 2.0 * (x * x + y * y));
+    let pitch = (2.0 * (w * y - z * x)).clamp(-1.0, 1.0).asin();
+    let yaw = (2.0 * (w * z + x * y)).atan2(1.0 - 2.0 * (y * y + z * z));
+    (roll.to_degrees(), pitch.to_degrees(), yaw.to_degrees())
+}
+
+/// Name of a PX4 `vehicle_status.nav_state`
+fn nav_state_name(state: u8) -> String {
+    const NAMES: &[(u8, &str)] = &[
+        (0, "Manual"),
+        (1, "Altitude"),
+        (2, "Position"),
+        (3, "Mission"),
+        (4, "Hold"),
+        (5, "Return"),
+        (10, "Acro"),
+        (12, "Descend"),
+        (13, "Termination"),
+        (14, "Offboard"),
+        (15, "Stabilized"),
+        (17, "Takeoff"),
+        (18, "Land"),
+        (19, "Follow Target"),
+        (20, "Precision Land"),
+        (21, "Orbit"),
+        (22, "VTOL Takeoff"),
+    ];
+
+    NAMES
+        .iter()
+        .find(|(num, _)| *num == state)
+        .map(|(_, name)| name.to_string())
+        .unwrap_or_else(|| format!("Nav state {}", state))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn message(msg_type: u8, payload: &[u8]) -> Vec<u8> {
+        let mut out = (payload.len() as u16).to_le_bytes().to_vec();
+        out.push(msg_type);
+        out.extend_from_slice(payload);
+        out
+    }
+
+    fn data(msg_id: u16, fields: &[&[u8]]) -> Vec<u8> {
+        let mut payload = msg_id.to_le_bytes().to_vec();
+        for field in fields {
+            payload.extend_from_slice(field);
+        }
+        message(b'D', &payload)
+    }
+
+    fn subscribe(msg_id: u16, name: &str) -> Vec<u8> {
+        let mut payload = vec![0];
+        payload.extend(msg_id.to_le_bytes());
+        payload.extend(name.as_bytes());
+        message(b'A', &payload)
+    }
+
+    #[test]
+    fn test_gps_points_carry_attitude_and_log_messages() {
+        let mut log = MAGIC.to_vec();
+        log.push(1);
+        log.extend(0u64.to_le_bytes());
+
+        let mut info = vec![16];
+        info.extend(b"char[3] sys_namePX4");
+        log.extend(message(b'I', &info));
+        log.extend(message(b'F', b"vec3:float x;float y;float z;"));
+        log.extend(message(
+            b'F',
+            b"vehicle_gps_position:uint64_t timestamp;int32_t lat;int32_t lon;int32_t alt;vec3 unused;float vel_m_s;uint8_t fix_type;uint8_t satellites_used;",
+        ));
+        log.extend(message(
+            b'F',
+            b"vehicle_attitude:uint64_t timestamp;float[4] q;",
+        ));
+        log.extend(subscribe(1, "vehicle_gps_position"));
+        log.extend(subscribe(2, "vehicle_attitude"));
+
+        let half = std::f32::consts::FRAC_1_SQRT_2;
+        log.extend(data(
+            2,
+            &[
+                &10u64.to_le_bytes(),
+                &half.to_le_bytes(),
+                &0f32.to_le_bytes(),
+                &0f32.to_le_bytes(),
+                &half.to_le_bytes(),
+            ],
+        ));
+        for (i, timestamp) in [5_000_000u64, 5_200_000].into_iter().enumerate() {
+            log.extend(data(
+                1,
+                &[
+                    &timestamp.to_le_bytes(),
+                    &470_000_000i32.to_le_bytes(),
+                    &(80_000_000i32 + i as i32 * 100).to_le_bytes(),
+                    &(500_000i32 + i as i32 * 2_000).to_le_bytes(),
+                    &[0u8; 12],
+                    &3.5f32.to_le_bytes(),
+                    &[3],
+                    &[11],
+                ],
+            ));
+        }
+        let mut warning = vec![b'4'];
+        warning.extend(5_500_000u64.to_le_bytes());
+        warning.extend(b"Low battery, return advised");
+        log.extend(message(b'L', &warning));
+
+        let parsed = parse_ulog(&log).unwrap();
+
+        assert_eq!(parsed.points.len(), 2);
+        let p = &parsed.points[1];
+        assert_eq!(p.timestamp_ms, 200);
+        assert!((p.longitude.unwrap() - 8.00001).abs() < 1e-9);
+        assert_eq!(p.altitude, Some(502.0));
+        assert_eq!(p.height, Some(2.0));
+        assert_eq!(p.speed, Some(3.5));
+        assert_eq!(p.satellites, Some(11));
+        assert!((p.yaw.unwrap() - 90.0).abs() < 1e-3);
+        assert_eq!(parsed.drone_model.as_deref(), Some("PX4"));
+
+        let event = &parsed.events.messages[0];
+        assert_eq!(event.timestamp_ms, 500);
+        assert_eq!(event.severity, FlightEvent::WARNING);
+        assert_eq!(event.category, "battery");
+    }
+
+    #[test]
+    fn test_rejects_non_ulog_data() {
+        assert!(matches!(
+            parse_ulog(b"not a ulog file"),
+            Err(ParserError::Parse(_))
+        ));
+    }
+}
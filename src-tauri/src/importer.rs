@@ -0,0 +1,595 @@
+//! Import pipeline shared by the Tauri commands and the headless CLI.
+//!
+//! Handles:
+//! - Archiving the original log into raw_logs
+//! - Parsing the log into flight metadata and telemetry
+//! - Inserting the flight and its telemetry into DuckDB
+//! - Batch imports of whole folders with parallel parsing
+//! - Re-processing flights from the raw_logs archive after parser upgrades
+
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use tokio::sync::{OwnedSemaphorePermit, Semaphore};
+use tokio::task::JoinSet;
+
+use crate::database::Database;
+use crate::formats;
+use crate::models::{
+    FileImportOutcome, FileImportStatus, FolderImportReport, ImportResult, ReprocessOutcome,
+    ReprocessReport,
+};
+use crate::parser::{self, LogParser, ParseResult, ParserError};
+
+/// DJI logs start with a 100 byte prefix header; anything smaller is not a flight log
+const MIN_LOG_FILE_SIZE: u64 = 100;
+
+/// Import a single DJI flight log file
+///
+/// Parse failures and duplicates are reported through `ImportResult`;
+/// only database errors are returned as `Err`.
+pub async fn import_log_file(db: &Database, path: &Path) -> Result<ImportResult, String> {
+    if !path.exists() {
+        return Ok(ImportResult {
+            success: false,
+            flight_id: None,
+            message: "File not found".to_string(),
+            point_count: 0,
+        });
+    }
+
+    // Create parser instance
+    let parser = LogParser::new(db);
+
+    // Parse the log file (rejects duplicates before anything is written)
+    let parse_result = match parser.parse_log(path).await {
+        Ok(result) => result,
+        Err(parser::ParserError::AlreadyImported) => {
+            return Ok(ImportResult {
+                success: false,
+                flight_id: None,
+                message: "This flight log has already been imported".to_string(),
+                point_count: 0,
+            });
+        }
+        Err(e) => {
+            return Ok(ImportResult {
+                success: false,
+                flight_id: None,
+                message: format!("Failed to parse log: {}", e),
+                point_count: 0,
+            });
+        }
+    };
+
+    let (flight_id, point_count) = store_flight(db, path, parse_result)?;
+
+    log::info!(
+        "Successfully imported flight {} with {} points",
+        flight_id,
+        point_count
+    );
+
+    Ok(ImportResult {
+        success: true,
+        flight_id: Some(flight_id),
+        message: format!("Successfully imported {} telemetry points", point_count),
+        point_count,
+    })
+}
+
+/// Recursively collect candidate logs (any supported format) below a directory, sorted by path
+pub fn collect_log_files(dir: &Path, recursive: bool) -> std::io::Result<Vec<PathBuf>> {
+    let mut files = Vec::new();
+    let mut pending = vec![dir.to_path_buf()];
+
+    while let Some(current) = pending.pop() {
+        for entry in fs::read_dir(&current)? {
+            let entry = match entry {
+                Ok(entry) => entry,
+                Err(e) => {
+                    log::warn!("Skipping unreadable entry in {:?}: {}", current, e);
+                    continue;
+                }
+            };
+            let path = entry.path();
+            // file_type() does not follow symlinks, so linked folders cannot loop
+            let file_type = match entry.file_type() {
+                Ok(file_type) => file_type,
+                Err(_) => continue,
+            };
+
+            if file_type.is_dir() {
+                if recursive && !is_hidden(&path) {
+                    pending.push(path);
+                }
+            } else if is_candidate_log(&path) {
+                files.push(path);
+            }
+        }
+    }
+
+    files.sort();
+    Ok(files)
+}
+
+/// Check whether a path looks like a flight log (an extension of a supported
+/// format, not hidden, not truncated)
+pub fn is_candidate_log(path: &Path) -> bool {
+    formats::has_log_extension(path)
+        && !is_hidden(path)
+        && fs::metadata(path)
+            .map(|m| m.is_file() && m.len() >= MIN_LOG_FILE_SIZE)
+            .unwrap_or(false)
+}
+
+fn is_hidden(path: &Path) -> bool {
+    path.file_name()
+        .and_then(|n| n.to_str())
+        .map(|n| n.starts_with('.'))
+        .unwrap_or(false)
+}
+
+/// Import every candidate log in a folder (see [`import_files`])
+pub async fn import_folder(
+    db: Arc<Database>,
+    dir: &Path,
+    recursive: bool,
+) -> Result<FolderImportReport, String> {
+    if !dir.is_dir() {
+        return Err(format!("Not a directory: {}", dir.display()));
+    }
+
+    let files = collect_log_files(dir, recursive)
+        .map_err(|e| format!("Failed to read {}: {}", dir.display(), e))?;
+
+    log::info!("Found {} candidate log files in {:?}", files.len(), dir);
+
+    Ok(import_files(db, files).await)
+}
+
+/// Import a batch of log files.
+///
+/// Parsing runs on the blocking thread pool, one file per core; database
+/// inserts happen on the calling task as each parse completes. A worker only
+/// starts its next file once its last result is stored, so memory stays
+/// bounded by one parsed log per core.
+pub async fn import_files(db: Arc<Database>, files: Vec<PathBuf>) -> FolderImportReport {
+    let jobs = files.into_iter().enumerate().collect();
+    let mut tasks = spawn_parse_tasks(&db, jobs, ParseMode::Import);
+
+    let mut outcomes = Vec::new();
+    let mut seen_hashes = HashSet::new();
+
+    while let Some(job) = tasks.next().await {
+        let outcome = match job.result {
+            Ok(parse_result) => store_parse_result(&db, &job.path, parse_result, &mut seen_hashes),
+            Err(e) => FileImportOutcome {
+                file_path: job.path.to_string_lossy().to_string(),
+                status: classify_parser_error(&e),
+                flight_id: None,
+                point_count: 0,
+                message: e.to_string(),
+            },
+        };
+        outcomes.push((job.key, outcome));
+    }
+
+    outcomes.sort_by_key(|(index, _)| *index);
+
+    let mut report = FolderImportReport::default();
+    for (_, outcome) in outcomes {
+        report.push(outcome);
+    }
+
+    log::info!(
+        "Batch import finished: {} imported, {} duplicates, {} failed of {} files",
+        report.imported,
+        report.duplicates,
+        report.no_telemetry + report.decrypt_failed + report.parse_errors + report.storage_errors,
+        report.total_files
+    );
+
+    report
+}
+
+/// Whether parse tasks should skip logs that are already in the library
+#[derive(Clone, Copy)]
+enum ParseMode {
+    Import,
+    Reparse,
+}
+
+/// A finished parse, tagged with its job key
+///
+/// The worker slot stays taken until the job is dropped, so parsed logs
+/// cannot pile up faster than the caller stores them.
+struct ParsedJob {
+    key: usize,
+    path: PathBuf,
+    result: Result<ParseResult, ParserError>,
+    _permit: Option<OwnedSemaphorePermit>,
+}
+
+/// Parse tasks spawned by [`spawn_parse_tasks`]
+struct ParseTasks {
+    tasks: JoinSet<ParsedJob>,
+    /// Jobs not yielded yet, to report tasks that panicked or were cancelled
+    pending: HashMap<usize, PathBuf>,
+}
+
+impl ParseTasks {
+    /// Next finished job, or `None` once every job was yielded. A task that
+    /// ended without a result yields a parse error for its file.
+    async fn next(&mut self) -> Option<ParsedJob> {
+        while let Some(joined) = self.tasks.join_next().await {
+            match joined {
+                Ok(job) => {
+                    self.pending.remove(&job.key);
+                    return Some(job);
+                }
+                Err(e) => log::error!("Parse task failed: {}", e),
+            }
+        }
+
+        let key = *self.pending.keys().next()?;
+        let path = self.pending.remove(&key)?;
+        Some(ParsedJob {
+            key,
+            path,
+            result: Err(ParserError::Parse("Parser task failed".to_string())),
+            _permit: None,
+        })
+    }
+}
+
+/// Spawn one parse task per job, limited to one concurrent parse per core.
+///
+/// `dji-log-parser` is CPU-bound and synchronous, so each parse runs on the
+/// blocking pool; results are tagged with the job key for the caller.
+fn spawn_parse_tasks(
+    db: &Arc<Database>,
+    jobs: Vec<(usize, PathBuf)>,
+    mode: ParseMode,
+) -> ParseTasks {
+    let workers = std::thread::available_parallelism()
+        .map(|n| n.get())
+        .unwrap_or(4);
+    let semaphore = Arc::new(Semaphore::new(workers));
+    let mut tasks = JoinSet::new();
+    let pending = jobs.iter().cloned().collect();
+
+    for (key, path) in jobs {
+        let db = Arc::clone(db);
+        let semaphore = Arc::clone(&semaphore);
+        tasks.spawn(async move {
+            let permit = semaphore.acquire_owned().await.ok();
+            let handle = tokio::runtime::Handle::current();
+            let parse_path = path.clone();
+            let result = tokio::task::spawn_blocking(move || {
+                let parser = LogParser::new(&db);
+                match mode {
+                    ParseMode::Import => handle.block_on(parser.parse_log(&parse_path)),
+                    ParseMode::Reparse => handle.block_on(parser.reparse_log(&parse_path)),
+                }
+            })
+            .await
+            .unwrap_or_else(|e| Err(ParserError::Parse(format!("Parser task failed: {}", e))));
+            ParsedJob {
+                key,
+                path,
+                result,
+                _permit: permit,
+            }
+        });
+    }
+
+    ParseTasks { tasks, pending }
+}
+
+/// Insert a parsed flight and archive its source file
+fn store_parse_result(
+    db: &Database,
+    path: &Path,
+    parse_result: ParseResult,
+    seen_hashes: &mut HashSet<String>,
+) -> FileImportOutcome {
+    let file_path = path.to_string_lossy().to_string();
+
+    // The same log can appear twice in one batch (e.g. copied folders); both
+    // parses pass the database duplicate check, so dedupe within the batch too
+    if let Some(hash) = &parse_result.metadata.file_hash {
+        if !seen_hashes.insert(hash.clone()) {
+            return FileImportOutcome {
+                file_path,
+                status: FileImportStatus::Duplicate,
+                flight_id: None,
+                point_count: 0,
+                message: "Duplicate of another file in this import".to_string(),
+            };
+        }
+    }
+
+    match store_flight(db, path, parse_result) {
+        Ok((flight_id, point_count)) => FileImportOutcome {
+            file_path,
+            status: FileImportStatus::Imported,
+            flight_id: Some(flight_id),
+            point_count,
+            message: format!("Successfully imported {} telemetry points", point_count),
+        },
+        Err(message) => FileImportOutcome {
+            file_path,
+            status: FileImportStatus::StorageError,
+            flight_id: None,
+            point_count: 0,
+            message,
+        },
+    }
+}
+
+/// Archive the source log and insert the flight with its telemetry atomically.
+///
+/// The log is only archived once parsing succeeded, and the archived copy is
+/// removed again if the database transaction rolls back, so a failed import
+/// leaves neither a flight row nor a stray file in raw_logs.
+fn store_flight(
+    db: &Database,
+    path: &Path,
+    mut parse_result: ParseResult,
+) -> Result<(i64, usize), String> {
+    let archive_path = match LogParser::new(db).archive_log_file(path) {
+        Ok(archive_path) => Some(archive_path),
+        Err(e) => {
+            log::warn!("Failed to archive log file: {}", e);
+            None
+        }
+    };
+    parse_result.metadata.archive_path = archive_path.clone();
+
+    match db.insert_flight_with_telemetry(
+        &parse_result.metadata,
+        &parse_result.points,
+        &parse_result.events,
+    ) {
+        Ok(stored) => Ok(stored),
+        Err(e) => {
+            if let Some(archive_path) = archive_path {
+                if let Err(remove_err) = fs::remove_file(&archive_path) {
+                    log::warn!(
+                        "Failed to remove archived log {}: {}",
+                        archive_path,
+                        remove_err
+                    );
+                }
+            }
+            Err(format!("Failed to store flight: {}", e))
+        }
+    }
+}
+
+/// Map a parser error onto a batch report status
+pub fn classify_parser_error(error: &ParserError) -> FileImportStatus {
+    match error {
+        ParserError::AlreadyImported => FileImportStatus::Duplicate,
+        ParserError::NoTelemetryData => FileImportStatus::NoTelemetry,
+        ParserError::EncryptionKeyRequired | ParserError::Api(_) => FileImportStatus::DecryptFailed,
+        ParserError::Io(_) | ParserError::Parse(_) => FileImportStatus::ParseError,
+    }
+}
+
+/// Index the raw_logs archive by file hash
+fn index_archived_logs(db: &Database) -> HashMap<String, PathBuf> {
+    let mut index = HashMap::new();
+
+    let entries = match fs::read_dir(db.raw_logs_dir()) {
+        Ok(entries) => entries,
+        Err(e) => {
+            log::warn!("Failed to read raw_logs directory: {}", e);
+            return index;
+        }
+    };
+
+    for path in entries.filter_map(|entry| entry.ok().map(|e| e.path())) {
+        if !path.is_file() {
+            continue;
+        }
+        match LogParser::calculate_file_hash(&path) {
+            Ok(hash) => {
+                index.entry(hash).or_insert(path);
+            }
+            Err(e) => log::warn!("Failed to hash archived log {:?}: {}", path, e),
+        }
+    }
+
+    index
+}
+
+/// Locate the archived original of each flight.
+///
+/// Uses the recorded `archive_path` when the file still exists; otherwise
+/// falls back to matching raw_logs by file hash (flights imported before the
+/// path was recorded) and backfills the path on the flight.
+fn archived_logs_for(
+    db: &Database,
+    flight_ids: Option<&[i64]>,
+) -> Result<Vec<(i64, Option<PathBuf>)>, String> {
+    let flights = db
+        .get_flight_archive_refs()
+        .map_err(|e| format!("Failed to get flights: {}", e))?;
+    let mut archive: Option<HashMap<String, PathBuf>> = None;
+    let mut located = Vec::new();
+
+    for (id, hash, archive_path) in flights {
+        if !flight_ids.map(|ids| ids.contains(&id)).unwrap_or(true) {
+            continue;
+        }
+
+        let recorded = archive_path.map(PathBuf::from).filter(|p| p.is_file());
+        if recorded.is_some() {
+            located.push((id, recorded));
+            continue;
+        }
+
+        let index = archive.get_or_insert_with(|| index_archived_logs(db));
+        let found = hash.and_then(|h| index.get(&h).cloned());
+        if let Some(path) = &found {
+            if let Err(e) = db.set_flight_archive_path(id, &path.to_string_lossy()) {
+                log::warn!("Failed to record archive path for flight {}: {}", id, e);
+            }
+        }
+        located.push((id, found));
+    }
+
+    Ok(located)
+}
+
+/// Path of the archived original log of a flight
+pub fn archived_log_path(db: &Database, flight_id: i64) -> Result<PathBuf, String> {
+    archived_logs_for(db, Some(&[flight_id]))?
+        .into_iter()
+        .next()
+        .ok_or_else(|| format!("Flight {} not found", flight_id))?
+        .1
+        .ok_or_else(|| format!("Archived raw log for flight {} not found", flight_id))
+}
+
+/// Copy the archived original log of a flight to `destination`.
+///
+/// The copy is checked against the SHA256 recorded at import time, so the
+/// exported file is byte-identical to what was originally imported.
+pub fn export_archived_log(
+    db: &Database,
+    flight_id: i64,
+    destination: &Path,
+) -> Result<(), String> {
+    let source = archived_log_path(db, flight_id)?;
+
+    let expected_hash = db
+        .get_flight_archive_refs()
+        .map_err(|e| format!("Failed to get flights: {}", e))?
+        .into_iter()
+        .find(|(id, _, _)| *id == flight_id)
+        .and_then(|(_, hash, _)| hash);
+
+    let actual_hash = LogParser::calculate_file_hash(&source)
+        .map_err(|e| format!("Failed to read archived log: {}", e))?;
+    if let Some(expected) = expected_hash {
+        if expected != actual_hash {
+            return Err(format!(
+                "Archived log {} does not match the imported file (hash mismatch)",
+                source.display()
+            ));
+        }
+    }
+
+    fs::copy(&source, destination).map_err(|e| format!("Failed to copy archived log: {}", e))?;
+
+    log::info!(
+        "Exported raw log of flight {} to {:?}",
+        flight_id,
+        destination
+    );
+    Ok(())
+}
+
+/// Re-parse one flight from its archived raw log, keeping id, name and notes
+pub async fn reprocess_flight(
+    db: Arc<Database>,
+    flight_id: i64,
+) -> Result<ReprocessOutcome, String> {
+    let flights = archived_logs_for(&db, Some(&[flight_id]))?;
+    if flights.is_empty() {
+        return Err(format!("Flight {} not found", flight_id));
+    }
+
+    let report = reprocess(db, flights).await;
+    report
+        .flights
+        .into_iter()
+        .next()
+        .ok_or_else(|| format!("Flight {} not found", flight_id))
+}
+
+/// Re-parse every flight in the library from the raw_logs archive
+pub async fn reprocess_all(db: Arc<Database>) -> Result<ReprocessReport, String> {
+    let flights = archived_logs_for(&db, None)?;
+    Ok(reprocess(db, flights).await)
+}
+
+async fn reprocess(db: Arc<Database>, flights: Vec<(i64, Option<PathBuf>)>) -> ReprocessReport {
+    let mut report = ReprocessReport {
+        total_flights: flights.len(),
+        ..Default::default()
+    };
+
+    let mut jobs = Vec::new();
+    let mut ids = Vec::new();
+    for (flight_id, path) in flights {
+        match path {
+            Some(path) => {
+                jobs.push((ids.len(), path));
+                ids.push(flight_id);
+            }
+            None => {
+                report.missing_archive += 1;
+                report.flights.push(ReprocessOutcome {
+                    flight_id,
+                    success: false,
+                    archive_path: None,
+                    point_count: 0,
+                    message: "Archived raw log not found".to_string(),
+                });
+            }
+        }
+    }
+
+    let mut tasks = spawn_parse_tasks(&db, jobs, ParseMode::Reparse);
+
+    while let Some(job) = tasks.next().await {
+        let flight_id = ids[job.key];
+        let archive_path = Some(job.path.to_string_lossy().to_string());
+
+        let stored = job.result.map_err(|e| e.to_string()).and_then(|parsed| {
+            db.replace_flight_data(flight_id, &parsed.metadata, &parsed.points, &parsed.events)
+                .map_err(|e| format!("Failed to store flight: {}", e))
+        });
+
+        let outcome = match stored {
+            Ok(point_count) => {
+                report.reprocessed += 1;
+                ReprocessOutcome {
+                    flight_id,
+                    success: true,
+                    archive_path,
+                    point_count,
+                    message: format!("Re-processed {} telemetry points", point_count),
+                }
+            }
+            Err(message) => {
+                report.failed += 1;
+                ReprocessOutcome {
+                    flight_id,
+                    success: false,
+                    archive_path,
+                    point_count: 0,
+                    message,
+                }
+            }
+        };
+        report.flights.push(outcome);
+    }
+
+    report.flights.sort_by_key(|outcome| outcome.flight_id);
+
+    log::info!(
+        "Re-processing finished: {} updated, {} missing archive, {} failed",
+        report.reprocessed,
+        report.missing_archive,
+        report.failed
+    );
+
+    report
+}
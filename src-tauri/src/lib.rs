@@ -1,7 +1,19 @@
+pub mod analysis;
 pub mod api;
+pub mod backup;
+pub mod battery;
+pub mod csv_import;
 pub mod database;
+pub mod export;
+pub mod formats;
+pub mod importer;
+pub mod logbook;
+pub mod maintenance;
 pub mod models;
 pub mod parser;
+pub mod solar;
+#[cfg(test)]
+pub(crate) mod test_support;
 
 pub use database::Database;
 pub use models::*;
@@ -0,0 +1,352 @@
+//! Pilot logbooks and currency totals.
+//!
+//! Logbook rows come from the database with takeoff, landing and light
+//! conditions already worked out. This module totals them per pilot and writes them
+//! as CSV or as a printable HTML table, laid out like an EASA remote pilot
+//! logbook or a FAA Part 107 flight log.
+
+use std::io::{self, Write};
+use std::str::FromStr;
+
+use chrono::{DateTime, Duration, Utc};
+use thiserror::Error;
+
+use crate::database::{Database, DatabaseError};
+use crate::export::{escape_csv, escape_xml};
+use crate::models::{Daylight, FlightQuery, LogbookEntry, Pilot};
+
+/// Window for the recent-experience totals
+pub const RECENT_DAYS: i64 = 90;
+
+const FEET_PER_METER: f64 = 3.28084;
+
+#[derive(Error, Debug)]
+pub enum LogbookError {
+    #[error("Database error: {0}")]
+    Database(#[from] DatabaseError),
+
+    #[error("IO error: {0}")]
+    Io(#[from] io::Error),
+
+    #[error("Unsupported logbook style: {0} (expected easa or part107)")]
+    UnsupportedStyle(String),
+}
+
+/// Column layout of a written logbook
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum LogbookStyle {
+    /// Metric, flight time as h:mm
+    #[default]
+    Easa,
+    /// Imperial, flight time in minutes
+    Part107,
+}
+
+impl FromStr for LogbookStyle {
+    type Err = LogbookError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_lowercase().as_str() {
+            "easa" => Ok(LogbookStyle::Easa),
+            "part107" | "faa" => Ok(LogbookStyle::Part107),
+            other => Err(LogbookError::UnsupportedStyle(other.to_string())),
+        }
+    }
+}
+
+impl LogbookStyle {
+    fn headers(&self) -> [&'static str; 10] {
+        match self {
+            LogbookStyle::Easa => [
+                "Date",
+                "Remote pilot",
+                "Aircraft",
+                "Serial",
+                "Takeoff location",
+                "Takeoff (UTC)",
+                "Landing (UTC)",
+                "Flight time",
+                "Day/Night",
+                "Max height (m)",
+            ],
+            LogbookStyle::Part107 => [
+                "Date",
+                "Remote PIC",
+                "sUAS",
+                "Serial",
+                "Launch location",
+                "Launch (UTC)",
+                "Recovery (UTC)",
+                "Duration (min)",
+                "Day/Night",
+                "Max altitude (ft AGL)",
+            ],
+        }
+    }
+
+    fn flight_time(&self, secs: f64) -> String {
+        match self {
+            LogbookStyle::Easa => {
+                let minutes = (secs / 60.0).round() as i64;
+                format!("{}:{:02}", minutes / 60, minutes % 60)
+            }
+            LogbookStyle::Part107 => format!("{:.1}", secs / 60.0),
+        }
+    }
+
+    fn height(&self, meters: f64) -> String {
+        match self {
+            LogbookStyle::Easa => format!("{:.0}", meters),
+            LogbookStyle::Part107 => format!("{:.0}", meters * FEET_PER_METER),
+        }
+    }
+
+    /// Cells of one entry, in header order, followed by the remarks
+    fn row(&self, entry: &LogbookEntry) -> Vec<String> {
+        let time =
+            |t: Option<DateTime<Utc>>| t.map(|t| t.format("%H:%M").to_string()).unwrap_or_default();
+        let aircraft = match (&entry.drone_model, &entry.aircraft_name) {
+            (Some(model), Some(name)) => format!("{} ({})", model, name),
+            (model, name) => model.clone().or(name.clone()).unwrap_or_default(),
+        };
+        let location = match (entry.home_lat, entry.home_lon) {
+            (Some(lat), Some(lon)) => format!("{:.5}, {:.5}", lat, lon),
+            _ => String::new(),
+        };
+
+        vec![
+            entry
+                .takeoff_time
+                .map(|t| t.format("%Y-%m-%d").to_string())
+                .unwrap_or_default(),
+            entry.pilot_name.clone().unwrap_or_default(),
+            aircraft,
+            entry.drone_serial.clone().unwrap_or_default(),
+            location,
+            time(entry.takeoff_time),
+            time(entry.landing_time),
+            self.flight_time(entry.duration_secs),
+            match entry.daylight {
+                Some(Daylight::Day) => "Day",
+                Some(Daylight::CivilTwilight) => "Civil twilight",
+                Some(Daylight::Night) => "Night",
+                None => "",
+            }
+            .to_string(),
+            entry
+                .max_altitude
+                .map(|m| self.height(m))
+                .unwrap_or_default(),
+            entry
+                .notes
+                .clone()
+                .unwrap_or_else(|| entry.display_name.clone()),
+        ]
+    }
+}
+
+fn is_night(entry: &LogbookEntry) -> bool {
+    entry.daylight == Some(Daylight::Night)
+}
+
+/// Fill in a pilot's totals from their logbook entries
+pub fn tally(pilot: &mut Pilot, entries: &[&LogbookEntry], now: DateTime<Utc>) {
+    let recent_since = now - Duration::days(RECENT_DAYS);
+
+    pilot.flight_count = entries.len() as i64;
+    pilot.total_duration_secs = entries.iter().map(|e| e.duration_secs).sum();
+    pilot.night_flights = entries.iter().filter(|e| is_night(e)).count() as i64;
+
+    let recent: Vec<&&LogbookEntry> = entries
+        .iter()
+        .filter(|e| {
+            e.takeoff_time
+                .is_some_and(|t| t >= recent_since && t <= now)
+        })
+        .collect();
+    pilot.recent_flights = recent.len() as i64;
+    pilot.recent_duration_secs = recent.iter().map(|e| e.duration_secs).sum();
+    pilot.recent_night_flights = recent.iter().filter(|e| is_night(e)).count() as i64;
+
+    pilot.last_flight = entries
+        .iter()
+        .filter_map(|e| e.takeoff_time)
+        .max()
+        .map(|t| t.to_rfc3339());
+}
+
+/// Write the logbook of the flights matching `query`, oldest first.
+/// Returns the number of flights written.
+///
+/// HTML output is a standalone page with a totals row, meant to be printed.
+pub fn export_logbook<W: Write>(
+    db: &Database,
+    query: &FlightQuery,
+    style: LogbookStyle,
+    html: bool,
+    writer: &mut W,
+) -> Result<usize, LogbookError> {
+    let entries = db.get_logbook(query)?;
+    let pilot = match query.pilot_id {
+        Some(id) => db.get_pilots()?.into_iter().find(|p| p.id == id),
+        None => None,
+    };
+
+    if html {
+        write_html(&entries, pilot.as_ref(), style, writer)?;
+    } else {
+        write_csv(&entries, style, writer)?;
+    }
+    Ok(entries.len())
+}
+
+fn write_csv<W: Write>(
+    entries: &[LogbookEntry],
+    style: LogbookStyle,
+    writer: &mut W,
+) -> io::Result<()> {
+    writeln!(writer, "{},Remarks", style.headers().join(","))?;
+    for entry in entries {
+        let row: Vec<String> = style
+            .row(entry)
+            .iter()
+            .map(|cell| escape_csv(cell))
+            .collect();
+        writeln!(writer, "{}", row.join(","))?;
+    }
+    Ok(())
+}
+
+fn write_html<W: Write>(
+    entries: &[LogbookEntry],
+    pilot: Option<&Pilot>,
+    style: LogbookStyle,
+    writer: &mut W,
+) -> io::Result<()> {
+    let title = match pilot {
+        Some(pilot) => format!("Pilot logbook: {}", pilot.name),
+        None => "Pilot logbook".to_string(),
+    };
+
+    writeln!(writer, "<!DOCTYPE html>")?;
+    writeln!(
+        writer,
+        "<html><head><meta charset=\"utf-8\"><title>{}</title>",
+        escape_xml(&title)
+    )?;
+    writeln!(
+        writer,
+        "<style>\
+         body{{font-family:sans-serif;font-size:11px}}\
+         table{{border-collapse:collapse;width:100%}}\
+         th,td{{border:1px solid #999;padding:3px 5px;text-align:left}}\
+         th{{background:#eee}}\
+         tfoot td{{font-weight:bold}}\
+         @page{{size:landscape}}\
+         </style></head><body>"
+    )?;
+    writeln!(writer, "<h1>{}</h1>", escape_xml(&title))?;
+    if let Some(license) = pilot.and_then(|p| p.license_number.as_deref()) {
+        writeln!(
+            writer,
+            "<p>Licence / certificate: {}</p>",
+            escape_xml(license)
+        )?;
+    }
+
+    writeln!(writer, "<table><thead><tr>")?;
+    for header in style.headers().iter().chain(std::iter::once(&"Remarks")) {
+        write!(writer, "<th>{}</th>", escape_xml(header))?;
+    }
+    writeln!(writer, "</tr></thead><tbody>")?;
+    for entry in entries {
+        write!(writer, "<tr>")?;
+        for cell in style.row(entry) {
+            write!(writer, "<td>{}</td>", escape_xml(&cell))?;
+        }
+        writeln!(writer, "</tr>")?;
+    }
+
+    let total_secs: f64 = entries.iter().map(|e| e.duration_secs).sum();
+    let night = entries.iter().filter(|e| is_night(e)).count();
+    writeln!(
+        writer,
+        "</tbody><tfoot><tr><td colspan=\"7\">Total: {} flights</td><td>{}</td><td>{} night</td><td colspan=\"2\"></td></tr></tfoot>",
+        entries.len(),
+        style.flight_time(total_secs),
+        night
+    )?;
+    writeln!(writer, "</table></body></html>")?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn entry(day: u32, duration_secs: f64, daylight: Daylight) -> LogbookEntry {
+        let takeoff = Utc.with_ymd_and_hms(2024, 5, day, 20, 0, 0).unwrap();
+        LogbookEntry {
+            flight_id: day as i64,
+            display_name: format!("Flight {}", day),
+            pilot_id: Some(1),
+            pilot_name: Some("Ann, Pilot".to_string()),
+            drone_model: Some("Mavic3".to_string()),
+            drone_serial: Some("AC-1".to_string()),
+            aircraft_name: None,
+            takeoff_time: Some(takeoff),
+            landing_time: Some(takeoff + Duration::seconds(duration_secs as i64)),
+            duration_secs,
+            home_lat: Some(47.0),
+            home_lon: Some(8.0),
+            max_altitude: Some(100.0),
+            total_distance: None,
+            daylight: Some(daylight),
+            notes: None,
+        }
+    }
+
+    #[test]
+    fn test_tally_counts_recent_and_night_flights() {
+        let entries = [
+            entry(1, 600.0, Daylight::Day),
+            entry(20, 1200.0, Daylight::Night),
+            entry(30, 300.0, Daylight::CivilTwilight),
+        ];
+        let refs: Vec<&LogbookEntry> = entries.iter().collect();
+        let mut pilot = Pilot::default();
+
+        // May 1 falls outside the 90 days before July 31
+        let now = Utc.with_ymd_and_hms(2024, 7, 31, 0, 0, 0).unwrap();
+        tally(&mut pilot, &refs, now);
+
+        assert_eq!(pilot.flight_count, 3);
+        assert_eq!(pilot.total_duration_secs, 2100.0);
+        assert_eq!(pilot.night_flights, 1);
+        assert_eq!(pilot.recent_flights, 2);
+        assert_eq!(pilot.recent_duration_secs, 1500.0);
+        assert_eq!(pilot.recent_night_flights, 1);
+        assert!(pilot.last_flight.unwrap().starts_with("2024-05-30T20:00"));
+    }
+
+    #[test]
+    fn test_csv_layouts() {
+        let entries = [entry(1, 4500.0, Daylight::Night)];
+
+        let mut out = Vec::new();
+        write_csv(&entries, LogbookStyle::Easa, &mut out).unwrap();
+        let text = String::from_utf8(out).unwrap();
+        let row = text.lines().nth(1).unwrap();
+        assert_eq!(
+            row,
+            "2024-05-01,\"Ann, Pilot\",Mavic3,AC-1,\"47.00000, 8.00000\",20:00,21:15,1:15,Night,100,Flight 1"
+        );
+
+        let mut out = Vec::new();
+        write_csv(&entries, LogbookStyle::Part107, &mut out).unwrap();
+        let text = String::from_utf8(out).unwrap();
+        assert!(text.starts_with("Date,Remote PIC,sUAS"));
+        assert!(text.contains(",75.0,Night,328,"));
+    }
+}
@@ -5,22 +5,42 @@
 
 #![cfg_attr(not(debug_assertions), windows_subsystem = "windows")]
 
+mod analysis;
 mod api;
+mod backup;
+mod battery;
+mod csv_import;
 mod database;
+mod export;
+mod formats;
+mod importer;
+mod logbook;
+mod maintenance;
 mod models;
 mod parser;
+mod solar;
+#[cfg(test)]
+mod test_support;
 
+use std::fs::File;
+use std::io::{BufWriter, Write};
 use std::path::PathBuf;
 use std::sync::Arc;
 
+use log::LevelFilter;
 use tauri::{AppHandle, Manager, State};
 use tauri_plugin_log::{Target, TargetKind};
-use log::LevelFilter;
 
-use database::{Database, DatabaseError};
-use models::{Flight, FlightDataResponse, ImportResult, OverviewStats, TelemetryData};
-use parser::LogParser;
 use api::DjiApi;
+use backup::{BackupError, BackupManifest, RestoreMode, RestoreReport};
+use database::{Database, DatabaseError, ParquetPartition};
+use export::{AltitudeMode, ExportError, ExportFormat, ExportOptions};
+use logbook::{LogbookError, LogbookStyle};
+use models::{
+    Aircraft, BatteryDetail, BatteryPack, Flight, FlightDataResponse, FlightEventEntry,
+    FlightEventQuery, FlightPage, FlightQuery, FolderImportReport, ImportResult, LogbookEntry,
+    OverviewStats, ParquetImportReport, Pilot, ReprocessOutcome, ReprocessReport, TelemetryData,
+};
 
 /// Application state containing the database connection
 pub struct AppState {
@@ -49,90 +69,75 @@ fn init_database(app: &AppHandle) -> Result<Database, String> {
 /// Import a DJI flight log file
 ///
 /// This command:
-/// 1. Copies the file to the app's raw_logs directory
-/// 2. Parses the log file (handling V13+ encryption if needed)
-/// 3. Bulk inserts telemetry data into DuckDB
+/// 1. Parses the log file (handling V13+ encryption if needed)
+/// 2. Copies the file to the app's raw_logs directory
+/// 3. Inserts the flight and its telemetry into DuckDB in one transaction
 /// 4. Returns the new flight ID
 #[tauri::command]
 async fn import_log(file_path: String, state: State<'_, AppState>) -> Result<ImportResult, String> {
     log::info!("Importing log file: {}", file_path);
 
-    let path = PathBuf::from(&file_path);
-
-    if !path.exists() {
-        return Ok(ImportResult {
-            success: false,
-            flight_id: None,
-            message: "File not found".to_string(),
-            point_count: 0,
-        });
-    }
-
-    // Create parser instance
-    let parser = LogParser::new(&state.db);
+    importer::import_log_file(&state.db, &PathBuf::from(&file_path)).await
+}
 
-    // Archive the original file
-    if let Err(e) = parser.archive_log_file(&path) {
-        log::warn!("Failed to archive log file: {}", e);
-    }
+/// Import every DJI log found in a folder (e.g. a copied FlightRecord folder)
+///
+/// Logs are parsed in parallel across cores; the returned report lists the
+/// outcome of each file along with per-status totals.
+#[tauri::command]
+async fn import_folder(
+    folder_path: String,
+    recursive: Option<bool>,
+    state: State<'_, AppState>,
+) -> Result<FolderImportReport, String> {
+    log::info!("Importing log folder: {}", folder_path);
+
+    importer::import_folder(
+        Arc::clone(&state.db),
+        &PathBuf::from(&folder_path),
+        recursive.unwrap_or(true),
+    )
+    .await
+}
 
-    // Parse the log file
-    let parse_result = match parser.parse_log(&path).await {
-        Ok(result) => result,
-        Err(parser::ParserError::AlreadyImported) => {
-            return Ok(ImportResult {
-                success: false,
-                flight_id: None,
-                message: "This flight log has already been imported".to_string(),
-                point_count: 0,
-            });
-        }
-        Err(e) => {
-            return Ok(ImportResult {
-                success: false,
-                flight_id: None,
-                message: format!("Failed to parse log: {}", e),
-                point_count: 0,
-            });
-        }
-    };
+/// Re-parse a flight from its archived raw log
+///
+/// Replaces telemetry and derived metadata (e.g. after a parser upgrade)
+/// while preserving the flight id, display name and notes.
+#[tauri::command]
+async fn reprocess_flight(
+    flight_id: i64,
+    state: State<'_, AppState>,
+) -> Result<ReprocessOutcome, String> {
+    importer::reprocess_flight(Arc::clone(&state.db), flight_id).await
+}
 
-    // Insert flight metadata
-    let flight_id = state
-        .db
-        .insert_flight(&parse_result.metadata)
-        .map_err(|e| format!("Failed to insert flight: {}", e))?;
+/// Re-parse every flight in the library from the raw_logs archive
+#[tauri::command]
+async fn reprocess_all_flights(state: State<'_, AppState>) -> Result<ReprocessReport, String> {
+    importer::reprocess_all(Arc::clone(&state.db)).await
+}
 
-    // Bulk insert telemetry data
-    let point_count = state
+/// Get a page of flights for the sidebar list, filtered and sorted by the
+/// backend (all flights, newest first, without a query)
+#[tauri::command]
+async fn get_flights(
+    query: Option<FlightQuery>,
+    state: State<'_, AppState>,
+) -> Result<FlightPage, String> {
+    state
         .db
-        .bulk_insert_telemetry(flight_id, &parse_result.points)
-        .map_err(|e| format!("Failed to insert telemetry: {}", e))?;
-
-    log::info!(
-        "Successfully imported flight {} with {} points",
-        flight_id,
-        point_count
-    );
-
-    Ok(ImportResult {
-        success: true,
-        flight_id: Some(flight_id),
-        message: format!(
-            "Successfully imported {} telemetry points",
-            point_count
-        ),
-        point_count,
-    })
+        .query_flights(&query.unwrap_or_default())
+        .map_err(|e| format!("Failed to get flights: {}", e))
 }
 
-/// Get all flights for the sidebar list
+/// Get a single flight's metadata
 #[tauri::command]
-async fn get_flights(state: State<'_, AppState>) -> Result<Vec<Flight>, String> {
+async fn get_flight(flight_id: i64, state: State<'_, AppState>) -> Result<Flight, String> {
     state
         .db
-        .get_all_flights()
-        .map_err(|e| format!("Failed to get flights: {}", e))
+        .get_flight(flight_id)
+        .map_err(|e| format!("Failed to get flight: {}", e))
 }
 
 /// Get complete flight data for visualization
@@ -150,15 +155,10 @@ async fn get_flight_data(
     log::debug!("Fetching flight data for ID: {}", flight_id);
 
     // Get flight metadata
-    let flights = state
+    let flight = state
         .db
-        .get_all_flights()
-        .map_err(|e| format!("Failed to get flights: {}", e))?;
-
-    let flight = flights
-        .into_iter()
-        .find(|f| f.id == flight_id)
-        .ok_or_else(|| format!("Flight {} not found", flight_id))?;
+        .get_flight(flight_id)
+        .map_err(|e| format!("Failed to get flight: {}", e))?;
 
     // Get telemetry with automatic downsampling
     let telemetry_records = state
@@ -178,22 +178,65 @@ async fn get_flight_data(
         .get_flight_track(flight_id, Some(2000))
         .map_err(|e| format!("Failed to get track: {}", e))?;
 
+    // Photo and video markers for the map
+    let camera_events = state
+        .db
+        .get_camera_events(flight_id)
+        .map_err(|e| format!("Failed to get camera events: {}", e))?;
+
+    // Home point records and RTH phases
+    let home_events = state
+        .db
+        .get_home_events(flight_id)
+        .map_err(|e| format!("Failed to get home events: {}", e))?;
+
+    // App tips and warnings
+    let events = state
+        .db
+        .get_flight_events(flight_id)
+        .map_err(|e| format!("Failed to get flight events: {}", e))?;
+
+    // Anomalies flagged at import
+    let findings = state
+        .db
+        .get_flight_findings(flight_id)
+        .map_err(|e| format!("Failed to get findings: {}", e))?;
+
     Ok(FlightDataResponse {
         flight,
         telemetry,
         track,
+        camera_events,
+        home_events,
+        events,
+        findings,
     })
 }
 
-/// Get overview stats for all flights
+/// Get overview stats, for all flights or those matching the filters
 #[tauri::command]
-async fn get_overview_stats(state: State<'_, AppState>) -> Result<OverviewStats, String> {
+async fn get_overview_stats(
+    query: Option<FlightQuery>,
+    state: State<'_, AppState>,
+) -> Result<OverviewStats, String> {
     state
         .db
-        .get_overview_stats()
+        .get_overview_stats(&query.unwrap_or_default())
         .map_err(|e| format!("Failed to get overview stats: {}", e))
 }
 
+/// Query app tips and warnings across all flights
+#[tauri::command]
+async fn query_flight_events(
+    query: FlightEventQuery,
+    state: State<'_, AppState>,
+) -> Result<Vec<FlightEventEntry>, String> {
+    state
+        .db
+        .query_flight_events(&query)
+        .map_err(|e| format!("Failed to query flight events: {}", e))
+}
+
 /// Delete a flight and all its telemetry data
 #[tauri::command]
 async fn delete_flight(flight_id: i64, state: State<'_, AppState>) -> Result<bool, String> {
@@ -233,6 +276,443 @@ async fn update_flight_name(
         .map_err(|e| format!("Failed to update flight name: {}", e))
 }
 
+/// Update the notes of a flight (blank clears them)
+#[tauri::command]
+async fn update_flight_notes(
+    flight_id: i64,
+    notes: String,
+    state: State<'_, AppState>,
+) -> Result<bool, String> {
+    state
+        .db
+        .update_flight_notes(flight_id, &notes)
+        .map(|_| true)
+        .map_err(|e| format!("Failed to update flight notes: {}", e))
+}
+
+/// Replace the tags of a flight, returning the stored tags
+#[tauri::command]
+async fn set_flight_tags(
+    flight_id: i64,
+    tags: Vec<String>,
+    state: State<'_, AppState>,
+) -> Result<Vec<String>, String> {
+    state
+        .db
+        .set_flight_tags(flight_id, &tags)
+        .map_err(|e| format!("Failed to update flight tags: {}", e))
+}
+
+/// Get every tag in use
+#[tauri::command]
+async fn get_all_tags(state: State<'_, AppState>) -> Result<Vec<String>, String> {
+    state
+        .db
+        .get_all_tags()
+        .map_err(|e| format!("Failed to get tags: {}", e))
+}
+
+/// Get the names of the defined custom fields
+#[tauri::command]
+async fn get_custom_fields(state: State<'_, AppState>) -> Result<Vec<String>, String> {
+    state
+        .db
+        .get_custom_fields()
+        .map_err(|e| format!("Failed to get custom fields: {}", e))
+}
+
+/// Define a new custom field (e.g. client or job number)
+#[tauri::command]
+async fn add_custom_field(name: String, state: State<'_, AppState>) -> Result<bool, String> {
+    state
+        .db
+        .add_custom_field(&name)
+        .map(|_| true)
+        .map_err(|e| format!("Failed to add custom field: {}", e))
+}
+
+/// Remove a custom field and its values
+#[tauri::command]
+async fn delete_custom_field(name: String, state: State<'_, AppState>) -> Result<bool, String> {
+    state
+        .db
+        .delete_custom_field(&name)
+        .map(|_| true)
+        .map_err(|e| format!("Failed to delete custom field: {}", e))
+}
+
+/// Set a custom field value on a flight (blank clears it)
+#[tauri::command]
+async fn set_flight_custom_field(
+    flight_id: i64,
+    field: String,
+    value: String,
+    state: State<'_, AppState>,
+) -> Result<bool, String> {
+    state
+        .db
+        .set_flight_custom_field(flight_id, &field, &value)
+        .map(|_| true)
+        .map_err(|e| format!("Failed to update custom field: {}", e))
+}
+
+/// Get every battery pack with its cycle count and health
+#[tauri::command]
+async fn get_batteries(state: State<'_, AppState>) -> Result<Vec<BatteryPack>, String> {
+    state
+        .db
+        .get_batteries()
+        .map_err(|e| format!("Failed to get batteries: {}", e))
+}
+
+/// Get a battery pack with its per-flight history
+#[tauri::command]
+async fn get_battery(serial: String, state: State<'_, AppState>) -> Result<BatteryDetail, String> {
+    state
+        .db
+        .get_battery(&serial)
+        .map_err(|e| format!("Failed to get battery: {}", e))
+}
+
+/// Name a battery pack (blank clears the name)
+#[tauri::command]
+async fn rename_battery(
+    serial: String,
+    name: String,
+    state: State<'_, AppState>,
+) -> Result<bool, String> {
+    state
+        .db
+        .rename_battery(&serial, &name)
+        .map(|_| true)
+        .map_err(|e| format!("Failed to rename battery: {}", e))
+}
+
+/// Update the notes of a battery pack (blank clears them)
+#[tauri::command]
+async fn update_battery_notes(
+    serial: String,
+    notes: String,
+    state: State<'_, AppState>,
+) -> Result<bool, String> {
+    state
+        .db
+        .update_battery_notes(&serial, &notes)
+        .map(|_| true)
+        .map_err(|e| format!("Failed to update battery notes: {}", e))
+}
+
+/// Retire a battery pack or put it back in service
+#[tauri::command]
+async fn set_battery_retired(
+    serial: String,
+    retired: bool,
+    state: State<'_, AppState>,
+) -> Result<bool, String> {
+    state
+        .db
+        .set_battery_retired(&serial, retired)
+        .map(|_| true)
+        .map_err(|e| format!("Failed to update battery: {}", e))
+}
+
+/// Get every aircraft with airframe hours, firmware and maintenance status
+#[tauri::command]
+async fn get_aircraft(state: State<'_, AppState>) -> Result<Vec<Aircraft>, String> {
+    state
+        .db
+        .get_aircraft()
+        .map_err(|e| format!("Failed to get aircraft: {}", e))
+}
+
+/// Name an aircraft (blank clears the name)
+#[tauri::command]
+async fn rename_aircraft(
+    serial: String,
+    name: String,
+    state: State<'_, AppState>,
+) -> Result<bool, String> {
+    state
+        .db
+        .rename_aircraft(&serial, &name)
+        .map(|_| true)
+        .map_err(|e| format!("Failed to rename aircraft: {}", e))
+}
+
+/// Set the notes of an aircraft (blank clears them)
+#[tauri::command]
+async fn update_aircraft_notes(
+    serial: String,
+    notes: String,
+    state: State<'_, AppState>,
+) -> Result<bool, String> {
+    state
+        .db
+        .update_aircraft_notes(&serial, &notes)
+        .map(|_| true)
+        .map_err(|e| format!("Failed to update aircraft notes: {}", e))
+}
+
+/// Add a maintenance item to an aircraft, returning its id
+#[tauri::command]
+async fn add_maintenance_item(
+    serial: String,
+    name: String,
+    interval_hours: Option<f64>,
+    interval_flights: Option<i64>,
+    state: State<'_, AppState>,
+) -> Result<i64, String> {
+    state
+        .db
+        .add_maintenance_item(&serial, &name, interval_hours, interval_flights)
+        .map_err(|e| format!("Failed to add maintenance item: {}", e))
+}
+
+/// Mark a maintenance item as done, now or at the given date
+#[tauri::command]
+async fn complete_maintenance_item(
+    item_id: i64,
+    done_at: Option<String>,
+    state: State<'_, AppState>,
+) -> Result<bool, String> {
+    state
+        .db
+        .complete_maintenance_item(item_id, done_at.as_deref())
+        .map(|_| true)
+        .map_err(|e| format!("Failed to complete maintenance item: {}", e))
+}
+
+/// Remove a maintenance item
+#[tauri::command]
+async fn delete_maintenance_item(item_id: i64, state: State<'_, AppState>) -> Result<bool, String> {
+    state
+        .db
+        .delete_maintenance_item(item_id)
+        .map(|_| true)
+        .map_err(|e| format!("Failed to delete maintenance item: {}", e))
+}
+
+/// Get every pilot with flight, night and 90-day totals
+#[tauri::command]
+async fn get_pilots(state: State<'_, AppState>) -> Result<Vec<Pilot>, String> {
+    state
+        .db
+        .get_pilots()
+        .map_err(|e| format!("Failed to get pilots: {}", e))
+}
+
+/// Add a pilot, returning its id
+#[tauri::command]
+async fn add_pilot(
+    name: String,
+    license_number: String,
+    state: State<'_, AppState>,
+) -> Result<i64, String> {
+    state
+        .db
+        .add_pilot(&name, &license_number)
+        .map_err(|e| format!("Failed to add pilot: {}", e))
+}
+
+/// Update a pilot's name, licence number and notes
+#[tauri::command]
+async fn update_pilot(
+    pilot_id: i64,
+    name: String,
+    license_number: String,
+    notes: String,
+    state: State<'_, AppState>,
+) -> Result<bool, String> {
+    state
+        .db
+        .update_pilot(pilot_id, &name, &license_number, &notes)
+        .map(|_| true)
+        .map_err(|e| format!("Failed to update pilot: {}", e))
+}
+
+/// Delete a pilot; their flights become unassigned
+#[tauri::command]
+async fn delete_pilot(pilot_id: i64, state: State<'_, AppState>) -> Result<bool, String> {
+    state
+        .db
+        .delete_pilot(pilot_id)
+        .map(|_| true)
+        .map_err(|e| format!("Failed to delete pilot: {}", e))
+}
+
+/// Assign a flight to a pilot (null unassigns it)
+#[tauri::command]
+async fn set_flight_pilot(
+    flight_id: i64,
+    pilot_id: Option<i64>,
+    state: State<'_, AppState>,
+) -> Result<bool, String> {
+    state
+        .db
+        .set_flight_pilot(flight_id, pilot_id)
+        .map(|_| true)
+        .map_err(|e| format!("Failed to assign pilot: {}", e))
+}
+
+/// Assign the flights of an aircraft to a pilot (null removes the rule)
+///
+/// Returns how many already imported flights were assigned.
+#[tauri::command]
+async fn set_aircraft_pilot(
+    serial: String,
+    pilot_id: Option<i64>,
+    state: State<'_, AppState>,
+) -> Result<usize, String> {
+    state
+        .db
+        .set_aircraft_pilot(&serial, pilot_id)
+        .map_err(|e| format!("Failed to assign pilot: {}", e))
+}
+
+/// Get logbook rows for the flights matching a query, oldest first
+#[tauri::command]
+async fn get_logbook(
+    query: FlightQuery,
+    state: State<'_, AppState>,
+) -> Result<Vec<LogbookEntry>, String> {
+    state
+        .db
+        .get_logbook(&query)
+        .map_err(|e| format!("Failed to get logbook: {}", e))
+}
+
+/// Write the pilot logbook of the flights matching a query
+///
+/// `style` is `easa` or `part107`; `html` writes a printable page instead of
+/// CSV. Returns the number of flights written.
+#[tauri::command]
+async fn export_logbook(
+    query: FlightQuery,
+    style: String,
+    html: bool,
+    destination_path: String,
+    state: State<'_, AppState>,
+) -> Result<usize, String> {
+    let style: LogbookStyle = style.parse().map_err(|e: LogbookError| e.to_string())?;
+
+    let file = File::create(&destination_path)
+        .map_err(|e| format!("Failed to create {}: {}", destination_path, e))?;
+    let mut writer = BufWriter::new(file);
+
+    logbook::export_logbook(&state.db, &query, style, html, &mut writer)
+        .and_then(|count| writer.flush().map(|_| count).map_err(Into::into))
+        .map_err(|e| format!("Logbook export failed: {}", e))
+}
+
+/// Get the archived original log path of a flight (e.g. to reveal it in the file manager)
+#[tauri::command]
+async fn get_flight_raw_log(flight_id: i64, state: State<'_, AppState>) -> Result<String, String> {
+    importer::archived_log_path(&state.db, flight_id).map(|path| path.to_string_lossy().to_string())
+}
+
+/// Copy the archived original log of a flight to a user-chosen destination
+#[tauri::command]
+async fn export_flight_raw_log(
+    flight_id: i64,
+    destination_path: String,
+    state: State<'_, AppState>,
+) -> Result<bool, String> {
+    importer::export_archived_log(&state.db, flight_id, &PathBuf::from(destination_path))
+        .map(|_| true)
+}
+
+/// Export one or more flights at full resolution (csv, gpx, kml, geojson or json)
+///
+/// Telemetry is streamed from the database straight into the destination file.
+#[tauri::command]
+async fn export_flights(
+    flight_ids: Vec<i64>,
+    format: String,
+    destination_path: String,
+    altitude_mode: Option<String>,
+    state: State<'_, AppState>,
+) -> Result<bool, String> {
+    let format: ExportFormat = format.parse().map_err(|e: ExportError| e.to_string())?;
+    let options = ExportOptions {
+        altitude_mode: match altitude_mode {
+            Some(mode) => mode.parse().map_err(|e: ExportError| e.to_string())?,
+            None => AltitudeMode::default(),
+        },
+    };
+
+    let file = File::create(&destination_path)
+        .map_err(|e| format!("Failed to create {}: {}", destination_path, e))?;
+    let mut writer = BufWriter::new(file);
+
+    export::export_flights(&state.db, &flight_ids, format, &options, &mut writer)
+        .and_then(|_| writer.flush().map_err(Into::into))
+        .map(|_| true)
+        .map_err(|e| format!("Export failed: {}", e))
+}
+
+/// Export the whole logbook to a directory of Parquet files
+///
+/// `partition` splits the per-flight tables by `flight` or `month` (default: none).
+/// Returns the number of exported flights.
+#[tauri::command]
+async fn export_parquet(
+    destination_dir: String,
+    partition: Option<String>,
+    state: State<'_, AppState>,
+) -> Result<usize, String> {
+    let partition = match partition {
+        Some(partition) => partition
+            .parse()
+            .map_err(|e: DatabaseError| e.to_string())?,
+        None => ParquetPartition::default(),
+    };
+
+    state
+        .db
+        .export_parquet(&PathBuf::from(destination_dir), partition)
+        .map_err(|e| format!("Parquet export failed: {}", e))
+}
+
+/// Import a Parquet logbook export, skipping flights already in the library
+#[tauri::command]
+async fn import_parquet(
+    source_dir: String,
+    state: State<'_, AppState>,
+) -> Result<ParquetImportReport, String> {
+    state
+        .db
+        .import_parquet(&PathBuf::from(source_dir))
+        .map_err(|e| format!("Parquet import failed: {}", e))
+}
+
+/// Write a backup archive of the whole logbook (database, raw logs, config)
+#[tauri::command]
+async fn create_backup(
+    destination_path: String,
+    state: State<'_, AppState>,
+) -> Result<BackupManifest, String> {
+    backup::create_backup(&state.db, &PathBuf::from(destination_path))
+        .map_err(|e| format!("Backup failed: {}", e))
+}
+
+/// Restore a backup archive, replacing the library or merging into it
+///
+/// `mode` is `replace` (default) or `merge`.
+#[tauri::command]
+async fn restore_backup(
+    archive_path: String,
+    mode: Option<String>,
+    state: State<'_, AppState>,
+) -> Result<RestoreReport, String> {
+    let mode = match mode {
+        Some(mode) => mode.parse().map_err(|e: BackupError| e.to_string())?,
+        None => RestoreMode::default(),
+    };
+
+    backup::restore_backup(&state.db, &PathBuf::from(archive_path), mode)
+        .map_err(|e| format!("Restore failed: {}", e))
+}
+
 /// Get the raw_logs directory path for the frontend
 #[tauri::command]
 async fn get_raw_logs_dir(state: State<'_, AppState>) -> Result<String, String> {
@@ -302,13 +782,51 @@ pub fn run() {
         })
         .invoke_handler(tauri::generate_handler![
             import_log,
+            import_folder,
+            reprocess_flight,
+            reprocess_all_flights,
             get_flights,
+            get_flight,
             get_flight_data,
             get_overview_stats,
+            query_flight_events,
             delete_flight,
             delete_all_flights,
             update_flight_name,
+            update_flight_notes,
+            set_flight_tags,
+            get_all_tags,
+            get_custom_fields,
+            add_custom_field,
+            delete_custom_field,
+            set_flight_custom_field,
+            get_batteries,
+            get_battery,
+            rename_battery,
+            update_battery_notes,
+            set_battery_retired,
+            get_aircraft,
+            rename_aircraft,
+            update_aircraft_notes,
+            add_maintenance_item,
+            complete_maintenance_item,
+            delete_maintenance_item,
+            get_pilots,
+            add_pilot,
+            update_pilot,
+            delete_pilot,
+            set_flight_pilot,
+            set_aircraft_pilot,
+            get_logbook,
+            export_logbook,
             get_raw_logs_dir,
+            get_flight_raw_log,
+            export_flight_raw_log,
+            export_flights,
+            export_parquet,
+            import_parquet,
+            create_backup,
+            restore_backup,
             has_api_key,
             set_api_key,
             get_app_data_dir,
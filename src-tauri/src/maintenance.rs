@@ -0,0 +1,95 @@
+//! Maintenance reminders for the aircraft registry.
+//!
+//! Each maintenance item repeats every so many flight hours and/or flights.
+//! The database counts the hours and flights flown since the item was last
+//! done; this module turns them into what is left and a due/overdue status.
+
+use crate::models::{MaintenanceItem, MaintenanceStatus};
+
+/// Share of an interval after which an item is reported as due
+const DUE_FRACTION: f64 = 0.9;
+
+/// Fill in the remaining hours/flights and the status of an item from its
+/// `hours_since` and `flights_since`
+///
+/// With both intervals set, whichever is closer to running out decides.
+pub fn apply_status(item: &mut MaintenanceItem) {
+    let hours = item.interval_hours.filter(|h| *h > 0.0);
+    let flights = item.interval_flights.filter(|f| *f > 0);
+
+    item.hours_remaining = hours.map(|interval| interval - item.hours_since);
+    item.flights_remaining = flights.map(|interval| interval - item.flights_since);
+
+    let used = [
+        hours.map(|interval| item.hours_since / interval),
+        flights.map(|interval| item.flights_since as f64 / interval as f64),
+    ];
+    item.status = used
+        .into_iter()
+        .flatten()
+        .map(status)
+        .max()
+        .unwrap_or_default();
+}
+
+fn status(used: f64) -> MaintenanceStatus {
+    if used >= 1.0 {
+        MaintenanceStatus::Overdue
+    } else if used >= DUE_FRACTION {
+        MaintenanceStatus::Due
+    } else {
+        MaintenanceStatus::Ok
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn item(
+        interval_hours: Option<f64>,
+        interval_flights: Option<i64>,
+        hours_since: f64,
+        flights_since: i64,
+    ) -> MaintenanceItem {
+        let mut item = MaintenanceItem {
+            interval_hours,
+            interval_flights,
+            hours_since,
+            flights_since,
+            ..Default::default()
+        };
+        apply_status(&mut item);
+        item
+    }
+
+    #[test]
+    fn test_status_follows_the_interval_closest_to_running_out() {
+        let props = item(Some(50.0), None, 20.0, 40);
+        assert_eq!(props.status, MaintenanceStatus::Ok);
+        assert_eq!(props.hours_remaining, Some(30.0));
+        assert_eq!(props.flights_remaining, None);
+
+        assert_eq!(
+            item(Some(50.0), None, 46.0, 0).status,
+            MaintenanceStatus::Due
+        );
+        assert_eq!(
+            item(Some(50.0), None, 50.0, 0).status,
+            MaintenanceStatus::Overdue
+        );
+
+        // Few hours but many flights: the flight interval decides
+        let imu = item(Some(50.0), Some(20), 5.0, 23);
+        assert_eq!(imu.status, MaintenanceStatus::Overdue);
+        assert_eq!(imu.flights_remaining, Some(-3));
+    }
+
+    #[test]
+    fn test_item_without_interval_is_never_due() {
+        let note = item(None, Some(0), 500.0, 1000);
+        assert_eq!(note.status, MaintenanceStatus::Ok);
+        assert_eq!(note.hours_remaining, None);
+        assert_eq!(note.flights_remaining, None);
+    }
+}
@@ -3,13 +3,14 @@
 //! These structs are shared between Rust backend and TypeScript frontend
 //! via Tauri's IPC system with serde serialization.
 
+use std::collections::BTreeMap;
+
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 
-/// Flight metadata stored in the flights table
-#[derive(Debug, Clone, Serialize, Deserialize)]
+/// Flight metadata stored in the flights table (the id is allocated on insert)
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct FlightMetadata {
-    pub id: i64,
     pub file_name: String,
     pub display_name: String,
     pub file_hash: Option<String>,
@@ -17,6 +18,7 @@ pub struct FlightMetadata {
     pub drone_serial: Option<String>,
     pub aircraft_name: Option<String>,
     pub battery_serial: Option<String>,
+    pub firmware_version: Option<String>,
     pub start_time: Option<DateTime<Utc>>,
     pub end_time: Option<DateTime<Utc>>,
     pub duration_secs: Option<f64>,
@@ -26,6 +28,10 @@ pub struct FlightMetadata {
     pub home_lat: Option<f64>,
     pub home_lon: Option<f64>,
     pub point_count: i32,
+    pub archive_path: Option<String>,
+    pub source_path: Option<String>,
+    pub photo_count: i32,
+    pub video_secs: f64,
 }
 
 /// Flight summary for list display
@@ -39,6 +45,7 @@ pub struct Flight {
     pub drone_serial: Option<String>,
     pub aircraft_name: Option<String>,
     pub battery_serial: Option<String>,
+    pub firmware_version: Option<String>,
     pub start_time: Option<String>,
     pub duration_secs: Option<f64>,
     pub total_distance: Option<f64>,
@@ -46,11 +53,257 @@ pub struct Flight {
     pub max_speed: Option<f64>,
     pub home_lat: Option<f64>,
     pub home_lon: Option<f64>,
+    /// Darkest light at takeoff or landing, unknown without time or home point
+    pub daylight: Option<Daylight>,
     pub point_count: Option<i32>,
+    /// Archived copy of the original log in raw_logs
+    pub archive_path: Option<String>,
+    /// Path the log was originally imported from
+    pub source_path: Option<String>,
+    /// Number of photos taken during the flight
+    pub photo_count: Option<i32>,
+    /// Total video recording time in seconds
+    pub video_secs: Option<f64>,
+    /// Number of anomaly findings, for the "has issues" indicator
+    pub issue_count: i64,
+    /// Free-form pilot notes
+    pub notes: Option<String>,
+    /// Pilot the flight is assigned to
+    pub pilot_id: Option<i64>,
+    pub pilot_name: Option<String>,
+    /// Tags, sorted case-insensitively
+    pub tags: Vec<String>,
+    /// Values of user-defined custom fields (client, job number, ...) by field name
+    pub custom_fields: BTreeMap<String, String>,
 }
 
-/// Raw telemetry point from parser (for bulk insert)
+/// Filters, ordering and page for listing flights
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FlightQuery {
+    /// Case-insensitive substring match on name, notes, tags and custom field values
+    pub search: Option<String>,
+    /// Only flights carrying all of these tags (case-insensitive)
+    #[serde(default)]
+    pub tags: Vec<String>,
+    /// Only flights with these custom field values (case-insensitive)
+    #[serde(default)]
+    pub custom_fields: BTreeMap<String, String>,
+    /// First day to include, `YYYY-MM-DD` (UTC)
+    pub date_from: Option<String>,
+    /// Last day to include, `YYYY-MM-DD` (UTC)
+    pub date_to: Option<String>,
+    pub drone_model: Option<String>,
+    pub drone_serial: Option<String>,
+    pub battery_serial: Option<String>,
+    pub pilot_id: Option<i64>,
+    pub daylight: Option<Daylight>,
+    pub min_duration_secs: Option<f64>,
+    pub min_distance_m: Option<f64>,
+    /// Only flights whose home point lies inside this box
+    pub bounds: Option<GeoBounds>,
+    #[serde(default)]
+    pub sort_by: FlightSortKey,
+    #[serde(default)]
+    pub sort_direction: SortDirection,
+    /// Number of matching flights to skip
+    pub offset: Option<usize>,
+    /// Page size (all matching flights if unset)
+    pub limit: Option<usize>,
+}
+
+/// Latitude/longitude box; `min_lon > max_lon` wraps across the antimeridian
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GeoBounds {
+    pub min_lat: f64,
+    pub min_lon: f64,
+    pub max_lat: f64,
+    pub max_lon: f64,
+}
+
+/// Column the flight list is ordered by
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum FlightSortKey {
+    #[default]
+    StartTime,
+    Name,
+    Duration,
+    Distance,
+    MaxAltitude,
+}
+
+/// Light conditions of a flight from the sun elevation at the home point,
+/// ordered from lightest to darkest
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum Daylight {
+    /// Sun above the horizon
+    Day,
+    /// Sun between the horizon and 6 degrees below it
+    CivilTwilight,
+    /// Sun more than 6 degrees below the horizon
+    Night,
+}
+
+impl Daylight {
+    /// Value stored in `flights.daylight`, the same as the serialized one
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Daylight::Day => "day",
+            Daylight::CivilTwilight => "civilTwilight",
+            Daylight::Night => "night",
+        }
+    }
+
+    pub fn from_db(value: &str) -> Option<Self> {
+        match value {
+            "day" => Some(Daylight::Day),
+            "civilTwilight" => Some(Daylight::CivilTwilight),
+            "night" => Some(Daylight::Night),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum SortDirection {
+    Asc,
+    #[default]
+    Desc,
+}
+
+/// One page of a flight query, with totals over every matching flight
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FlightPage {
+    pub flights: Vec<Flight>,
+    pub total_count: i64,
+    pub total_duration_secs: f64,
+    pub total_distance_m: f64,
+    pub offset: usize,
+}
+
+/// Camera event captured during a flight
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CameraEvent {
+    pub timestamp_ms: i64,
+    /// One of `photo`, `videoStart`, `videoStop`
+    pub event_type: String,
+    pub latitude: Option<f64>,
+    pub longitude: Option<f64>,
+    /// Height above takeoff in meters
+    pub altitude: Option<f64>,
+}
+
+impl CameraEvent {
+    pub const PHOTO: &'static str = "photo";
+    pub const VIDEO_START: &'static str = "videoStart";
+    pub const VIDEO_STOP: &'static str = "videoStop";
+}
+
+/// Home point recorded or updated, or return-to-home started/ended
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HomeEvent {
+    pub timestamp_ms: i64,
+    /// One of `homeSet`, `homeUpdated`, `rthStart`, `rthEnd`
+    pub event_type: String,
+    /// Home point for home events, aircraft position for RTH events
+    pub latitude: Option<f64>,
+    pub longitude: Option<f64>,
+    pub altitude: Option<f64>,
+    /// RTH trigger reported by the flight controller (e.g. low battery)
+    pub detail: Option<String>,
+}
+
+impl HomeEvent {
+    pub const HOME_SET: &'static str = "homeSet";
+    pub const HOME_UPDATED: &'static str = "homeUpdated";
+    pub const RTH_START: &'static str = "rthStart";
+    pub const RTH_END: &'static str = "rthEnd";
+
+    /// Whether this event carries a home point position
+    pub fn is_home_point(&self) -> bool {
+        self.event_type == Self::HOME_SET || self.event_type == Self::HOME_UPDATED
+    }
+}
+
+/// Tip or warning message shown to the pilot by the DJI app
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FlightEvent {
+    pub timestamp_ms: i64,
+    /// `info` for app tips, `warning` for app warnings
+    pub severity: String,
+    /// Coarse topic derived from the text (battery, gps, signal, ...)
+    pub category: String,
+    pub message: String,
+}
+
+impl FlightEvent {
+    pub const INFO: &'static str = "info";
+    pub const WARNING: &'static str = "warning";
+}
+
+/// Flight event with the flight it belongs to, for queries across flights
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FlightEventEntry {
+    pub flight_id: i64,
+    pub display_name: String,
+    pub start_time: Option<String>,
+    #[serde(flatten)]
+    pub event: FlightEvent,
+}
+
+/// Filters for querying flight events across all flights
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FlightEventQuery {
+    pub severity: Option<String>,
+    pub category: Option<String>,
+    /// Case-insensitive substring match on the message
+    pub search: Option<String>,
+    pub limit: Option<usize>,
+}
+
+/// Anomaly flagged by the telemetry analysis pass
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Finding {
+    pub timestamp_ms: i64,
+    /// How long the anomaly lasted (0 for single-sample events)
+    pub duration_ms: i64,
+    /// Rule that produced the finding, see `analysis` for the list
+    pub kind: String,
+    /// `warning` or `critical`
+    pub severity: String,
+    pub message: String,
+    /// Worst value observed, in the unit of the rule
+    pub value: Option<f64>,
+}
+
+impl Finding {
+    pub const WARNING: &'static str = "warning";
+    pub const CRITICAL: &'static str = "critical";
+}
+
+/// Event streams extracted from a log alongside its telemetry
 #[derive(Debug, Clone, Default)]
+pub struct FlightEvents {
+    pub camera: Vec<CameraEvent>,
+    pub home: Vec<HomeEvent>,
+    pub messages: Vec<FlightEvent>,
+    pub findings: Vec<Finding>,
+}
+
+/// Raw telemetry point from parser (for bulk insert)
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
 pub struct TelemetryPoint {
     pub timestamp_ms: i64,
 
@@ -83,6 +336,15 @@ pub struct TelemetryPoint {
     pub battery_voltage: Option<f64>,
     pub battery_current: Option<f64>,
     pub battery_temp: Option<f64>,
+    /// Individual cell voltages in pack order, empty when not reported
+    pub battery_cell_voltages: Vec<f64>,
+    /// Capacity at full charge and remaining, in mAh
+    pub battery_full_capacity: Option<i32>,
+    pub battery_remaining_capacity: Option<i32>,
+    /// Low voltage warning from the flight controller: 0 none, 1 low, 2 critically low
+    pub battery_voltage_warning: Option<i32>,
+    /// Cell voltages were estimated by the aircraft rather than measured
+    pub battery_cells_estimated: Option<bool>,
 
     // Status
     pub flight_mode: Option<String>,
@@ -93,6 +355,24 @@ pub struct TelemetryPoint {
     pub rc_downlink: Option<i32>,
 }
 
+impl TelemetryPoint {
+    /// Spread between the highest and lowest cell voltage, if at least two
+    /// cells are reported
+    pub fn cell_imbalance(&self) -> Option<f64> {
+        cell_imbalance(&self.battery_cell_voltages)
+    }
+}
+
+/// Spread between the highest and lowest of a pack's cell voltages
+pub fn cell_imbalance(cells: &[f64]) -> Option<f64> {
+    if cells.len() < 2 {
+        return None;
+    }
+    let max = cells.iter().copied().fold(f64::MIN, f64::max);
+    let min = cells.iter().copied().fold(f64::MAX, f64::min);
+    Some(max - min)
+}
+
 /// Telemetry record for frontend consumption (optimized for ECharts)
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
@@ -110,6 +390,12 @@ pub struct TelemetryRecord {
     pub battery_percent: Option<i32>,
     pub battery_voltage: Option<f64>,
     pub battery_temp: Option<f64>,
+    pub battery_cell_voltages: Vec<f64>,
+    /// Max minus min cell voltage
+    pub battery_cell_imbalance: Option<f64>,
+    pub battery_full_capacity: Option<i32>,
+    pub battery_remaining_capacity: Option<i32>,
+    pub battery_voltage_warning: Option<i32>,
     pub pitch: Option<f64>,
     pub roll: Option<f64>,
     pub yaw: Option<f64>,
@@ -127,6 +413,12 @@ pub struct FlightDataResponse {
     pub flight: Flight,
     pub telemetry: TelemetryData,
     pub track: Vec<[f64; 3]>, // [lng, lat, height] for map
+    pub camera_events: Vec<CameraEvent>,
+    pub home_events: Vec<HomeEvent>,
+    /// App tips and warnings in time order
+    pub events: Vec<FlightEvent>,
+    /// Anomalies found in the telemetry, in time order
+    pub findings: Vec<Finding>,
 }
 
 /// Overview statistics across all flights
@@ -145,6 +437,11 @@ pub struct OverviewStats {
     pub top_flights: Vec<TopFlight>,
     pub top_distance_flights: Vec<TopDistanceFlight>,
     pub battery_health_points: Vec<BatteryHealthPoint>,
+    /// Flights by light conditions; flights without time or home point
+    /// are in none of them
+    pub day_flights: i64,
+    pub civil_twilight_flights: i64,
+    pub night_flights: i64,
 }
 
 /// Battery usage summary
@@ -207,6 +504,165 @@ pub struct BatteryHealthPoint {
     pub rate_per_min: f64,
 }
 
+/// Battery pack in the registry, with lifecycle figures over all its flights
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BatteryPack {
+    pub serial: String,
+    /// User-given name, e.g. the number written on the pack
+    pub name: Option<String>,
+    pub notes: Option<String>,
+    pub retired_at: Option<String>,
+    pub flight_count: i64,
+    pub total_duration_secs: f64,
+    /// Cycle-equivalents: cumulative percent discharged / 100
+    pub cycles: f64,
+    pub first_flight: Option<String>,
+    pub last_flight: Option<String>,
+    /// Lowest estimated cell voltage seen in any flight
+    pub min_cell_voltage: Option<f64>,
+    /// Highest pack temperature seen in any flight
+    pub max_temp: Option<f64>,
+    /// Recent internal resistance estimate in milliohms
+    pub internal_resistance_mohm: Option<f64>,
+    /// Recent worst cell imbalance per flight in volts
+    pub cell_imbalance: Option<f64>,
+    /// Recent health score, 100 = as new
+    pub health_score: Option<f64>,
+    /// Health score change per 100 cycles, negative while the pack wears
+    pub health_trend: Option<f64>,
+}
+
+/// Battery figures of one flight, with the pack's running cycle count
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BatteryFlight {
+    pub flight_id: i64,
+    pub display_name: String,
+    pub start_time: Option<String>,
+    pub duration_secs: Option<f64>,
+    pub discharged_percent: f64,
+    /// Cycle-equivalents up to and including this flight
+    pub cycles: f64,
+    pub min_voltage: Option<f64>,
+    pub cell_count: Option<i32>,
+    pub min_cell_voltage: Option<f64>,
+    pub max_temp: Option<f64>,
+    pub internal_resistance_mohm: Option<f64>,
+    /// Worst spread between cell voltages during the flight, in volts
+    pub max_cell_imbalance: Option<f64>,
+    /// Resistance of the new pack relative to this flight's, in percent
+    pub health_score: Option<f64>,
+}
+
+/// A pack with its per-flight history, oldest flight first
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BatteryDetail {
+    pub pack: BatteryPack,
+    pub flights: Vec<BatteryFlight>,
+}
+
+/// Aircraft registry entry, keyed by drone serial
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Aircraft {
+    pub serial: String,
+    /// User-given name, overrides the name recorded in the logs
+    pub name: Option<String>,
+    pub notes: Option<String>,
+    /// Model and name recorded in the latest flight
+    pub drone_model: Option<String>,
+    pub aircraft_name: Option<String>,
+    pub flight_count: i64,
+    /// Accumulated airframe time in seconds
+    pub total_duration_secs: f64,
+    pub first_flight: Option<String>,
+    pub last_flight: Option<String>,
+    /// Firmware versions seen in flights, in the order they first appeared
+    pub firmware_versions: Vec<String>,
+    pub maintenance: Vec<MaintenanceItem>,
+}
+
+/// A recurring maintenance task of an aircraft (propeller replacement,
+/// IMU calibration, ...), due every so many flight hours and/or flights
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MaintenanceItem {
+    pub id: i64,
+    pub drone_serial: String,
+    pub name: String,
+    pub interval_hours: Option<f64>,
+    pub interval_flights: Option<i64>,
+    /// When the task was last done; None counts from the first flight
+    pub last_done_at: Option<String>,
+    pub notes: Option<String>,
+    /// Flight hours and flights since the task was last done
+    pub hours_since: f64,
+    pub flights_since: i64,
+    /// Left until the next service, negative once overdue
+    pub hours_remaining: Option<f64>,
+    pub flights_remaining: Option<i64>,
+    pub status: MaintenanceStatus,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum MaintenanceStatus {
+    #[default]
+    Ok,
+    /// Most of an interval is used up
+    Due,
+    /// An interval has been reached
+    Overdue,
+}
+
+/// A remote pilot with totals over the flights assigned to them
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Pilot {
+    pub id: i64,
+    pub name: String,
+    /// Remote pilot certificate or licence number
+    pub license_number: Option<String>,
+    pub notes: Option<String>,
+    /// Aircraft serials whose flights are assigned to this pilot on import
+    pub aircraft_serials: Vec<String>,
+    pub flight_count: i64,
+    pub total_duration_secs: f64,
+    /// Flights that took off or landed after civil dusk or before civil dawn
+    pub night_flights: i64,
+    /// Flights and flight time in the last 90 days
+    pub recent_flights: i64,
+    pub recent_duration_secs: f64,
+    pub recent_night_flights: i64,
+    pub last_flight: Option<String>,
+}
+
+/// One row of a pilot logbook
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LogbookEntry {
+    pub flight_id: i64,
+    pub display_name: String,
+    pub pilot_id: Option<i64>,
+    pub pilot_name: Option<String>,
+    pub drone_model: Option<String>,
+    pub drone_serial: Option<String>,
+    /// Registry name of the aircraft, or the name recorded in the log
+    pub aircraft_name: Option<String>,
+    pub takeoff_time: Option<DateTime<Utc>>,
+    /// Takeoff time plus flight duration
+    pub landing_time: Option<DateTime<Utc>>,
+    pub duration_secs: f64,
+    pub home_lat: Option<f64>,
+    pub home_lon: Option<f64>,
+    pub max_altitude: Option<f64>,
+    pub total_distance: Option<f64>,
+    pub daylight: Option<Daylight>,
+    pub notes: Option<String>,
+}
+
 /// Telemetry data formatted for ECharts
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
@@ -237,6 +693,12 @@ pub struct TelemetryData {
     pub battery_voltage: Vec<Option<f64>>,
     /// Battery temperature series
     pub battery_temp: Vec<Option<f64>>,
+    /// One voltage series per battery cell, empty when cells aren't reported
+    pub battery_cells: Vec<Vec<Option<f64>>>,
+    /// Cell imbalance series (max minus min cell voltage)
+    pub battery_cell_imbalance: Vec<Option<f64>>,
+    /// Remaining battery capacity series (mAh)
+    pub battery_remaining_capacity: Vec<Option<i32>>,
     /// Number of GPS satellites
     pub satellites: Vec<Option<i32>>,
     /// RC signal strength
@@ -257,6 +719,11 @@ impl TelemetryData {
     /// Create TelemetryData from a vector of TelemetryRecords
     pub fn from_records(records: &[TelemetryRecord]) -> Self {
         let base_time = records.first().map(|r| r.timestamp_ms).unwrap_or(0);
+        let cell_count = records
+            .iter()
+            .map(|r| r.battery_cell_voltages.len())
+            .max()
+            .unwrap_or(0);
 
         Self {
             time: records
@@ -275,6 +742,19 @@ impl TelemetryData {
             battery: records.iter().map(|r| r.battery_percent).collect(),
             battery_voltage: records.iter().map(|r| r.battery_voltage).collect(),
             battery_temp: records.iter().map(|r| r.battery_temp).collect(),
+            battery_cells: (0..cell_count)
+                .map(|cell| {
+                    records
+                        .iter()
+                        .map(|r| r.battery_cell_voltages.get(cell).copied())
+                        .collect()
+                })
+                .collect(),
+            battery_cell_imbalance: records.iter().map(|r| r.battery_cell_imbalance).collect(),
+            battery_remaining_capacity: records
+                .iter()
+                .map(|r| r.battery_remaining_capacity)
+                .collect(),
             satellites: records.iter().map(|r| r.satellites).collect(),
             rc_signal: records.iter().map(|r| r.rc_signal).collect(),
             rc_uplink: records.iter().map(|r| r.rc_uplink).collect(),
@@ -296,6 +776,95 @@ pub struct ImportResult {
     pub point_count: usize,
 }
 
+/// Outcome of importing one file during a batch import
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum FileImportStatus {
+    Imported,
+    Duplicate,
+    NoTelemetry,
+    DecryptFailed,
+    ParseError,
+    StorageError,
+}
+
+/// Per-file entry of a batch import report
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FileImportOutcome {
+    pub file_path: String,
+    pub status: FileImportStatus,
+    pub flight_id: Option<i64>,
+    pub point_count: usize,
+    pub message: String,
+}
+
+/// Batch (folder) import report with per-status totals
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FolderImportReport {
+    pub total_files: usize,
+    pub imported: usize,
+    pub duplicates: usize,
+    pub no_telemetry: usize,
+    pub decrypt_failed: usize,
+    pub parse_errors: usize,
+    pub storage_errors: usize,
+    pub total_points: usize,
+    pub files: Vec<FileImportOutcome>,
+}
+
+impl FolderImportReport {
+    /// Record a file outcome and update the totals
+    pub fn push(&mut self, outcome: FileImportOutcome) {
+        self.total_files += 1;
+        match outcome.status {
+            FileImportStatus::Imported => {
+                self.imported += 1;
+                self.total_points += outcome.point_count;
+            }
+            FileImportStatus::Duplicate => self.duplicates += 1,
+            FileImportStatus::NoTelemetry => self.no_telemetry += 1,
+            FileImportStatus::DecryptFailed => self.decrypt_failed += 1,
+            FileImportStatus::ParseError => self.parse_errors += 1,
+            FileImportStatus::StorageError => self.storage_errors += 1,
+        }
+        self.files.push(outcome);
+    }
+}
+
+/// Result of re-processing one flight from its archived raw log
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ReprocessOutcome {
+    pub flight_id: i64,
+    pub success: bool,
+    pub archive_path: Option<String>,
+    pub point_count: usize,
+    pub message: String,
+}
+
+/// Re-processing report for the whole library
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ReprocessReport {
+    pub total_flights: usize,
+    pub reprocessed: usize,
+    pub missing_archive: usize,
+    pub failed: usize,
+    pub flights: Vec<ReprocessOutcome>,
+}
+
+/// Result of importing a Parquet logbook export
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ParquetImportReport {
+    pub flights_imported: usize,
+    /// Flights whose file hash is already in the library
+    pub flights_skipped: usize,
+    pub telemetry_rows: usize,
+}
+
 /// Statistics for a flight
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
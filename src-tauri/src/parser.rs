@@ -1,11 +1,13 @@
-//! Parser module for DJI flight log files.
+//! Parser module for flight log files.
 //!
 //! Handles:
 //! - Parsing various DJI log formats using dji-log-parser
+//! - Dispatching other formats (CSV, GPX, ArduPilot, PX4) to their `formats` reader
 //! - Extracting telemetry data points
 //! - File hash calculation for duplicate detection
-//! - V13+ encrypted log handling with API key fetching
+//! - V13+ encrypted log handling with API key fetching and keychain caching
 
+use std::collections::HashMap;
 use std::fs::{self, File};
 use std::io::{BufReader, Read};
 use std::path::Path;
@@ -14,12 +16,20 @@ use chrono::{DateTime, Utc};
 use sha2::{Digest, Sha256};
 use thiserror::Error;
 
-use dji_log_parser::frame::Frame;
+use dji_log_parser::frame::{records_to_frames, Frame};
+use dji_log_parser::keychain::KeychainFeaturePoint;
+use dji_log_parser::record::firmware::{Firmware, SenderType};
+use dji_log_parser::record::osd::GoHomeStatus;
+use dji_log_parser::record::Record;
 use dji_log_parser::DJILog;
 
+use crate::analysis;
 use crate::api::DjiApi;
 use crate::database::Database;
-use crate::models::{FlightMetadata, FlightStats, TelemetryPoint};
+use crate::formats;
+use crate::models::{
+    CameraEvent, FlightEvent, FlightEvents, FlightMetadata, FlightStats, HomeEvent, TelemetryPoint,
+};
 
 #[derive(Error, Debug)]
 pub enum ParserError {
@@ -46,9 +56,25 @@ pub enum ParserError {
 pub struct ParseResult {
     pub metadata: FlightMetadata,
     pub points: Vec<TelemetryPoint>,
+    pub events: FlightEvents,
 }
 
-/// DJI Log Parser wrapper
+/// Log contents extracted by a format-specific reader, before statistics
+/// and metadata are derived
+pub struct ParsedLog {
+    pub points: Vec<TelemetryPoint>,
+    pub events: FlightEvents,
+    pub drone_model: Option<String>,
+    pub drone_serial: Option<String>,
+    pub aircraft_name: Option<String>,
+    pub battery_serial: Option<String>,
+    /// Flight controller / aircraft firmware version
+    pub firmware_version: Option<String>,
+    pub start_time: Option<DateTime<Utc>>,
+    pub end_time: Option<DateTime<Utc>>,
+}
+
+/// Flight log parser: reads any supported format and derives flight metadata
 pub struct LogParser<'a> {
     db: &'a Database,
     api: DjiApi,
@@ -112,7 +138,7 @@ impl<'a> LogParser<'a> {
         Ok(final_path.to_string_lossy().to_string())
     }
 
-    /// Parse a DJI log file and extract all telemetry data
+    /// Parse a flight log file and extract all telemetry data
     pub async fn parse_log(&self, file_path: &Path) -> Result<ParseResult, ParserError> {
         log::info!("Parsing log file: {:?}", file_path);
 
@@ -127,28 +153,108 @@ impl<'a> LogParser<'a> {
             return Err(ParserError::AlreadyImported);
         }
 
+        self.parse_log_with_hash(file_path, file_hash).await
+    }
+
+    /// Re-parse an archived log of an already imported flight.
+    ///
+    /// Unlike [`parse_log`](Self::parse_log) this skips the duplicate check,
+    /// since the flight row for this file is expected to exist.
+    pub async fn reparse_log(&self, file_path: &Path) -> Result<ParseResult, ParserError> {
+        log::info!("Re-parsing archived log file: {:?}", file_path);
+
+        let file_hash = Self::calculate_file_hash(file_path)?;
+        self.parse_log_with_hash(file_path, file_hash).await
+    }
+
+    async fn parse_log_with_hash(
+        &self,
+        file_path: &Path,
+        file_hash: String,
+    ) -> Result<ParseResult, ParserError> {
         // Read the file
         let file_data = fs::read(file_path)?;
 
+        let format = formats::detect(file_path, &file_data);
+        log::debug!("Reading {:?} as {}", file_path, format.name());
+        let log = format.read(self, file_data).await?;
+
+        self.build_parse_result(file_path, file_hash, log)
+    }
+
+    /// Read a DJI binary flight record
+    pub(crate) async fn parse_dji_log(&self, file_data: Vec<u8>) -> Result<ParsedLog, ParserError> {
         // Parse with dji-log-parser
-        let parser = DJILog::from_bytes(file_data).map_err(|e| ParserError::Parse(e.to_string()))?;
+        let parser =
+            DJILog::from_bytes(file_data).map_err(|e| ParserError::Parse(e.to_string()))?;
 
         // Check if we need an encryption key for V13+ logs
-        let frames = self.get_frames(&parser).await?;
+        let records = self.get_records(&parser).await?;
+        let firmware_version = self.extract_firmware_version(&records);
+        let frames = records_to_frames(records, parser.details.clone());
 
         if frames.is_empty() {
             return Err(ParserError::NoTelemetryData);
         }
 
-        // Extract telemetry points
-        let points = self.extract_telemetry(&frames);
+        // Extract telemetry points and events on a shared time base
+        let timestamps = frame_timestamps(&frames);
+        let points = self.extract_telemetry(&frames, &timestamps);
+        let events = FlightEvents {
+            camera: self.extract_camera_events(&frames, &timestamps),
+            home: self.extract_home_events(&frames, &timestamps),
+            messages: self.extract_app_messages(&frames, &timestamps),
+            findings: Vec::new(),
+        };
+
+        Ok(ParsedLog {
+            points,
+            events,
+            drone_model: self.extract_drone_model(&parser),
+            drone_serial: self.extract_serial(&parser),
+            aircraft_name: self.extract_aircraft_name(&parser),
+            battery_serial: self.extract_battery_serial(&parser),
+            firmware_version,
+            start_time: self.extract_start_time(&parser),
+            end_time: self.extract_end_time(&parser),
+        })
+    }
+
+    /// Derive statistics, findings and flight metadata from a read log
+    fn build_parse_result(
+        &self,
+        file_path: &Path,
+        file_hash: String,
+        log: ParsedLog,
+    ) -> Result<ParseResult, ParserError> {
+        let ParsedLog {
+            points,
+            mut events,
+            drone_model,
+            drone_serial,
+            aircraft_name,
+            battery_serial,
+            firmware_version,
+            start_time,
+            end_time,
+        } = log;
 
         if points.is_empty() {
             return Err(ParserError::NoTelemetryData);
         }
 
-        // Calculate statistics
+        // Calculate statistics and flag anomalies
         let stats = self.calculate_stats(&points);
+        events.findings = analysis::analyze(&points);
+        let (photo_count, video_secs) = camera_summary(&events.camera);
+
+        // Prefer the home point recorded by the aircraft over the first GPS fix
+        let home_location = events
+            .home
+            .iter()
+            .find(|e| e.is_home_point())
+            .and_then(|e| Some([e.longitude?, e.latitude?]))
+            .or(stats.home_location);
 
         // Build metadata
         let file_name = file_path
@@ -165,66 +271,134 @@ impl<'a> LogParser<'a> {
             .to_string();
 
         let metadata = FlightMetadata {
-            id: self.db.generate_flight_id(),
             file_name,
             display_name,
             file_hash: Some(file_hash),
-            drone_model: self.extract_drone_model(&parser),
-            drone_serial: self.extract_serial(&parser),
-            aircraft_name: self.extract_aircraft_name(&parser),
-            battery_serial: self.extract_battery_serial(&parser),
-            start_time: self.extract_start_time(&parser),
-            end_time: self.extract_end_time(&parser),
+            drone_model,
+            drone_serial,
+            aircraft_name,
+            battery_serial,
+            firmware_version,
+            start_time,
+            end_time,
             duration_secs: Some(stats.duration_secs),
             total_distance: Some(stats.total_distance_m),
             max_altitude: Some(stats.max_altitude_m),
             max_speed: Some(stats.max_speed_ms),
-            home_lat: stats.home_location.map(|h| h[1]),
-            home_lon: stats.home_location.map(|h| h[0]),
+            home_lat: home_location.map(|h| h[1]),
+            home_lon: home_location.map(|h| h[0]),
             point_count: points.len() as i32,
+            archive_path: None,
+            source_path: Some(file_path.to_string_lossy().to_string()),
+            photo_count,
+            video_secs,
         };
 
-        Ok(ParseResult { metadata, points })
+        Ok(ParseResult {
+            metadata,
+            points,
+            events,
+        })
     }
 
-    /// Get frames from the parser, handling encryption if needed
-    async fn get_frames(&self, parser: &DJILog) -> Result<Vec<Frame>, ParserError> {
+    /// Get records from the parser, handling encryption if needed
+    ///
+    /// V13+ keychains are cached in the database, keyed by a fingerprint of the
+    /// log's encoded keychain records, so re-parsing a known log works offline.
+    async fn get_records(&self, parser: &DJILog) -> Result<Vec<Record>, ParserError> {
         // Version 13+ requires keychains for decryption
         if parser.version >= 13 {
-            let api_key = self.api.get_api_key().ok_or(ParserError::EncryptionKeyRequired)?;
+            let cache_key = Self::keychain_cache_key(parser)?;
+
+            if let Some(keychains) = self.cached_keychains(&cache_key) {
+                match parser.records(Some(keychains)) {
+                    Ok(records) => {
+                        log::info!("Decrypted log using cached keychains");
+                        return Ok(records);
+                    }
+                    Err(e) => log::warn!("Cached keychains failed, fetching again: {}", e),
+                }
+            }
+
+            let api_key = self
+                .api
+                .get_api_key()
+                .ok_or(ParserError::EncryptionKeyRequired)?;
             let keychains = parser
                 .fetch_keychains(&api_key)
                 .map_err(|e| ParserError::Api(e.to_string()))?;
+
+            self.cache_keychains(&cache_key, &keychains);
+
             return parser
-                .frames(Some(keychains))
+                .records(Some(keychains))
                 .map_err(|e| ParserError::Parse(e.to_string()));
         }
 
         // Pre-13 logs are unencrypted
         parser
-            .frames(None)
+            .records(None)
             .map_err(|e| ParserError::Parse(e.to_string()))
     }
 
+    /// SHA256 of the keychain request embedded in the log.
+    ///
+    /// The request only contains the log's encoded key-storage records, so the
+    /// same log (or logs sharing key storage) always maps to the same entry.
+    fn keychain_cache_key(parser: &DJILog) -> Result<String, ParserError> {
+        let request = parser
+            .keychains_request()
+            .map_err(|e| ParserError::Parse(e.to_string()))?;
+        let encoded =
+            serde_json::to_vec(&request).map_err(|e| ParserError::Parse(e.to_string()))?;
+        Ok(format!("{:x}", Sha256::digest(&encoded)))
+    }
+
+    /// Look up previously fetched keychains; cache errors are never fatal
+    fn cached_keychains(&self, cache_key: &str) -> Option<Vec<Vec<KeychainFeaturePoint>>> {
+        let cached = match self.db.get_keychain(cache_key) {
+            Ok(cached) => cached?,
+            Err(e) => {
+                log::warn!("Failed to read keychain cache: {}", e);
+                return None;
+            }
+        };
+
+        match serde_json::from_str(&cached) {
+            Ok(keychains) => Some(keychains),
+            Err(e) => {
+                log::warn!("Ignoring unreadable cached keychains: {}", e);
+                None
+            }
+        }
+    }
+
+    fn cache_keychains(&self, cache_key: &str, keychains: &[Vec<KeychainFeaturePoint>]) {
+        let result = serde_json::to_string(keychains)
+            .map_err(|e| e.to_string())
+            .and_then(|json| {
+                self.db
+                    .store_keychain(cache_key, &json)
+                    .map_err(|e| e.to_string())
+            });
+
+        if let Err(e) = result {
+            log::warn!("Failed to cache keychains: {}", e);
+        }
+    }
+
     /// Extract telemetry points from parsed frames
-    fn extract_telemetry(&self, frames: &[Frame]) -> Vec<TelemetryPoint> {
+    fn extract_telemetry(&self, frames: &[Frame], timestamps: &[i64]) -> Vec<TelemetryPoint> {
         let mut points = Vec::with_capacity(frames.len());
-        let mut timestamp_ms: i64 = 0;
 
-        for frame in frames {
+        for (frame, &timestamp_ms) in frames.iter().zip(timestamps) {
             let osd = &frame.osd;
             let gimbal = &frame.gimbal;
             let battery = &frame.battery;
             let rc = &frame.rc;
 
-            let current_timestamp_ms = if osd.fly_time > 0.0 {
-                (osd.fly_time * 1000.0) as i64
-            } else {
-                timestamp_ms
-            };
-
             let mut point = TelemetryPoint {
-                timestamp_ms: current_timestamp_ms,
+                timestamp_ms,
                 ..Default::default()
             };
 
@@ -252,6 +426,17 @@ impl<'a> LogParser<'a> {
             point.battery_voltage = Some(battery.voltage as f64);
             point.battery_current = Some(battery.current as f64);
             point.battery_temp = Some(battery.temperature as f64);
+            point.battery_cell_voltages = battery
+                .cell_voltages
+                .iter()
+                .take(battery.cell_num as usize)
+                .map(|&v| v as f64)
+                .filter(|&v| v > 0.0)
+                .collect();
+            point.battery_full_capacity = Some(battery.full_capacity as i32);
+            point.battery_remaining_capacity = Some(battery.current_capacity as i32);
+            point.battery_voltage_warning = Some(osd.voltage_warning as i32);
+            point.battery_cells_estimated = Some(battery.is_cell_voltage_estimated);
 
             point.rc_uplink = rc.uplink_signal.map(i32::from);
             point.rc_downlink = rc.downlink_signal.map(i32::from);
@@ -261,17 +446,157 @@ impl<'a> LogParser<'a> {
             if point.latitude.is_some() && point.longitude.is_some() {
                 points.push(point);
             }
-
-            // Increment timestamp (frames are typically at 10Hz = 100ms intervals)
-            timestamp_ms = current_timestamp_ms + 100;
         }
 
         points
     }
 
+    /// Extract photo and video events from the camera state of each frame
+    ///
+    /// The camera flags stay set for as long as the state lasts, so events are
+    /// emitted on the rising edge. A recording still running at the end of the
+    /// log is closed at the last frame.
+    fn extract_camera_events(&self, frames: &[Frame], timestamps: &[i64]) -> Vec<CameraEvent> {
+        let mut events = Vec::new();
+        let mut was_photo = false;
+        let mut was_video = false;
+
+        for (frame, &timestamp_ms) in frames.iter().zip(timestamps) {
+            let camera = &frame.camera;
+
+            if camera.is_photo && !was_photo {
+                events.push(camera_event(frame, timestamp_ms, CameraEvent::PHOTO));
+            }
+            if camera.is_video && !was_video {
+                events.push(camera_event(frame, timestamp_ms, CameraEvent::VIDEO_START));
+            } else if !camera.is_video && was_video {
+                events.push(camera_event(frame, timestamp_ms, CameraEvent::VIDEO_STOP));
+            }
+
+            was_photo = camera.is_photo;
+            was_video = camera.is_video;
+        }
+
+        if was_video {
+            if let (Some(frame), Some(&timestamp_ms)) = (frames.last(), timestamps.last()) {
+                events.push(camera_event(frame, timestamp_ms, CameraEvent::VIDEO_STOP));
+            }
+        }
+
+        events
+    }
+
+    /// Extract home point records and return-to-home phases
+    ///
+    /// The home point is only valid once `is_home_record` is set, which may be
+    /// well after takeoff when launching before GPS lock. Later moves of more
+    /// than a few meters (dynamic home point, pilot refresh) are recorded as
+    /// updates.
+    fn extract_home_events(&self, frames: &[Frame], timestamps: &[i64]) -> Vec<HomeEvent> {
+        let mut events = Vec::new();
+        let mut current_home: Option<(f64, f64)> = None;
+        let mut in_rth = false;
+
+        for (frame, &timestamp_ms) in frames.iter().zip(timestamps) {
+            let home = &frame.home;
+            let osd = &frame.osd;
+
+            if home.is_home_record && (home.latitude != 0.0 || home.longitude != 0.0) {
+                let moved = match current_home {
+                    None => true,
+                    Some((lat, lon)) => {
+                        haversine_distance(lat, lon, home.latitude, home.longitude)
+                            > HOME_UPDATE_THRESHOLD_M
+                    }
+                };
+
+                if moved {
+                    let event_type = if current_home.is_none() {
+                        HomeEvent::HOME_SET
+                    } else {
+                        HomeEvent::HOME_UPDATED
+                    };
+                    events.push(HomeEvent {
+                        timestamp_ms,
+                        event_type: event_type.to_string(),
+                        latitude: Some(home.latitude),
+                        longitude: Some(home.longitude),
+                        altitude: Some(home.altitude as f64),
+                        detail: None,
+                    });
+                    current_home = Some((home.latitude, home.longitude));
+                }
+            }
+
+            // Any go-home phase other than standby means RTH is in progress
+            let rth_active = !matches!(osd.go_home_status, None | Some(GoHomeStatus::Standby));
+
+            if rth_active != in_rth {
+                let has_fix = osd.latitude != 0.0 || osd.longitude != 0.0;
+                events.push(HomeEvent {
+                    timestamp_ms,
+                    event_type: if rth_active {
+                        HomeEvent::RTH_START
+                    } else {
+                        HomeEvent::RTH_END
+                    }
+                    .to_string(),
+                    latitude: has_fix.then_some(osd.latitude),
+                    longitude: has_fix.then_some(osd.longitude),
+                    altitude: Some(osd.height as f64),
+                    detail: if rth_active {
+                        osd.flight_action.map(|action| format!("{:?}", action))
+                    } else {
+                        None
+                    },
+                });
+                in_rth = rth_active;
+            }
+        }
+
+        events
+    }
+
+    /// Extract app tips and warnings shown to the pilot
+    ///
+    /// A frame only carries the messages whose records arrived with it, so a
+    /// warning that stays up is repeated on later frames. Repeats of a message
+    /// within [`MESSAGE_REPEAT_WINDOW_MS`] of its last sighting are skipped.
+    fn extract_app_messages(&self, frames: &[Frame], timestamps: &[i64]) -> Vec<FlightEvent> {
+        let mut events = Vec::new();
+        let mut last_seen: HashMap<(&str, &str), i64> = HashMap::new();
+
+        for (frame, &timestamp_ms) in frames.iter().zip(timestamps) {
+            let app = &frame.app;
+
+            for (text, severity) in [
+                (app.tip.as_str(), FlightEvent::INFO),
+                (app.warn.as_str(), FlightEvent::WARNING),
+            ] {
+                for message in text.split(';').map(str::trim).filter(|m| !m.is_empty()) {
+                    let previous = last_seen.insert((severity, message), timestamp_ms);
+                    if previous.is_some_and(|ms| timestamp_ms - ms <= MESSAGE_REPEAT_WINDOW_MS) {
+                        continue;
+                    }
+                    events.push(FlightEvent {
+                        timestamp_ms,
+                        severity: severity.to_string(),
+                        category: message_category(message).to_string(),
+                        message: message.to_string(),
+                    });
+                }
+            }
+        }
+
+        events
+    }
+
     /// Calculate flight statistics from telemetry points
     fn calculate_stats(&self, points: &[TelemetryPoint]) -> FlightStats {
-        let duration_secs = points.last().map(|p| p.timestamp_ms as f64 / 1000.0).unwrap_or(0.0);
+        let duration_secs = points
+            .last()
+            .map(|p| p.timestamp_ms as f64 / 1000.0)
+            .unwrap_or(0.0);
 
         let max_altitude = points
             .iter()
@@ -301,13 +626,11 @@ impl<'a> LogParser<'a> {
         // Calculate total distance using haversine formula
         let total_distance = self.calculate_total_distance(points);
 
-        // Home location is the first valid GPS point
-        let home_location = points
-            .iter()
-            .find_map(|p| match (p.longitude, p.latitude) {
-                (Some(lon), Some(lat)) => Some([lon, lat]),
-                _ => None,
-            });
+        // Fallback home location for logs without a recorded home point
+        let home_location = points.iter().find_map(|p| match (p.longitude, p.latitude) {
+            (Some(lon), Some(lat)) => Some([lon, lat]),
+            _ => None,
+        });
 
         FlightStats {
             duration_secs,
@@ -317,7 +640,11 @@ impl<'a> LogParser<'a> {
             } else {
                 0.0
             },
-            max_speed_ms: if max_speed.is_finite() { max_speed } else { 0.0 },
+            max_speed_ms: if max_speed.is_finite() {
+                max_speed
+            } else {
+                0.0
+            },
             avg_speed_ms: avg_speed,
             min_battery,
             home_location,
@@ -383,6 +710,21 @@ impl<'a> LogParser<'a> {
         }
     }
 
+    /// Extract the aircraft firmware version from the log's firmware records
+    ///
+    /// Camera, gimbal, RC and battery report their own firmware; only the
+    /// flight controller's record is the aircraft's.
+    fn extract_firmware_version(&self, records: &[Record]) -> Option<String> {
+        records.iter().find_map(|record| match record {
+            Record::Firmware(Firmware {
+                sender_type: SenderType::MC,
+                version,
+                ..
+            }) if !version.trim().is_empty() => Some(version.trim().to_string()),
+            _ => None,
+        })
+    }
+
     /// Extract flight start time
     fn extract_start_time(&self, parser: &DJILog) -> Option<DateTime<Utc>> {
         Some(parser.details.start_time)
@@ -396,8 +738,115 @@ impl<'a> LogParser<'a> {
     }
 }
 
+/// Minimum home point move (meters) recorded as a home point update
+const HOME_UPDATE_THRESHOLD_M: f64 = 5.0;
+
+/// Gap (ms) after which a repeated app message is recorded again
+const MESSAGE_REPEAT_WINDOW_MS: i64 = 10_000;
+
+/// Time offset of each frame in milliseconds
+///
+/// Uses the OSD flight time when present, otherwise advances 100ms from the
+/// previous frame (frames are typically at 10Hz).
+fn frame_timestamps(frames: &[Frame]) -> Vec<i64> {
+    let mut timestamps = Vec::with_capacity(frames.len());
+    let mut next_ms: i64 = 0;
+
+    for frame in frames {
+        let timestamp_ms = if frame.osd.fly_time > 0.0 {
+            (frame.osd.fly_time * 1000.0) as i64
+        } else {
+            next_ms
+        };
+        timestamps.push(timestamp_ms);
+        next_ms = timestamp_ms + 100;
+    }
+
+    timestamps
+}
+
+/// Coarse category of an app message, from keywords in its text
+pub(crate) fn message_category(message: &str) -> &'static str {
+    const CATEGORIES: &[(&str, &[&str])] = &[
+        ("battery", &["battery", "voltage", "power"]),
+        ("gps", &["gps", "satellite", "positioning"]),
+        (
+            "signal",
+            &[
+                "signal",
+                "rc ",
+                "remote controller",
+                "transmission",
+                "disconnected",
+            ],
+        ),
+        ("weather", &["wind", "temperature", "rain"]),
+        ("obstacle", &["obstacle", "vision", "collision"]),
+        ("compass", &["compass", "imu", "magnetic"]),
+        (
+            "rth",
+            &["return to home", "returning home", "home point", "rth"],
+        ),
+        (
+            "airspace",
+            &[
+                "altitude limit",
+                "distance limit",
+                "restricted",
+                "no-fly",
+                "geo zone",
+            ],
+        ),
+        ("storage", &["sd card", "storage"]),
+    ];
+
+    let lower = message.to_lowercase();
+    CATEGORIES
+        .iter()
+        .find(|(_, keywords)| keywords.iter().any(|k| lower.contains(k)))
+        .map(|(category, _)| *category)
+        .unwrap_or("general")
+}
+
+/// Camera event at the aircraft's position in the given frame
+fn camera_event(frame: &Frame, timestamp_ms: i64, event_type: &str) -> CameraEvent {
+    let osd = &frame.osd;
+    // 0,0 means no GPS fix yet
+    let has_fix = osd.latitude != 0.0 || osd.longitude != 0.0;
+
+    CameraEvent {
+        timestamp_ms,
+        event_type: event_type.to_string(),
+        latitude: has_fix.then_some(osd.latitude),
+        longitude: has_fix.then_some(osd.longitude),
+        altitude: Some(osd.height as f64),
+    }
+}
+
+/// Photo count and total recording time in seconds from camera events
+fn camera_summary(events: &[CameraEvent]) -> (i32, f64) {
+    let mut photo_count = 0;
+    let mut video_ms: i64 = 0;
+    let mut recording_since = None;
+
+    for event in events {
+        match event.event_type.as_str() {
+            CameraEvent::PHOTO => photo_count += 1,
+            CameraEvent::VIDEO_START => recording_since = Some(event.timestamp_ms),
+            CameraEvent::VIDEO_STOP => {
+                if let Some(start) = recording_since.take() {
+                    video_ms += (event.timestamp_ms - start).max(0);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    (photo_count, video_ms as f64 / 1000.0)
+}
+
 /// Haversine distance calculation in meters
-fn haversine_distance(lat1: f64, lon1: f64, lat2: f64, lon2: f64) -> f64 {
+pub(crate) fn haversine_distance(lat1: f64, lon1: f64, lat2: f64, lon2: f64) -> f64 {
     const R: f64 = 6_371_000.0; // Earth's radius in meters
 
     let lat1_rad = lat1.to_radians();
@@ -412,3 +861,62 @@ fn haversine_distance(lat1: f64, lon1: f64, lat2: f64, lon2: f64) -> f64 {
 
     R * c
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    fn warning_frame(warn: &str) -> Frame {
+        let mut frame = Frame::default();
+        frame.app.warn = warn.to_string();
+        frame
+    }
+
+    #[test]
+    fn test_repeated_warning_recorded_once_per_window() {
+        let dir = tempdir().unwrap();
+        let db = Database::new(dir.path().to_path_buf()).unwrap();
+        let parser = LogParser::new(&db);
+
+        // Records clear the message on frames in between, as in real logs
+        let frames: Vec<Frame> = ["Low battery", "", "Low battery", "", "Low battery", ""]
+            .into_iter()
+            .map(warning_frame)
+            .collect();
+        let timestamps = [0, 100, 2_000, 2_100, 30_000, 30_100];
+
+        let events = parser.extract_app_messages(&frames, &timestamps);
+        let times: Vec<i64> = events.iter().map(|e| e.timestamp_ms).collect();
+        assert_eq!(times, [0, 30_000]);
+        assert!(events.iter().all(|e| e.severity == FlightEvent::WARNING));
+    }
+
+    #[test]
+    fn test_firmware_version_taken_from_flight_controller() {
+        let dir = tempdir().unwrap();
+        let db = Database::new(dir.path().to_path_buf()).unwrap();
+        let parser = LogParser::new(&db);
+
+        let firmware = |sender_type, version: &str| {
+            Record::Firmware(Firmware {
+                sender_type,
+                sub_sender_type: 0,
+                version: version.to_string(),
+            })
+        };
+        let records = vec![
+            firmware(SenderType::Battery, "1.2.3"),
+            firmware(SenderType::RC, "4.5.6"),
+            firmware(SenderType::MC, ""),
+            firmware(SenderType::Camera, "7.8.9"),
+            firmware(SenderType::MC, "10.1.2"),
+        ];
+
+        assert_eq!(
+            parser.extract_firmware_version(&records).as_deref(),
+            Some("10.1.2")
+        );
+        assert_eq!(parser.extract_firmware_version(&records[..2]), None);
+    }
+}
@@ -0,0 +1,123 @@
+//! Sun position from time and place, computed locally.
+//!
+//! Uses the NOAA solar position equations, which are accurate to well under
+//! a degree for the years flight logs come from. Atmospheric refraction is
+//! only accounted for in the sunrise/sunset threshold.
+
+use chrono::{DateTime, Utc};
+
+use crate::models::Daylight;
+
+/// Sun elevation at sunrise and sunset: the upper limb touching the horizon,
+/// lifted by refraction
+pub const SUNSET_ELEVATION_DEG: f64 = -0.833;
+
+/// Sun elevation at the end of evening civil twilight; below it is night
+/// for EASA and FAA purposes
+pub const NIGHT_ELEVATION_DEG: f64 = -6.0;
+
+/// Sun elevation above the horizon in degrees at a place and time
+pub fn sun_elevation(lat: f64, lon: f64, time: DateTime<Utc>) -> f64 {
+    let unix_secs = time.timestamp() as f64 + f64::from(time.timestamp_subsec_millis()) / 1000.0;
+    let julian_day = unix_secs / 86_400.0 + 2_440_587.5;
+    let t = (julian_day - 2_451_545.0) / 36_525.0;
+
+    // Mean longitude and anomaly of the sun, orbit eccentricity
+    let mean_long = (280.46646 + t * (36_000.769_83 + t * 0.0003032)).rem_euclid(360.0);
+    let mean_anomaly = (357.52911 + t * (35_999.050_29 - 0.0001537 * t)).to_radians();
+    let eccentricity = 0.016708634 - t * (0.000042037 + 0.0000001267 * t);
+
+    let center = mean_anomaly.sin() * (1.914602 - t * (0.004817 + 0.000014 * t))
+        + (2.0 * mean_anomaly).sin() * (0.019993 - 0.000101 * t)
+        + (3.0 * mean_anomaly).sin() * 0.000289;
+    let omega = (125.04 - 1934.136 * t).to_radians();
+    let apparent_long = (mean_long + center - 0.00569 - 0.00478 * omega.sin()).to_radians();
+
+    let mean_obliquity =
+        23.0 + (26.0 + (21.448 - t * (46.815 + t * (0.00059 - t * 0.001813))) / 60.0) / 60.0;
+    let obliquity = (mean_obliquity + 0.00256 * omega.cos()).to_radians();
+    let declination = (obliquity.sin() * apparent_long.sin()).asin();
+
+    // Equation of time in minutes
+    let y = (obliquity / 2.0).tan().powi(2);
+    let l = mean_long.to_radians();
+    let eq_time = 4.0
+        * (y * (2.0 * l).sin() - 2.0 * eccentricity * mean_anomaly.sin()
+            + 4.0 * eccentricity * y * mean_anomaly.sin() * (2.0 * l).cos()
+            - 0.5 * y * y * (4.0 * l).sin()
+            - 1.25 * eccentricity * eccentricity * (2.0 * mean_anomaly).sin())
+        .to_degrees();
+
+    let utc_minutes = unix_secs.rem_euclid(86_400.0) / 60.0;
+    let solar_minutes = (utc_minutes + eq_time + 4.0 * lon).rem_euclid(1440.0);
+    let hour_angle = (solar_minutes / 4.0 - 180.0).to_radians();
+
+    let lat = lat.to_radians();
+    let cos_zenith =
+        lat.sin() * declination.sin() + lat.cos() * declination.cos() * hour_angle.cos();
+    90.0 - cos_zenith.clamp(-1.0, 1.0).acos().to_degrees()
+}
+
+/// Light conditions at a place and time
+pub fn daylight_at(lat: f64, lon: f64, time: DateTime<Utc>) -> Daylight {
+    let elevation = sun_elevation(lat, lon, time);
+    if elevation >= SUNSET_ELEVATION_DEG {
+        Daylight::Day
+    } else if elevation >= NIGHT_ELEVATION_DEG {
+        Daylight::CivilTwilight
+    } else {
+        Daylight::Night
+    }
+}
+
+/// Light conditions of a flight: the darker of takeoff and landing, so a
+/// flight that lands after dusk counts as a night flight
+pub fn classify_flight(
+    lat: f64,
+    lon: f64,
+    takeoff: DateTime<Utc>,
+    landing: DateTime<Utc>,
+) -> Daylight {
+    daylight_at(lat, lon, takeoff).max(daylight_at(lat, lon, landing))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    #[test]
+    fn test_sun_elevation_matches_known_positions() {
+        // Zurich at solar noon on the June solstice: 90 - 47.37 + 23.44
+        let noon = Utc.with_ymd_and_hms(2024, 6, 21, 11, 27, 0).unwrap();
+        assert!((sun_elevation(47.37, 8.54, noon) - 66.07).abs() < 0.3);
+
+        // Equator at the March equinox: overhead at noon, underfoot at midnight
+        let noon = Utc.with_ymd_and_hms(2024, 3, 20, 12, 7, 0).unwrap();
+        assert!(sun_elevation(0.0, 0.0, noon) > 88.0);
+        let midnight = Utc.with_ymd_and_hms(2024, 3, 20, 0, 7, 0).unwrap();
+        assert!(sun_elevation(0.0, 0.0, midnight) < -88.0);
+    }
+
+    #[test]
+    fn test_flight_classified_by_its_darker_end() {
+        // Sunset in Zurich on 2024-12-01 is around 15:40 UTC, civil dusk ~16:15
+        let afternoon = Utc.with_ymd_and_hms(2024, 12, 1, 14, 0, 0).unwrap();
+        let dusk = Utc.with_ymd_and_hms(2024, 12, 1, 15, 50, 0).unwrap();
+        let night = Utc.with_ymd_and_hms(2024, 12, 1, 16, 40, 0).unwrap();
+
+        assert_eq!(
+            classify_flight(47.37, 8.54, afternoon, afternoon),
+            Daylight::Day
+        );
+        assert_eq!(
+            classify_flight(47.37, 8.54, afternoon, dusk),
+            Daylight::CivilTwilight
+        );
+        assert_eq!(classify_flight(47.37, 8.54, dusk, night), Daylight::Night);
+        assert_eq!(
+            classify_flight(47.37, 8.54, night, afternoon),
+            Daylight::Night
+        );
+    }
+}
@@ -0,0 +1,20 @@
+//! Fixtures shared by the unit tests of several modules
+
+use crate::models::FlightMetadata;
+
+/// Metadata of a short flight without position or aircraft details
+///
+/// Tests override the fields they care about with struct update syntax.
+pub(crate) fn sample_flight(number: i64, file_hash: &str) -> FlightMetadata {
+    FlightMetadata {
+        file_name: format!("{}.txt", number),
+        display_name: format!("Flight {}", number),
+        file_hash: Some(file_hash.to_string()),
+        duration_secs: Some(1.0),
+        total_distance: Some(0.0),
+        max_altitude: Some(0.0),
+        max_speed: Some(0.0),
+        point_count: 2,
+        ..Default::default()
+    }
+}
@@ -1,6 +1,6 @@
 /**
  * Telemetry charts component using ECharts
- * Displays height, VPS height, speed, battery, attitude, RC, GPS, distance to home, and velocity data
+ * Displays height, VPS height, speed, battery, battery cells, attitude, RC, GPS, distance to home, and velocity data
  * Optimized for performance with large datasets
  */
 
@@ -106,6 +106,10 @@ export function TelemetryCharts({ data, unitSystem, startTime }: TelemetryCharts
     () => createBatteryChart(data, splitLineColor, tooltipFormatter, tooltipColors),
     [data, splitLineColor, tooltipColors, tooltipFormatter]
   );
+  const batteryCellsOption = useMemo(
+    () => createBatteryCellsChart(data, splitLineColor, tooltipFormatter, tooltipColors),
+    [data, splitLineColor, tooltipColors, tooltipFormatter]
+  );
   const attitudeOption = useMemo(
     () => createAttitudeChart(data, splitLineColor, tooltipFormatter, tooltipColors),
     [data, splitLineColor, tooltipColors, tooltipFormatter]
@@ -159,6 +163,19 @@ export function TelemetryCharts({ data, unitSystem, startTime }: TelemetryCharts
         />
       </div>
 
+      {/* Battery Cells Chart (only logs with per-cell voltages) */}
+      {data.batteryCells.length > 0 && (
+        <div className="h-56">
+          <ReactECharts
+            option={batteryCellsOption}
+            style={{ height: '100%', width: '100%' }}
+            opts={{ renderer: 'canvas' }}
+            notMerge={true}
+            onChartReady={registerChart}
+          />
+        </div>
+      )}
+
       {/* Attitude Chart */}
       <div className="h-60">
         <ReactECharts
@@ -603,6 +620,129 @@ function createBatteryChart(
   };
 }
 
+const CELL_COLORS = ['#38bdf8', '#34d399', '#fbbf24', '#f472b6', '#a78bfa', '#fb923c'];
+
+/** Imbalance (mV) from which a pack deserves a closer look */
+const CELL_IMBALANCE_WARNING_MV = 100;
+
+function createBatteryCellsChart(
+  data: TelemetryData,
+  splitLineColor: string,
+  tooltipFormatter: TooltipFormatter,
+  tooltipColors: TooltipColors
+): EChartsOption {
+  const cellNames = data.batteryCells.map((_, index) => `Cell ${index + 1}`);
+  const imbalanceMv = data.batteryCellImbalance.map((value) =>
+    value === null ? null : Math.round(value * 1000)
+  );
+  const cellRange = computeRange(data.batteryCells.flat());
+  const imbalanceRange = computeRange(
+    [...imbalanceMv, CELL_IMBALANCE_WARNING_MV],
+    { clampMin: 0 }
+  );
+  return {
+    ...baseChartConfig,
+    tooltip: {
+      ...baseChartConfig.tooltip,
+      backgroundColor: tooltipColors.background,
+      borderColor: tooltipColors.border,
+      textStyle: { color: tooltipColors.text },
+      formatter: tooltipFormatter,
+    },
+    legend: {
+      ...baseChartConfig.legend,
+      data: [...cellNames, 'Imbalance'],
+    },
+    xAxis: {
+      ...createTimeAxis(data.time),
+    },
+    yAxis: [
+      {
+        type: 'value',
+        name: 'Cell (V)',
+        min: cellRange.min,
+        max: cellRange.max,
+        axisLine: {
+          lineStyle: {
+            color: '#38bdf8',
+          },
+        },
+        axisLabel: {
+          color: '#9ca3af',
+        },
+        splitLine: {
+          lineStyle: {
+            color: splitLineColor,
+          },
+        },
+      },
+      {
+        type: 'value',
+        name: 'Imbalance (mV)',
+        position: 'right',
+        min: imbalanceRange.min,
+        max: imbalanceRange.max,
+        axisLine: {
+          lineStyle: {
+            color: '#ef4444',
+          },
+        },
+        axisLabel: {
+          color: '#9ca3af',
+        },
+        splitLine: {
+          show: false,
+        },
+      },
+    ],
+    series: [
+      ...data.batteryCells.map(
+        (cell, index): LineSeriesOption => ({
+          name: cellNames[index],
+          type: 'line',
+          data: cell,
+          smooth: true,
+          symbol: 'none',
+          itemStyle: {
+            color: CELL_COLORS[index % CELL_COLORS.length],
+          },
+          lineStyle: {
+            color: CELL_COLORS[index % CELL_COLORS.length],
+            width: 1.5,
+          },
+        })
+      ),
+      {
+        name: 'Imbalance',
+        type: 'line',
+        data: imbalanceMv,
+        yAxisIndex: 1,
+        smooth: true,
+        symbol: 'none',
+        itemStyle: {
+          color: '#ef4444',
+        },
+        lineStyle: {
+          color: '#ef4444',
+          width: 1.5,
+          type: 'dashed',
+        },
+        markLine: {
+          silent: true,
+          symbol: 'none',
+          lineStyle: {
+            color: 'rgba(239, 68, 68, 0.5)',
+          },
+          label: {
+            show: false,
+          },
+          data: [{ yAxis: CELL_IMBALANCE_WARNING_MV }],
+        },
+      },
+    ],
+  };
+}
+
 function createAttitudeChart(
   data: TelemetryData,
   splitLineColor: string,
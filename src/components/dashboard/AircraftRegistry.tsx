@@ -0,0 +1,294 @@
+/**
+ * Aircraft registry: airframe hours, firmware and maintenance reminders
+ */
+
+import { useCallback, useEffect, useState } from 'react';
+import { invoke } from '@tauri-apps/api/core';
+import type { Aircraft, MaintenanceItem, MaintenanceStatus } from '@/types';
+import { formatDateTime } from '@/lib/utils';
+
+interface AircraftRegistryProps {
+  /** Changes whenever flights are imported or removed */
+  refreshKey: unknown;
+}
+
+const STATUS_STYLES: Record<MaintenanceStatus, string> = {
+  ok: 'text-emerald-400',
+  due: 'text-amber-400',
+  overdue: 'text-red-400',
+};
+
+function formatHours(seconds: number): string {
+  return `${(seconds / 3600).toFixed(1)} h`;
+}
+
+function formatInterval(item: MaintenanceItem): string {
+  const parts: string[] = [];
+  if (item.intervalHours !== null) parts.push(`${item.intervalHours} h`);
+  if (item.intervalFlights !== null) parts.push(`${item.intervalFlights} flights`);
+  return `every ${parts.join(' / ')}`;
+}
+
+function formatRemaining(item: MaintenanceItem): string {
+  const parts: string[] = [];
+  if (item.hoursRemaining !== null) parts.push(`${item.hoursRemaining.toFixed(1)} h`);
+  if (item.flightsRemaining !== null) parts.push(`${item.flightsRemaining} flights`);
+  return parts.join(' / ');
+}
+
+/** Worst status of an aircraft's items, with how many items have it */
+function maintenanceSummary(aircraft: Aircraft): { status: MaintenanceStatus; count: number } {
+  for (const status of ['overdue', 'due'] as const) {
+    const count = aircraft.maintenance.filter((item) => item.status === status).length;
+    if (count > 0) return { status, count };
+  }
+  return { status: 'ok', count: aircraft.maintenance.length };
+}
+
+export function AircraftRegistry({ refreshKey }: AircraftRegistryProps) {
+  const [aircraft, setAircraft] = useState<Aircraft[]>([]);
+  const [nameDrafts, setNameDrafts] = useState<Record<string, string>>({});
+  const [expanded, setExpanded] = useState<string | null>(null);
+  const [newItem, setNewItem] = useState({ name: '', hours: '', flights: '' });
+  const [doneAt, setDoneAt] = useState('');
+  const [error, setError] = useState<string | null>(null);
+
+  const loadAircraft = useCallback(async () => {
+    try {
+      const loaded = await invoke<Aircraft[]>('get_aircraft');
+      setAircraft(loaded);
+      setNameDrafts(Object.fromEntries(loaded.map((a) => [a.serial, a.name ?? ''])));
+      setError(null);
+    } catch (err) {
+      setError(`Failed to load aircraft: ${err}`);
+    }
+  }, []);
+
+  useEffect(() => {
+    loadAircraft();
+  }, [loadAircraft, refreshKey]);
+
+  const saveName = async (entry: Aircraft) => {
+    const name = (nameDrafts[entry.serial] ?? '').trim();
+    if (name === (entry.name ?? '')) return;
+    try {
+      await invoke('rename_aircraft', { serial: entry.serial, name });
+      await loadAircraft();
+    } catch (err) {
+      setError(`Failed to rename aircraft: ${err}`);
+    }
+  };
+
+  const addItem = async (serial: string) => {
+    const hours = parseFloat(newItem.hours);
+    const flights = parseInt(newItem.flights, 10);
+    try {
+      await invoke('add_maintenance_item', {
+        serial,
+        name: newItem.name,
+        intervalHours: Number.isFinite(hours) ? hours : null,
+        intervalFlights: Number.isFinite(flights) ? flights : null,
+      });
+      setNewItem({ name: '', hours: '', flights: '' });
+      await loadAircraft();
+    } catch (err) {
+      setError(`${err}`);
+    }
+  };
+
+  const completeItem = async (item: MaintenanceItem) => {
+    try {
+      await invoke('complete_maintenance_item', {
+        itemId: item.id,
+        doneAt: doneAt || null,
+      });
+      await loadAircraft();
+    } catch (err) {
+      setError(`${err}`);
+    }
+  };
+
+  const deleteItem = async (item: MaintenanceItem) => {
+    try {
+      await invoke('delete_maintenance_item', { itemId: item.id });
+      await loadAircraft();
+    } catch (err) {
+      setError(`${err}`);
+    }
+  };
+
+  const selected = aircraft.find((a) => a.serial === expanded) ?? null;
+
+  return (
+    <div className="card p-4">
+      <h3 className="text-sm font-semibold text-white mb-3">Aircraft & Maintenance</h3>
+
+      {error && <p className="text-xs text-red-400 mb-2">{error}</p>}
+
+      {aircraft.length === 0 ? (
+        <p className="text-sm text-gray-400">No aircraft data available.</p>
+      ) : (
+        <div className="overflow-x-auto">
+          <table className="w-full text-xs">
+            <thead>
+              <tr className="text-left text-gray-500 border-b border-gray-700/50">
+                <th className="py-1.5 pr-3 font-medium">Serial</th>
+                <th className="py-1.5 pr-3 font-medium">Name</th>
+                <th className="py-1.5 pr-3 font-medium">Model</th>
+                <th className="py-1.5 pr-3 font-medium text-right">Flights</th>
+                <th className="py-1.5 pr-3 font-medium text-right">Airframe</th>
+                <th className="py-1.5 pr-3 font-medium">Firmware</th>
+                <th className="py-1.5 pr-3 font-medium">Last flight</th>
+                <th className="py-1.5 font-medium">Maintenance</th>
+              </tr>
+            </thead>
+            <tbody>
+              {aircraft.map((entry) => {
+                const summary = maintenanceSummary(entry);
+                return (
+                  <tr key={entry.serial} className="border-b border-gray-700/30">
+                    <td className="py-1.5 pr-3">
+                      <button
+                        type="button"
+                        onClick={() =>
+                          setExpanded(expanded === entry.serial ? null : entry.serial)
+                        }
+                        className="text-gray-300 hover:text-white font-mono"
+                        title="Show maintenance items"
+                      >
+                        {entry.serial}
+                      </button>
+                    </td>
+                    <td className="py-1.5 pr-3">
+                      <input
+                        type="text"
+                        value={nameDrafts[entry.serial] ?? ''}
+                        onChange={(e) =>
+                          setNameDrafts((drafts) => ({
+                            ...drafts,
+                            [entry.serial]: e.target.value,
+                          }))
+                        }
+                        onBlur={() => saveName(entry)}
+                        onKeyDown={(e) => {
+                          if (e.key === 'Enter') {
+                            e.currentTarget.blur();
+                          }
+                        }}
+                        placeholder={entry.aircraftName ?? 'Name'}
+                        className="input text-xs h-7 w-28"
+                      />
+                    </td>
+                    <td className="py-1.5 pr-3 text-gray-300">{entry.droneModel ?? '—'}</td>
+                    <td className="py-1.5 pr-3 text-right text-gray-300">{entry.flightCount}</td>
+                    <td className="py-1.5 pr-3 text-right text-gray-300">
+                      {formatHours(entry.totalDurationSecs)}
+                    </td>
+                    <td
+                      className="py-1.5 pr-3 text-gray-400"
+                      title={entry.firmwareVersions.join(', ')}
+                    >
+                      {entry.firmwareVersions[entry.firmwareVersions.length - 1] ?? '—'}
+                    </td>
+                    <td className="py-1.5 pr-3 text-gray-400">
+                      {formatDateTime(entry.lastFlight)}
+                    </td>
+                    <td className={`py-1.5 ${STATUS_STYLES[summary.status]}`}>
+                      {entry.maintenance.length === 0 ? '—' : `${summary.count} ${summary.status}`}
+                    </td>
+                  </tr>
+                );
+              })}
+            </tbody>
+          </table>
+        </div>
+      )}
+
+      {selected && (
+        <div className="mt-4">
+          <div className="flex items-center justify-between mb-2">
+            <h4 className="text-xs font-semibold text-gray-300">
+              {selected.name ?? selected.aircraftName ?? selected.serial} · maintenance
+            </h4>
+            <label className="flex items-center gap-2 text-xs text-gray-400">
+              Done on
+              <input
+                type="date"
+                value={doneAt}
+                onChange={(e) => setDoneAt(e.target.value)}
+                className="input text-xs h-7"
+                title="Leave empty to record it as done now"
+              />
+            </label>
+          </div>
+
+          <div className="space-y-1">
+            {selected.maintenance.map((item) => (
+              <div
+                key={item.id}
+                className="flex items-center gap-3 p-1.5 rounded hover:bg-gray-700/30 text-xs"
+              >
+                <span className="flex-1 min-w-0 truncate text-white">{item.name}</span>
+                <span className="text-gray-400">{formatInterval(item)}</span>
+                <span className="text-gray-400">
+                  last {item.lastDoneAt ? formatDateTime(item.lastDoneAt) : 'never'}
+                </span>
+                <span className={`w-32 text-right ${STATUS_STYLES[item.status]}`}>
+                  {formatRemaining(item)} left
+                </span>
+                <button
+                  type="button"
+                  onClick={() => completeItem(item)}
+                  className="text-gray-400 hover:text-white"
+                >
+                  Done
+                </button>
+                <button
+                  type="button"
+                  onClick={() => deleteItem(item)}
+                  className="text-gray-500 hover:text-red-400"
+                >
+                  Remove
+                </button>
+              </div>
+            ))}
+          </div>
+
+          <div className="flex items-center gap-2 mt-3">
+            <input
+              type="text"
+              value={newItem.name}
+              onChange={(e) => setNewItem({ ...newItem, name: e.target.value })}
+              placeholder="Item, e.g. Propellers"
+              className="input text-xs h-7 flex-1"
+            />
+            <input
+              type="number"
+              min="0"
+              value={newItem.hours}
+              onChange={(e) => setNewItem({ ...newItem, hours: e.target.value })}
+              placeholder="Every h"
+              className="input text-xs h-7 w-20"
+            />
+            <input
+              type="number"
+              min="1"
+              value={newItem.flights}
+              onChange={(e) => setNewItem({ ...newItem, flights: e.target.value })}
+              placeholder="Every flights"
+              className="input text-xs h-7 w-24"
+            />
+            <button
+              type="button"
+              onClick={() => addItem(selected.serial)}
+              disabled={!newItem.name.trim()}
+              className="btn-secondary text-xs h-7 px-3"
+            >
+              Add
+            </button>
+          </div>
+        </div>
+      )}
+    </div>
+  );
+}
@@ -0,0 +1,258 @@
+/**
+ * Battery pack registry: names, cycle counts, health and retirement
+ */
+
+import { useCallback, useEffect, useState } from 'react';
+import { invoke } from '@tauri-apps/api/core';
+import type { BatteryDetail, BatteryPack } from '@/types';
+import { formatDateTime, formatDuration } from '@/lib/utils';
+
+interface BatteryRegistryProps {
+  /** Changes whenever flights are imported or removed */
+  refreshKey: unknown;
+  onSelectFlight?: (flightId: number) => void;
+}
+
+function formatReading(value: number | null, digits: number, unit: string): string {
+  return value === null ? '—' : `${value.toFixed(digits)}${unit}`;
+}
+
+function healthColor(score: number | null): string {
+  if (score === null) return 'text-gray-500';
+  return score > 80 ? 'text-emerald-400' : score > 60 ? 'text-amber-400' : 'text-red-400';
+}
+
+export function BatteryRegistry({ refreshKey, onSelectFlight }: BatteryRegistryProps) {
+  const [packs, setPacks] = useState<BatteryPack[]>([]);
+  const [nameDrafts, setNameDrafts] = useState<Record<string, string>>({});
+  const [expanded, setExpanded] = useState<BatteryDetail | null>(null);
+  const [showRetired, setShowRetired] = useState(false);
+  const [error, setError] = useState<string | null>(null);
+
+  const loadPacks = useCallback(async () => {
+    try {
+      const loaded = await invoke<BatteryPack[]>('get_batteries');
+      setPacks(loaded);
+      setNameDrafts(
+        Object.fromEntries(loaded.map((pack) => [pack.serial, pack.name ?? '']))
+      );
+      setError(null);
+    } catch (err) {
+      setError(`Failed to load batteries: ${err}`);
+    }
+  }, []);
+
+  useEffect(() => {
+    loadPacks();
+  }, [loadPacks, refreshKey]);
+
+  const saveName = async (pack: BatteryPack) => {
+    const name = (nameDrafts[pack.serial] ?? '').trim();
+    if (name === (pack.name ?? '')) return;
+    try {
+      await invoke('rename_battery', { serial: pack.serial, name });
+      await loadPacks();
+    } catch (err) {
+      setError(`Failed to rename battery: ${err}`);
+    }
+  };
+
+  const toggleRetired = async (pack: BatteryPack) => {
+    try {
+      await invoke('set_battery_retired', {
+        serial: pack.serial,
+        retired: pack.retiredAt === null,
+      });
+      await loadPacks();
+    } catch (err) {
+      setError(`Failed to update battery: ${err}`);
+    }
+  };
+
+  const toggleHistory = async (serial: string) => {
+    if (expanded?.pack.serial === serial) {
+      setExpanded(null);
+      return;
+    }
+    try {
+      setExpanded(await invoke<BatteryDetail>('get_battery', { serial }));
+    } catch (err) {
+      setError(`Failed to load battery history: ${err}`);
+    }
+  };
+
+  const retiredCount = packs.filter((pack) => pack.retiredAt !== null).length;
+  const visiblePacks = showRetired
+    ? packs
+    : packs.filter((pack) => pack.retiredAt === null);
+
+  return (
+    <div className="card p-4">
+      <div className="flex items-center justify-between mb-3">
+        <h3 className="text-sm font-semibold text-white">Battery Registry</h3>
+        {retiredCount > 0 && (
+          <label className="flex items-center gap-2 text-xs text-gray-400">
+            <input
+              type="checkbox"
+              checked={showRetired}
+              onChange={(e) => setShowRetired(e.target.checked)}
+            />
+            Show retired ({retiredCount})
+          </label>
+        )}
+      </div>
+
+      {error && <p className="text-xs text-red-400 mb-2">{error}</p>}
+
+      {visiblePacks.length === 0 ? (
+        <p className="text-sm text-gray-400">No battery data available.</p>
+      ) : (
+        <div className="overflow-x-auto">
+          <table className="w-full text-xs">
+            <thead>
+              <tr className="text-left text-gray-500 border-b border-gray-700/50">
+                <th className="py-1.5 pr-3 font-medium">Serial</th>
+                <th className="py-1.5 pr-3 font-medium">Name</th>
+                <th className="py-1.5 pr-3 font-medium text-right">Flights</th>
+                <th className="py-1.5 pr-3 font-medium text-right">Cycles</th>
+                <th className="py-1.5 pr-3 font-medium text-right">Health</th>
+                <th className="py-1.5 pr-3 font-medium text-right">Trend / 100 cyc</th>
+                <th className="py-1.5 pr-3 font-medium text-right">Resistance</th>
+                <th className="py-1.5 pr-3 font-medium text-right">Min cell</th>
+                <th className="py-1.5 pr-3 font-medium text-right">Cell Δ</th>
+                <th className="py-1.5 pr-3 font-medium text-right">Max temp</th>
+                <th className="py-1.5 pr-3 font-medium">Last flight</th>
+                <th className="py-1.5 font-medium" />
+              </tr>
+            </thead>
+            <tbody>
+              {visiblePacks.map((pack) => (
+                <tr
+                  key={pack.serial}
+                  className={`border-b border-gray-700/30 ${
+                    pack.retiredAt !== null ? 'opacity-50' : ''
+                  }`}
+                >
+                  <td className="py-1.5 pr-3">
+                    <button
+                      type="button"
+                      onClick={() => toggleHistory(pack.serial)}
+                      className="text-gray-300 hover:text-white font-mono"
+                      title="Show flight history"
+                    >
+                      {pack.serial}
+                    </button>
+                  </td>
+                  <td className="py-1.5 pr-3">
+                    <input
+                      type="text"
+                      value={nameDrafts[pack.serial] ?? ''}
+                      onChange={(e) =>
+                        setNameDrafts((drafts) => ({
+                          ...drafts,
+                          [pack.serial]: e.target.value,
+                        }))
+                      }
+                      onBlur={() => saveName(pack)}
+                      onKeyDown={(e) => {
+                        if (e.key === 'Enter') {
+                          e.currentTarget.blur();
+                        }
+                      }}
+                      placeholder="Name"
+                      className="input text-xs h-7 w-28"
+                    />
+                  </td>
+                  <td className="py-1.5 pr-3 text-right text-gray-300">{pack.flightCount}</td>
+                  <td className="py-1.5 pr-3 text-right text-gray-300">
+                    {pack.cycles.toFixed(1)}
+                  </td>
+                  <td className={`py-1.5 pr-3 text-right font-medium ${healthColor(pack.healthScore)}`}>
+                    {formatReading(pack.healthScore, 0, '%')}
+                  </td>
+                  <td className="py-1.5 pr-3 text-right text-gray-400">
+                    {pack.healthTrend === null
+                      ? '—'
+                      : `${pack.healthTrend > 0 ? '+' : ''}${pack.healthTrend.toFixed(1)}%`}
+                  </td>
+                  <td className="py-1.5 pr-3 text-right text-gray-300">
+                    {formatReading(pack.internalResistanceMohm, 1, ' mΩ')}
+                  </td>
+                  <td className="py-1.5 pr-3 text-right text-gray-300">
+                    {formatReading(pack.minCellVoltage, 2, ' V')}
+                  </td>
+                  <td className="py-1.5 pr-3 text-right text-gray-300">
+                    {formatReading(
+                      pack.cellImbalance === null ? null : pack.cellImbalance * 1000,
+                      0,
+                      ' mV'
+                    )}
+                  </td>
+                  <td className="py-1.5 pr-3 text-right text-gray-300">
+                    {formatReading(pack.maxTemp, 1, ' °C')}
+                  </td>
+                  <td className="py-1.5 pr-3 text-gray-400">
+                    {formatDateTime(pack.lastFlight)}
+                  </td>
+                  <td className="py-1.5 text-right">
+                    <button
+                      type="button"
+                      onClick={() => toggleRetired(pack)}
+                      className="text-gray-400 hover:text-white"
+                    >
+                      {pack.retiredAt !== null ? 'Reactivate' : 'Retire'}
+                    </button>
+                  </td>
+                </tr>
+              ))}
+            </tbody>
+          </table>
+        </div>
+      )}
+
+      {expanded && (
+        <div className="mt-4">
+          <h4 className="text-xs font-semibold text-gray-300 mb-2">
+            {expanded.pack.name ?? expanded.pack.serial} · flight history
+          </h4>
+          <div className="max-h-[240px] overflow-y-auto space-y-1">
+            {expanded.flights.map((flight) => (
+              <div
+                key={flight.flightId}
+                onClick={() => onSelectFlight?.(flight.flightId)}
+                className="flex items-center gap-3 p-1.5 rounded hover:bg-gray-700/30 cursor-pointer text-xs"
+              >
+                <span className="flex-1 min-w-0 truncate text-white">
+                  {flight.displayName}
+                </span>
+                <span className="text-gray-400">{formatDateTime(flight.startTime)}</span>
+                <span className="text-gray-400 w-14 text-right">
+                  {formatDuration(flight.durationSecs)}
+                </span>
+                <span className="text-gray-400 w-12 text-right">
+                  {flight.dischargedPercent.toFixed(0)}%
+                </span>
+                <span className="text-gray-400 w-16 text-right">
+                  {flight.cycles.toFixed(1)} cyc
+                </span>
+                <span className="text-gray-400 w-16 text-right">
+                  {formatReading(flight.internalResistanceMohm, 1, ' mΩ')}
+                </span>
+                <span className="text-gray-400 w-14 text-right">
+                  {formatReading(
+                    flight.maxCellImbalance === null ? null : flight.maxCellImbalance * 1000,
+                    0,
+                    ' mV'
+                  )}
+                </span>
+                <span className={`w-10 text-right ${healthColor(flight.healthScore)}`}>
+                  {formatReading(flight.healthScore, 0, '%')}
+                </span>
+              </div>
+            ))}
+          </div>
+        </div>
+      )}
+    </div>
+  );
+}
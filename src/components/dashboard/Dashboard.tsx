@@ -267,7 +267,12 @@ export function Dashboard() {
                   <h2 className="font-semibold text-white">Flight Path</h2>
                 </div>
                 <div className="flex-1">
-                  <FlightMap track={currentFlightData!.track} themeMode={themeMode} />
+                  <FlightMap
+                    track={currentFlightData!.track}
+                    themeMode={themeMode}
+                    cameraEvents={currentFlightData!.cameraEvents}
+                    homeEvents={currentFlightData!.homeEvents}
+                  />
                 </div>
               </div>
             </div>
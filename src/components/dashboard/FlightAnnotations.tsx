@@ -0,0 +1,169 @@
+/**
+ * Pilot, notes, tags and custom field values for the selected flight
+ */
+
+import { useEffect, useState } from 'react';
+import type { Flight } from '@/types';
+import { useFlightStore } from '@/stores/flightStore';
+
+interface FlightAnnotationsProps {
+  flight: Flight;
+}
+
+export function FlightAnnotations({ flight }: FlightAnnotationsProps) {
+  const {
+    customFields,
+    loadCustomFields,
+    pilots,
+    loadPilots,
+    setFlightPilot,
+    updateFlightNotes,
+    setFlightTags,
+    setFlightCustomField,
+  } = useFlightStore();
+  const [notes, setNotes] = useState(flight.notes ?? '');
+  const [tagDraft, setTagDraft] = useState('');
+  const [fieldDrafts, setFieldDrafts] = useState<Record<string, string>>(
+    flight.customFields
+  );
+
+  useEffect(() => {
+    loadCustomFields();
+    loadPilots();
+  }, [loadCustomFields, loadPilots]);
+
+  // Reset drafts when another flight is selected
+  useEffect(() => {
+    setNotes(flight.notes ?? '');
+    setTagDraft('');
+    setFieldDrafts(flight.customFields);
+  }, [flight.id]);
+
+  const saveNotes = () => {
+    if (notes.trim() !== (flight.notes ?? '')) {
+      updateFlightNotes(flight.id, notes);
+    }
+  };
+
+  const addTag = () => {
+    const tag = tagDraft.trim();
+    if (!tag) return;
+    setTagDraft('');
+    setFlightTags(flight.id, [...flight.tags, tag]);
+  };
+
+  const removeTag = (tag: string) => {
+    setFlightTags(
+      flight.id,
+      flight.tags.filter((existing) => existing !== tag)
+    );
+  };
+
+  const saveField = (field: string) => {
+    const value = fieldDrafts[field] ?? '';
+    if (value.trim() !== (flight.customFields[field] ?? '')) {
+      setFlightCustomField(flight.id, field, value);
+    }
+  };
+
+  return (
+    <div className="mt-3 grid grid-cols-1 md:grid-cols-2 gap-3">
+      <div>
+        <label className="block text-xs text-gray-500 mb-1">Notes</label>
+        <textarea
+          value={notes}
+          onChange={(e) => setNotes(e.target.value)}
+          onBlur={saveNotes}
+          rows={3}
+          placeholder="Add notes about this flight"
+          className="input w-full text-xs resize-y"
+        />
+      </div>
+
+      <div className="space-y-2">
+        <div>
+          <label className="block text-xs text-gray-500 mb-1">Pilot</label>
+          <select
+            value={flight.pilotId ?? ''}
+            onChange={(e) =>
+              setFlightPilot(flight.id, e.target.value ? Number(e.target.value) : null)
+            }
+            className="input text-xs h-7 w-48"
+          >
+            <option value="">Unassigned</option>
+            {pilots.map((pilot) => (
+              <option key={pilot.id} value={pilot.id}>
+                {pilot.name}
+              </option>
+            ))}
+          </select>
+        </div>
+
+        <div>
+          <label className="block text-xs text-gray-500 mb-1">Tags</label>
+          <div className="flex flex-wrap items-center gap-1.5">
+            {flight.tags.map((tag) => (
+              <span
+                key={tag}
+                className="flex items-center gap-1 px-2 py-0.5 rounded-full text-xs border border-dji-primary/40 text-dji-primary bg-dji-primary/10"
+              >
+                {tag}
+                <button
+                  type="button"
+                  onClick={() => removeTag(tag)}
+                  className="text-dji-primary/70 hover:text-white"
+                  title="Remove tag"
+                >
+                  ×
+                </button>
+              </span>
+            ))}
+            <input
+              type="text"
+              value={tagDraft}
+              onChange={(e) => setTagDraft(e.target.value)}
+              onKeyDown={(e) => {
+                if (e.key === 'Enter') {
+                  e.preventDefault();
+                  addTag();
+                }
+              }}
+              onBlur={addTag}
+              placeholder="Add tag"
+              className="input text-xs h-7 w-28"
+            />
+          </div>
+        </div>
+
+        {customFields.length > 0 && (
+          <div className="grid grid-cols-2 gap-2">
+            {customFields.map((field) => (
+              <div key={field}>
+                <label className="block text-xs text-gray-500 mb-1">
+                  {field}
+                </label>
+                <input
+                  type="text"
+                  value={fieldDrafts[field] ?? ''}
+                  onChange={(e) =>
+                    setFieldDrafts((drafts) => ({
+                      ...drafts,
+                      [field]: e.target.value,
+                    }))
+                  }
+                  onBlur={() => saveField(field)}
+                  onKeyDown={(e) => {
+                    if (e.key === 'Enter') {
+                      e.currentTarget.blur();
+                    }
+                  }}
+                  className="input w-full text-xs h-7"
+                />
+              </div>
+            ))}
+          </div>
+        )}
+      </div>
+    </div>
+  );
+}
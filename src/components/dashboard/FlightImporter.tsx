@@ -9,7 +9,7 @@ import { open } from '@tauri-apps/plugin-dialog';
 import { useFlightStore } from '@/stores/flightStore';
 
 export function FlightImporter() {
-  const { importLog, isImporting } = useFlightStore();
+  const { importLog, importFolder, isImporting } = useFlightStore();
   const [isBatchProcessing, setIsBatchProcessing] = useState(false);
   const [batchMessage, setBatchMessage] = useState<string | null>(null);
   const [cooldownRemaining, setCooldownRemaining] = useState(0);
@@ -31,8 +31,8 @@ export function FlightImporter() {
       multiple: true,
       filters: [
         {
-          name: 'DJI Log Files',
-          extensions: ['txt', 'dat', 'log', 'csv'],
+          name: 'Flight Logs',
+          extensions: ['txt', 'dat', 'log', 'csv', 'gpx', 'bin', 'ulg'],
         },
       ],
     });
@@ -84,6 +84,26 @@ export function FlightImporter() {
     }
   };
 
+  // Handle folder selection (e.g. a copied FlightRecord folder)
+  const handleBrowseFolder = async () => {
+    const selected = await open({ directory: true, multiple: false });
+    if (typeof selected !== 'string') return;
+
+    setBatchMessage(null);
+    setCurrentFileName(getShortFileName(selected));
+    const report = await importFolder(selected);
+    setCurrentFileName(null);
+    if (!report) return;
+
+    const failed =
+      report.noTelemetry + report.decryptFailed + report.parseErrors + report.storageErrors;
+    setBatchMessage(
+      `Folder import finished. ${report.imported} of ${report.totalFiles} ` +
+        `file${report.totalFiles === 1 ? '' : 's'} imported, ` +
+        `${report.duplicates} already imported, ${failed} failed.`
+    );
+  };
+
   // Handle drag and drop
   const onDrop = useCallback(
     async (acceptedFiles: File[]) => {
@@ -105,6 +125,8 @@ export function FlightImporter() {
     accept: {
       'text/plain': ['.txt', '.dat', '.log'],
       'text/csv': ['.csv'],
+      'application/gpx+xml': ['.gpx'],
+      'application/octet-stream': ['.bin', '.ulg'],
     },
     multiple: true,
     noClick: true, // Disable click to use our custom button
@@ -159,6 +181,13 @@ export function FlightImporter() {
           >
             Browse Files
           </button>
+          <button
+            onClick={handleBrowseFolder}
+            className="btn-secondary text-sm py-1.5 px-3 ml-2"
+            disabled={isImporting || isBatchProcessing}
+          >
+            Import Folder
+          </button>
           {batchMessage && (
             <p className="mt-2 text-xs text-gray-400">{batchMessage}</p>
           )}
@@ -16,7 +16,7 @@ import { writeTextFile } from '@tauri-apps/plugin-fs';
 import { useFlightStore } from '@/stores/flightStore';
 import { formatDuration, formatDateTime, formatDistance } from '@/lib/utils';
 import { DayPicker, type DateRange } from 'react-day-picker';
-import type { FlightDataResponse, Flight, TelemetryData } from '@/types';
+import type { Daylight, FlightDataResponse, Flight, TelemetryData } from '@/types';
 import 'react-day-picker/dist/style.css';
 
 export function FlightList({ showControls = true }: { showControls?: boolean } = {}) {
@@ -42,6 +42,9 @@ export function FlightList({ showControls = true }: { showControls?: boolean } =
   } | null>(null);
   const [selectedDrone, setSelectedDrone] = useState('');
   const [selectedBattery, setSelectedBattery] = useState('');
+  const [selectedTag, setSelectedTag] = useState('');
+  const [selectedPilot, setSelectedPilot] = useState('');
+  const [selectedDaylight, setSelectedDaylight] = useState<Daylight | ''>('');
   const [searchQuery, setSearchQuery] = useState('');
   const [sortOption, setSortOption] = useState<
     'name' | 'date' | 'duration' | 'distance'
@@ -167,6 +170,33 @@ export function FlightList({ showControls = true }: { showControls?: boolean } =
     return Array.from(unique);
   }, [flights]);
 
+  const tagOptions = useMemo(() => {
+    const unique = new Map<string, string>();
+    flights.forEach((flight) => {
+      flight.tags.forEach((tag) => {
+        const key = tag.toLowerCase();
+        if (!unique.has(key)) {
+          unique.set(key, tag);
+        }
+      });
+    });
+    return Array.from(unique.values()).sort((a, b) =>
+      a.toLowerCase().localeCompare(b.toLowerCase())
+    );
+  }, [flights]);
+
+  const pilotOptions = useMemo(() => {
+    const unique = new Map<number, string>();
+    flights.forEach((flight) => {
+      if (flight.pilotId !== null && flight.pilotName) {
+        unique.set(flight.pilotId, flight.pilotName);
+      }
+    });
+    return Array.from(unique.entries())
+      .map(([id, name]) => ({ id, name }))
+      .sort((a, b) => a.name.toLowerCase().localeCompare(b.name.toLowerCase()));
+  }, [flights]);
+
   const filteredFlights = useMemo(() => {
     if (!showControls) return flights;
     const start = dateRange?.from ?? null;
@@ -190,9 +220,22 @@ export function FlightList({ showControls = true }: { showControls?: boolean } =
         if (flight.batterySerial !== selectedBattery) return false;
       }
 
+      if (selectedTag) {
+        const tag = selectedTag.toLowerCase();
+        if (!flight.tags.some((t) => t.toLowerCase() === tag)) return false;
+      }
+
+      if (selectedPilot) {
+        if (String(flight.pilotId) !== selectedPilot) return false;
+      }
+
+      if (selectedDaylight) {
+        if (flight.daylight !== selectedDaylight) return false;
+      }
+
       return true;
     });
-  }, [dateRange, flights, selectedBattery, selectedDrone]);
+  }, [dateRange, flights, selectedBattery, selectedDaylight, selectedDrone, selectedPilot, selectedTag]);
 
   const normalizedSearch = useMemo(
     () => (showControls ? searchQuery.trim().toLowerCase() : ''),
@@ -206,8 +249,14 @@ export function FlightList({ showControls = true }: { showControls?: boolean } =
   const searchedFlights = useMemo(() => {
     if (!normalizedSearch) return filteredFlights;
     return filteredFlights.filter((flight) => {
-      const title = getFlightTitle(flight).toLowerCase();
-      return title.includes(normalizedSearch);
+      // Match the title, notes, tags and custom field values
+      const haystack = [
+        getFlightTitle(flight),
+        flight.notes ?? '',
+        ...flight.tags,
+        ...Object.values(flight.customFields),
+      ];
+      return haystack.some((text) => text.toLowerCase().includes(normalizedSearch));
     });
   }, [filteredFlights, getFlightTitle, normalizedSearch]);
 
@@ -730,6 +779,9 @@ ${points}
               setDateRange(undefined);
               setSelectedDrone('');
               setSelectedBattery('');
+              setSelectedTag('');
+              setSelectedPilot('');
+              setSelectedDaylight('');
             }}
             className="text-xs text-gray-400 hover:text-white"
           >
@@ -769,6 +821,56 @@ ${points}
           </select>
         </div>
 
+        {tagOptions.length > 0 && (
+          <div>
+            <label className="block text-xs text-gray-400 mb-1">Tag</label>
+            <select
+              value={selectedTag}
+              onChange={(e) => setSelectedTag(e.target.value)}
+              className="input w-full text-xs h-8 px-3 py-0 leading-[1.2]"
+            >
+              <option value="">All tags</option>
+              {tagOptions.map((tag) => (
+                <option key={tag} value={tag}>
+                  {tag}
+                </option>
+              ))}
+            </select>
+          </div>
+        )}
+
+        {pilotOptions.length > 0 && (
+          <div>
+            <label className="block text-xs text-gray-400 mb-1">Pilot</label>
+            <select
+              value={selectedPilot}
+              onChange={(e) => setSelectedPilot(e.target.value)}
+              className="input w-full text-xs h-8 px-3 py-0 leading-[1.2]"
+            >
+              <option value="">All pilots</option>
+              {pilotOptions.map((pilot) => (
+                <option key={pilot.id} value={pilot.id}>
+                  {pilot.name}
+                </option>
+              ))}
+            </select>
+          </div>
+        )}
+
+        <div>
+          <label className="block text-xs text-gray-400 mb-1">Light</label>
+          <select
+            value={selectedDaylight}
+            onChange={(e) => setSelectedDaylight(e.target.value as Daylight | '')}
+            className="input w-full text-xs h-8 px-3 py-0 leading-[1.2]"
+          >
+            <option value="">Any light</option>
+            <option value="day">Day</option>
+            <option value="civilTwilight">Civil twilight</option>
+            <option value="night">Night</option>
+          </select>
+        </div>
+
         {/* Filtered count and Clear filters on same line */}
         <div className="flex items-center justify-between">
           <span className="text-xs text-gray-400">
@@ -779,6 +881,9 @@ ${points}
               setDateRange(undefined);
               setSelectedDrone('');
               setSelectedBattery('');
+              setSelectedTag('');
+              setSelectedPilot('');
+              setSelectedDaylight('');
             }}
             className="text-xs text-gray-400 hover:text-white"
           >
@@ -999,6 +1104,12 @@ ${points}
                   <p className="font-medium text-white truncate">
                     {flight.displayName || flight.fileName}
                   </p>
+                  {flight.issueCount > 0 && (
+                    <span
+                      className="flex-shrink-0 w-2 h-2 rounded-full bg-amber-400"
+                      title={`${flight.issueCount} issue${flight.issueCount === 1 ? '' : 's'} detected`}
+                    />
+                  )}
                   <button
                     onClick={(e) => {
                       e.stopPropagation();
@@ -1038,6 +1149,20 @@ ${points}
                 </span>
               </div>
 
+              {/* Tags */}
+              {flight.tags.length > 0 && (
+                <div className="flex flex-wrap gap-1 mt-1.5">
+                  {flight.tags.map((tag) => (
+                    <span
+                      key={tag}
+                      className="px-1.5 py-0.5 rounded-full text-[10px] border border-dji-primary/40 text-dji-primary bg-dji-primary/10"
+                    >
+                      {tag}
+                    </span>
+                  ))}
+                </div>
+              )}
+
               {confirmDeleteId === flight.id && editingId !== flight.id && (
                 <div className="flex items-center gap-2 mt-2 text-xs">
                   <span className="text-gray-400">Are you sure?</span>
@@ -5,7 +5,7 @@
 
 import type { FlightDataResponse } from '@/types';
 import { save } from '@tauri-apps/plugin-dialog';
-import { writeTextFile } from '@tauri-apps/plugin-fs';
+import { invoke } from '@tauri-apps/api/core';
 import { useMemo, useState } from 'react';
 import {
   formatDuration,
@@ -15,6 +15,7 @@ import {
   formatDateTime,
 } from '@/lib/utils';
 import { useFlightStore } from '@/stores/flightStore';
+import { FlightAnnotations } from './FlightAnnotations';
 
 interface FlightStatsProps {
   data: FlightDataResponse;
@@ -39,159 +40,12 @@ export function FlightStats({ data }: FlightStatsProps) {
       { id: 'json', label: 'JSON', extension: 'json' },
       { id: 'gpx', label: 'GPX', extension: 'gpx' },
       { id: 'kml', label: 'KML', extension: 'kml' },
+      { id: 'geojson', label: 'GeoJSON', extension: 'geojson' },
+      { id: 'raw', label: 'Original log', extension: 'txt' },
     ],
     []
   );
 
-  const buildCsv = () => {
-    const trackAligned = data.track.length === telemetry.time.length;
-    const latSeries = telemetry.latitude ?? [];
-    const lngSeries = telemetry.longitude ?? [];
-    const distanceToHome = computeDistanceToHomeSeries(telemetry);
-    const headers = [
-      'time_s',
-      'lat',
-      'lng',
-      'alt_m',
-      'distance_to_home_m',
-      'height_m',
-      'vps_height_m',
-      'altitude_m',
-      'speed_ms',
-      'velocity_x_ms',
-      'velocity_y_ms',
-      'velocity_z_ms',
-      'battery_percent',
-      'battery_voltage_v',
-      'battery_temp_c',
-      'satellites',
-      'rc_signal',
-      'rc_uplink',
-      'rc_downlink',
-      'pitch_deg',
-      'roll_deg',
-      'yaw_deg',
-    ];
-
-    const escapeCsv = (value: string) => {
-      if (value.includes('"')) value = value.replace(/"/g, '""');
-      if (value.includes(',') || value.includes('\n') || value.includes('\r')) {
-        return `"${value}"`;
-      }
-      return value;
-    };
-
-    const getValue = (arr: (number | null)[] | undefined, index: number) => {
-      const val = arr?.[index];
-      return val === null || val === undefined ? '' : String(val);
-    };
-
-    const rows = telemetry.time.map((time, index) => {
-      const track = trackAligned ? data.track[index] : null;
-      const lat = track ? track[1] : latSeries[index];
-      const lng = track ? track[0] : lngSeries[index];
-      const alt = track ? track[2] : '';
-      const values = [
-        String(time),
-        lat === null || lat === undefined ? '' : String(lat),
-        lng === null || lng === undefined ? '' : String(lng),
-        alt === null || alt === undefined ? '' : String(alt),
-        distanceToHome[index] === null || distanceToHome[index] === undefined
-          ? ''
-          : String(distanceToHome[index]),
-        getValue(telemetry.height, index),
-        getValue(telemetry.vpsHeight, index),
-        getValue(telemetry.altitude, index),
-        getValue(telemetry.speed, index),
-        getValue(telemetry.velocityX, index),
-        getValue(telemetry.velocityY, index),
-        getValue(telemetry.velocityZ, index),
-        getValue(telemetry.battery, index),
-        getValue(telemetry.batteryVoltage, index),
-        getValue(telemetry.batteryTemp, index),
-        getValue(telemetry.satellites, index),
-        getValue(telemetry.rcSignal, index),
-        getValue(telemetry.rcUplink, index),
-        getValue(telemetry.rcDownlink, index),
-        getValue(telemetry.pitch, index),
-        getValue(telemetry.roll, index),
-        getValue(telemetry.yaw, index),
-      ].map(escapeCsv);
-      return values.join(',');
-    });
-
-    return [headers.join(','), ...rows].join('\n');
-  };
-
-  const buildJson = () => {
-    return JSON.stringify(
-      {
-        exportedAt: new Date().toISOString(),
-        flight,
-        telemetry,
-        track: data.track,
-        derived: {
-          distanceToHome: computeDistanceToHomeSeries(telemetry),
-        },
-      },
-      null,
-      2
-    );
-  };
-
-  const buildGpx = () => {
-    const name = flight.displayName || flight.fileName || 'DJI Flight';
-    const points = data.track
-      .map(([lng, lat, alt]) => {
-        return `      <trkpt lat="${lat}" lon="${lng}"><ele>${alt}</ele></trkpt>`;
-      })
-      .join('\n');
-
-    return `<?xml version="1.0" encoding="UTF-8"?>
-<gpx version="1.1" creator="DJI Log Viewer" xmlns="http://www.topografix.com/GPX/1/1">
-  <metadata>
-    <name>${escapeXml(name)}</name>
-    <time>${new Date().toISOString()}</time>
-  </metadata>
-  <trk>
-    <name>${escapeXml(name)}</name>
-    <trkseg>
-${points}
-    </trkseg>
-  </trk>
-</gpx>`;
-  };
-
-  const buildKml = () => {
-    const name = flight.displayName || flight.fileName || 'DJI Flight';
-    const coordinates = data.track
-      .map(([lng, lat, alt]) => `${lng},${lat},${alt}`)
-      .join(' ');
-    return `<?xml version="1.0" encoding="UTF-8"?>
-<kml xmlns="http://www.opengis.net/kml/2.2">
-  <Document>
-    <name>${escapeXml(name)}</name>
-    <Placemark>
-      <name>${escapeXml(name)}</name>
-      <LineString>
-        <tessellate>1</tessellate>
-        <altitudeMode>absolute</altitudeMode>
-        <coordinates>${coordinates}</coordinates>
-      </LineString>
-    </Placemark>
-  </Document>
-</kml>`;
-  };
-
-  const escapeXml = (value: string) => {
-    return value
-      .replace(/&/g, '&amp;')
-      .replace(/</g, '&lt;')
-      .replace(/>/g, '&gt;')
-      .replace(/"/g, '&quot;')
-      .replace(/'/g, '&apos;');
-  };
-
   const handleExport = async (format: string, extension: string) => {
     if (isExporting) return;
     setIsExporting(true);
@@ -207,25 +61,21 @@ ${points}
       });
       if (!filePath) return;
 
-      let content = '';
-      switch (format) {
-        case 'csv':
-          content = buildCsv();
-          break;
-        case 'json':
-          content = buildJson();
-          break;
-        case 'gpx':
-          content = buildGpx();
-          break;
-        case 'kml':
-          content = buildKml();
-          break;
-        default:
-          return;
+      if (format === 'raw') {
+        // Byte-identical copy of the archived source file (audit trail)
+        await invoke('export_flight_raw_log', {
+          flightId: flight.id,
+          destinationPath: filePath,
+        });
+        return;
       }
 
-      await writeTextFile(filePath, content);
+      // Full-resolution telemetry is streamed from the database by the backend
+      await invoke('export_flights', {
+        flightIds: [flight.id],
+        format,
+        destinationPath: filePath,
+      });
     } catch (error) {
       console.error('Export failed:', error);
     } finally {
@@ -263,6 +113,19 @@ ${points}
                 Battery SN: {flight.batterySerial}
               </span>
             )}
+            {flight.firmwareVersion && (
+              <span className="px-2 py-0.5 rounded-full text-xs border border-gray-600/60 text-gray-400 bg-dji-surface/60">
+                FW: {flight.firmwareVersion}
+              </span>
+            )}
+            {flight.daylight && flight.daylight !== 'day' && (
+              <span
+                className="px-2 py-0.5 rounded-full text-xs border border-indigo-400/50 text-indigo-300 bg-indigo-500/10"
+                title="Sun below the horizon at takeoff or landing"
+              >
+                {flight.daylight === 'night' ? 'Night' : 'Civil twilight'}
+              </span>
+            )}
           </div>
         </div>
 
@@ -270,6 +133,12 @@ ${points}
           <p className="text-xs text-gray-500">
             {flight.pointCount?.toLocaleString() || 0} data points
           </p>
+          {(flight.photoCount || flight.videoSecs) ? (
+            <p className="text-xs text-gray-500">
+              {flight.photoCount || 0} photos
+              {flight.videoSecs ? ` · ${formatDuration(flight.videoSecs)} video` : ''}
+            </p>
+          ) : null}
         </div>
       </div>
 
@@ -336,6 +205,8 @@ ${points}
           )}
         </div>
       </div>
+
+      <FlightAnnotations flight={flight} />
     </div>
   );
 }
@@ -495,42 +366,3 @@ function ChevronIcon() {
     </svg>
   );
 }
-
-function computeDistanceToHomeSeries(telemetry: FlightDataResponse['telemetry']) {
-  const lats = telemetry.latitude ?? [];
-  const lngs = telemetry.longitude ?? [];
-  let homeLat: number | null = null;
-  let homeLng: number | null = null;
-  for (let i = 0; i < lats.length; i += 1) {
-    const lat = lats[i];
-    const lng = lngs[i];
-    if (typeof lat === 'number' && typeof lng === 'number') {
-      homeLat = lat;
-      homeLng = lng;
-      break;
-    }
-  }
-  if (homeLat === null || homeLng === null) {
-    return telemetry.time.map(() => null);
-  }
-
-  return telemetry.time.map((_, index) => {
-    const lat = lats[index];
-    const lng = lngs[index];
-    if (typeof lat !== 'number' || typeof lng !== 'number') return null;
-    return haversineDistance(homeLat, homeLng, lat, lng);
-  });
-}
-
-function haversineDistance(lat1: number, lon1: number, lat2: number, lon2: number): number {
-  const toRad = (value: number) => (value * Math.PI) / 180;
-  const r = 6371000;
-  const dLat = toRad(lat2 - lat1);
-  const dLon = toRad(lon2 - lon1);
-  const a =
-    Math.sin(dLat / 2) * Math.sin(dLat / 2) +
-    Math.cos(toRad(lat1)) * Math.cos(toRad(lat2)) * Math.sin(dLon / 2) * Math.sin(dLon / 2);
-  const c = 2 * Math.atan2(Math.sqrt(a), Math.sqrt(1 - a));
-  return r * c;
-}
-
@@ -7,7 +7,8 @@ import { useCallback, useEffect, useMemo, useRef, useState } from 'react';
 import ReactECharts from 'echarts-for-react';
 import { DayPicker, type DateRange } from 'react-day-picker';
 import 'react-day-picker/dist/style.css';
-import type { BatteryHealthPoint, Flight, OverviewStats } from '@/types';
+import { invoke } from '@tauri-apps/api/core';
+import type { BatteryHealthPoint, Daylight, Flight, FlightQuery, OverviewStats } from '@/types';
 import {
   formatDistance,
   formatDuration,
@@ -17,6 +18,9 @@ import {
   type UnitSystem,
 } from '@/lib/utils';
 import { useFlightStore } from '@/stores/flightStore';
+import { AircraftRegistry } from './AircraftRegistry';
+import { BatteryRegistry } from './BatteryRegistry';
+import { PilotLogbook } from './PilotLogbook';
 
 function resolveThemeMode(mode: 'system' | 'dark' | 'light'): 'dark' | 'light' {
   if (mode === 'system') {
@@ -50,6 +54,7 @@ export function Overview({ stats, flights, unitSystem, onSelectFlight }: Overvie
   } | null>(null);
   const [selectedDrone, setSelectedDrone] = useState('');
   const [selectedBattery, setSelectedBattery] = useState('');
+  const [selectedDaylight, setSelectedDaylight] = useState<Daylight | ''>('');
   const dateButtonRef = useRef<HTMLButtonElement | null>(null);
 
   const dateFormatter = useMemo(
@@ -132,163 +137,71 @@ export function Overview({ stats, flights, unitSystem, onSelectFlight }: Overvie
     return Array.from(unique);
   }, [flights]);
 
-  // Filter flights
-  const filteredFlights = useMemo(() => {
-    const start = dateRange?.from ?? null;
-    const end = dateRange?.to ? new Date(dateRange.to) : null;
-    if (end) end.setHours(23, 59, 59, 999);
-
-    return flights.filter((flight) => {
-      if (start || end) {
-        if (!flight.startTime) return false;
-        const flightDate = new Date(flight.startTime);
-        if (start && flightDate < start) return false;
-        if (end && flightDate > end) return false;
-      }
+  const hasFilters = Boolean(
+    dateRange?.from || dateRange?.to || selectedDrone || selectedBattery || selectedDaylight
+  );
 
-      if (selectedDrone) {
-        const key = `${flight.droneModel ?? ''}||${flight.droneSerial ?? ''}`;
-        if (key !== selectedDrone) return false;
-      }
+  // Filtered stats are computed by the backend over the matching flights
+  const [filteredOverview, setFilteredOverview] = useState<OverviewStats | null>(null);
+  const query = useMemo<FlightQuery | null>(() => {
+    if (!hasFilters) return null;
+    const pad = (value: number) => String(value).padStart(2, '0');
+    const toDay = (date: Date) =>
+      `${date.getFullYear()}-${pad(date.getMonth() + 1)}-${pad(date.getDate())}`;
+    const [droneModel, droneSerial] = selectedDrone ? selectedDrone.split('||') : [];
+    return {
+      dateFrom: dateRange?.from ? toDay(dateRange.from) : null,
+      dateTo: dateRange?.to ? toDay(dateRange.to) : null,
+      droneModel: droneModel || null,
+      droneSerial: droneSerial || null,
+      batterySerial: selectedBattery || null,
+      daylight: selectedDaylight || null,
+    };
+  }, [dateRange, hasFilters, selectedBattery, selectedDaylight, selectedDrone]);
 
-      if (selectedBattery) {
-        if (flight.batterySerial !== selectedBattery) return false;
-      }
+  useEffect(() => {
+    if (!query) {
+      setFilteredOverview(null);
+      return;
+    }
+    let cancelled = false;
+    invoke<OverviewStats>('get_overview_stats', { query })
+      .then((result) => {
+        if (!cancelled) setFilteredOverview(result);
+      })
+      .catch((err) => console.error('Failed to load filtered overview stats:', err));
+    return () => {
+      cancelled = true;
+    };
+  }, [query, stats]);
 
-      return true;
-    });
-  }, [dateRange, flights, selectedBattery, selectedDrone]);
+  const activeStats = (hasFilters && filteredOverview) || stats;
 
-  // Compute filtered stats
   const filteredStats = useMemo(() => {
-    const totalFlights = filteredFlights.length;
-    const totalDistanceM = filteredFlights.reduce((sum, f) => sum + (f.totalDistance ?? 0), 0);
-    const totalDurationSecs = filteredFlights.reduce((sum, f) => sum + (f.durationSecs ?? 0), 0);
-    const totalPoints = filteredFlights.reduce((sum, f) => sum + (f.pointCount ?? 0), 0);
-    const maxAltitudeM = Math.max(0, ...filteredFlights.map((f) => f.maxAltitude ?? 0));
-
-    // Battery usage
-    const batteryMap = new Map<string, { count: number; duration: number }>();
-    filteredFlights.forEach((f) => {
-      if (f.batterySerial) {
-        const existing = batteryMap.get(f.batterySerial) || { count: 0, duration: 0 };
-        batteryMap.set(f.batterySerial, {
-          count: existing.count + 1,
-          duration: existing.duration + (f.durationSecs ?? 0),
-        });
-      }
-    });
-    const batteriesUsed = Array.from(batteryMap.entries())
-      .map(([serial, data]) => ({
-        batterySerial: serial,
-        flightCount: data.count,
-        totalDurationSecs: data.duration,
-      }))
-      .sort((a, b) => b.flightCount - a.flightCount);
-
-    // Drone usage with disambiguation for same model names
-    const droneMap = new Map<string, { model: string; serial: string | null; name: string | null; count: number }>();
-    filteredFlights.forEach((f) => {
-      const key = `${f.droneModel ?? 'Unknown'}||${f.droneSerial ?? ''}`;
-      const existing = droneMap.get(key);
-      if (existing) {
-        existing.count++;
-      } else {
-        droneMap.set(key, {
-          model: f.droneModel ?? 'Unknown',
-          serial: f.droneSerial ?? null,
-          name: f.aircraftName ?? null,
-          count: 1,
-        });
-      }
-    });
-
-    // Check if any model names are duplicated
+    // Disambiguate drones sharing a model name with their serial
     const modelCounts = new Map<string, number>();
-    droneMap.forEach((d) => {
-      const displayName = d.name || d.model;
+    activeStats.dronesUsed.forEach((d) => {
+      const displayName = d.aircraftName || d.droneModel;
       modelCounts.set(displayName, (modelCounts.get(displayName) || 0) + 1);
     });
 
-    const dronesUsed = Array.from(droneMap.entries())
-      .map(([_, data]) => {
-        const displayName = data.name || data.model;
-        const needsSerial = (modelCounts.get(displayName) || 0) > 1 && data.serial;
-        return {
-          droneModel: data.model,
-          droneSerial: data.serial,
-          aircraftName: data.name,
-          flightCount: data.count,
-          displayLabel: needsSerial ? `${displayName} (${data.serial})` : displayName,
-        };
-      })
-      .sort((a, b) => b.flightCount - a.flightCount);
-
-    // Flights by date (from filtered)
-    const dateMap = new Map<string, number>();
-    const pad = (value: number) => String(value).padStart(2, '0');
-    const toDateKey = (value: string) => {
-      const date = new Date(value);
-      if (Number.isNaN(date.getTime())) return value.split('T')[0];
-      return `${date.getFullYear()}-${pad(date.getMonth() + 1)}-${pad(date.getDate())}`;
-    };
-    filteredFlights.forEach((f) => {
-      if (f.startTime) {
-        const date = toDateKey(f.startTime);
-        dateMap.set(date, (dateMap.get(date) || 0) + 1);
-      }
+    const dronesUsed = activeStats.dronesUsed.map((d) => {
+      const displayName = d.aircraftName || d.droneModel;
+      const needsSerial = (modelCounts.get(displayName) || 0) > 1 && d.droneSerial;
+      return {
+        ...d,
+        displayLabel: needsSerial ? `${displayName} (${d.droneSerial})` : displayName,
+      };
     });
-    const flightsByDate = Array.from(dateMap.entries())
-      .map(([date, count]) => ({ date, count }))
-      .sort((a, b) => a.date.localeCompare(b.date));
-
-    // Top 3 longest flights
-    const topFlights = [...filteredFlights]
-      .filter((f) => f.durationSecs !== null)
-      .sort((a, b) => (b.durationSecs ?? 0) - (a.durationSecs ?? 0))
-      .slice(0, 3)
-      .map((f) => ({
-        id: f.id,
-        displayName: f.displayName || f.fileName,
-        durationSecs: f.durationSecs ?? 0,
-        startTime: f.startTime,
-      }));
-
-
-    // For max distance from home, use the global stat if no filter applied
-    const hasFilters = dateRange?.from || dateRange?.to || selectedDrone || selectedBattery;
-    const maxDistanceFromHomeM = hasFilters ? 0 : stats.maxDistanceFromHomeM;
-
-    return {
-      totalFlights,
-      totalDistanceM,
-      totalDurationSecs,
-      totalPoints,
-      maxAltitudeM,
-      maxDistanceFromHomeM,
-      batteriesUsed,
-      dronesUsed,
-      flightsByDate,
-      topFlights,
-    };
-  }, [filteredFlights, dateRange, selectedDrone, selectedBattery, stats.maxDistanceFromHomeM]);
 
-  const filteredHealthPoints = useMemo(() => {
-    if (!stats.batteryHealthPoints.length) return [] as BatteryHealthPoint[];
-    const idSet = new Set(filteredFlights.map((flight) => flight.id));
-    return stats.batteryHealthPoints.filter((point) => idSet.has(point.flightId));
-  }, [filteredFlights, stats.batteryHealthPoints]);
+    return { ...activeStats, dronesUsed };
+  }, [activeStats]);
 
-  const filteredTopDistanceFlights = useMemo(() => {
-    if (!stats.topDistanceFlights?.length) return [] as typeof stats.topDistanceFlights;
-    const idSet = new Set(filteredFlights.map((flight) => flight.id));
-    return stats.topDistanceFlights
-      .filter((flight) => idSet.has(flight.id))
-      .sort((a, b) => b.maxDistanceFromHomeM - a.maxDistanceFromHomeM)
-      .slice(0, 3);
-  }, [filteredFlights, stats.topDistanceFlights]);
-
-  const hasFilters = dateRange?.from || dateRange?.to || selectedDrone || selectedBattery;
+  const filteredHealthPoints: BatteryHealthPoint[] = activeStats.batteryHealthPoints;
+  const filteredTopDistanceFlights = useMemo(
+    () => (activeStats.topDistanceFlights ?? []).slice(0, 3),
+    [activeStats.topDistanceFlights]
+  );
 
   const avgDistancePerFlight =
     filteredStats.totalFlights > 0
@@ -398,11 +311,26 @@ export function Overview({ stats, flights, unitSystem, onSelectFlight }: Overvie
             </select>
           </div>
 
+          <div className="flex-1 min-w-[140px]">
+            <label className="block text-xs text-gray-400 mb-1">Light</label>
+            <select
+              value={selectedDaylight}
+              onChange={(e) => setSelectedDaylight(e.target.value as Daylight | '')}
+              className="input w-full text-xs h-8 px-3 py-0 leading-[1.2]"
+            >
+              <option value="">Any light</option>
+              <option value="day">Day</option>
+              <option value="civilTwilight">Civil twilight</option>
+              <option value="night">Night</option>
+            </select>
+          </div>
+
           <button
             onClick={() => {
               setDateRange(undefined);
               setSelectedDrone('');
               setSelectedBattery('');
+              setSelectedDaylight('');
             }}
             disabled={!hasFilters}
             className={`h-8 px-3 rounded-lg text-xs font-medium transition-colors ${
@@ -431,7 +359,7 @@ export function Overview({ stats, flights, unitSystem, onSelectFlight }: Overvie
         <StatCard label="Max Altitude" value={formatAltitude(filteredStats.maxAltitudeM, unitSystem)} small />
         <StatCard
           label="Max Distance from Home"
-          value={hasFilters ? '--' : formatDistance(filteredStats.maxDistanceFromHomeM, unitSystem)}
+          value={formatDistance(filteredStats.maxDistanceFromHomeM, unitSystem)}
           small
         />
         <StatCard label="Avg Distance / Flight" value={formatDistance(avgDistancePerFlight, unitSystem)} small />
@@ -439,6 +367,17 @@ export function Overview({ stats, flights, unitSystem, onSelectFlight }: Overvie
         <StatCard label="Avg Speed" value={formatSpeed(avgSpeed, unitSystem)} small />
       </div>
 
+      {/* Light conditions, from the sun elevation at takeoff and landing */}
+        <div className="grid grid-cols-3 gap-3">
+        <StatCard label="Day Flights" value={filteredStats.dayFlights.toLocaleString()} small />
+        <StatCard
+          label="Civil Twilight Flights"
+          value={filteredStats.civilTwilightFlights.toLocaleString()}
+          small
+        />
+        <StatCard label="Night Flights" value={filteredStats.nightFlights.toLocaleString()} small />
+      </div>
+
       {/* Activity Heatmap */}
         <div className="card p-4">
           <h3 className="text-sm font-semibold text-white mb-3 text-center">
@@ -562,6 +501,15 @@ export function Overview({ stats, flights, unitSystem, onSelectFlight }: Overvie
           </div>
         </div>
         </div>
+
+        {/* Battery Registry */}
+        <BatteryRegistry refreshKey={stats} onSelectFlight={onSelectFlight} />
+
+        {/* Aircraft Registry */}
+        <AircraftRegistry refreshKey={stats} />
+
+        {/* Pilots & Logbook */}
+        <PilotLogbook refreshKey={stats} />
       </div>
     </div>
   );
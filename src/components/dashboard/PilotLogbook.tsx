@@ -0,0 +1,343 @@
+/**
+ * Pilots: totals, aircraft assignment rules and printable logbooks
+ */
+
+import { useCallback, useEffect, useState } from 'react';
+import { invoke } from '@tauri-apps/api/core';
+import { save } from '@tauri-apps/plugin-dialog';
+import type { Aircraft, LogbookStyle, Pilot } from '@/types';
+import { useFlightStore } from '@/stores/flightStore';
+import { formatDateTime } from '@/lib/utils';
+
+interface PilotLogbookProps {
+  /** Changes whenever flights are imported or removed */
+  refreshKey: unknown;
+}
+
+function formatHours(seconds: number): string {
+  return `${(seconds / 3600).toFixed(1)} h`;
+}
+
+export function PilotLogbook({ refreshKey }: PilotLogbookProps) {
+  const { loadFlights, loadPilots } = useFlightStore();
+  const [pilots, setPilots] = useState<Pilot[]>([]);
+  const [serials, setSerials] = useState<string[]>([]);
+  const [drafts, setDrafts] = useState<Record<number, { name: string; license: string }>>({});
+  const [expanded, setExpanded] = useState<number | null>(null);
+  const [newPilot, setNewPilot] = useState({ name: '', license: '' });
+  const [ruleSerial, setRuleSerial] = useState('');
+  const [style, setStyle] = useState<LogbookStyle>('easa');
+  const [html, setHtml] = useState(true);
+  const [message, setMessage] = useState<string | null>(null);
+  const [error, setError] = useState<string | null>(null);
+
+  const load = useCallback(async () => {
+    try {
+      const [loaded, aircraft] = await Promise.all([
+        invoke<Pilot[]>('get_pilots'),
+        invoke<Aircraft[]>('get_aircraft'),
+      ]);
+      setPilots(loaded);
+      setSerials(aircraft.map((a) => a.serial));
+      setDrafts(
+        Object.fromEntries(
+          loaded.map((p) => [p.id, { name: p.name, license: p.licenseNumber ?? '' }])
+        )
+      );
+      setError(null);
+    } catch (err) {
+      setError(`Failed to load pilots: ${err}`);
+    }
+  }, []);
+
+  useEffect(() => {
+    load();
+  }, [load, refreshKey]);
+
+  // Pilot edits also change the pilot names shown on flights
+  const reload = async () => {
+    await Promise.all([load(), loadPilots(), loadFlights()]);
+  };
+
+  const addPilot = async () => {
+    try {
+      await invoke('add_pilot', { name: newPilot.name, licenseNumber: newPilot.license });
+      setNewPilot({ name: '', license: '' });
+      await reload();
+    } catch (err) {
+      setError(`${err}`);
+    }
+  };
+
+  const savePilot = async (pilot: Pilot) => {
+    const draft = drafts[pilot.id];
+    if (!draft) return;
+    if (draft.name.trim() === pilot.name && draft.license.trim() === (pilot.licenseNumber ?? '')) {
+      return;
+    }
+    try {
+      await invoke('update_pilot', {
+        pilotId: pilot.id,
+        name: draft.name,
+        licenseNumber: draft.license,
+        notes: pilot.notes ?? '',
+      });
+      await reload();
+    } catch (err) {
+      setError(`${err}`);
+    }
+  };
+
+  const deletePilot = async (pilot: Pilot) => {
+    try {
+      await invoke('delete_pilot', { pilotId: pilot.id });
+      if (expanded === pilot.id) setExpanded(null);
+      await reload();
+    } catch (err) {
+      setError(`${err}`);
+    }
+  };
+
+  const setAircraftPilot = async (serial: string, pilotId: number | null) => {
+    try {
+      const assigned = await invoke<number>('set_aircraft_pilot', { serial, pilotId });
+      setRuleSerial('');
+      setMessage(pilotId !== null ? `${assigned} existing flight(s) of ${serial} assigned` : null);
+      await reload();
+    } catch (err) {
+      setError(`${err}`);
+    }
+  };
+
+  const exportLogbook = async (pilot: Pilot) => {
+    const extension = html ? 'html' : 'csv';
+    const destinationPath = await save({
+      title: 'Export pilot logbook',
+      defaultPath: `logbook-${pilot.name.replace(/[^\w-]+/g, '_')}-${style}.${extension}`,
+      filters: [{ name: html ? 'HTML' : 'CSV', extensions: [extension] }],
+    });
+    if (!destinationPath) return;
+
+    try {
+      const count = await invoke<number>('export_logbook', {
+        query: { pilotId: pilot.id },
+        style,
+        html,
+        destinationPath,
+      });
+      setMessage(`Exported ${count} flight(s) to ${destinationPath}`);
+      setError(null);
+    } catch (err) {
+      setError(`${err}`);
+    }
+  };
+
+  const selected = pilots.find((p) => p.id === expanded) ?? null;
+  const ruleOwners = new Map(pilots.flatMap((p) => p.aircraftSerials.map((s) => [s, p.name] as const)));
+
+  return (
+    <div className="card p-4">
+      <h3 className="text-sm font-semibold text-white mb-3">Pilots & Logbook</h3>
+
+      {error && <p className="text-xs text-red-400 mb-2">{error}</p>}
+      {message && <p className="text-xs text-gray-400 mb-2">{message}</p>}
+
+      {pilots.length > 0 && (
+        <div className="overflow-x-auto">
+          <table className="w-full text-xs">
+            <thead>
+              <tr className="text-left text-gray-500 border-b border-gray-700/50">
+                <th className="py-1.5 pr-3 font-medium">Name</th>
+                <th className="py-1.5 pr-3 font-medium">Licence</th>
+                <th className="py-1.5 pr-3 font-medium text-right">Flights</th>
+                <th className="py-1.5 pr-3 font-medium text-right">Hours</th>
+                <th className="py-1.5 pr-3 font-medium text-right">Night</th>
+                <th className="py-1.5 pr-3 font-medium text-right">Last 90 days</th>
+                <th className="py-1.5 pr-3 font-medium">Last flight</th>
+                <th className="py-1.5 font-medium" />
+              </tr>
+            </thead>
+            <tbody>
+              {pilots.map((pilot) => (
+                <tr key={pilot.id} className="border-b border-gray-700/30">
+                  <td className="py-1.5 pr-3">
+                    <input
+                      type="text"
+                      value={drafts[pilot.id]?.name ?? ''}
+                      onChange={(e) =>
+                        setDrafts((all) => ({
+                          ...all,
+                          [pilot.id]: { ...all[pilot.id], name: e.target.value },
+                        }))
+                      }
+                      onBlur={() => savePilot(pilot)}
+                      onKeyDown={(e) => {
+                        if (e.key === 'Enter') {
+                          e.currentTarget.blur();
+                        }
+                      }}
+                      className="input text-xs h-7 w-32"
+                    />
+                  </td>
+                  <td className="py-1.5 pr-3">
+                    <input
+                      type="text"
+                      value={drafts[pilot.id]?.license ?? ''}
+                      onChange={(e) =>
+                        setDrafts((all) => ({
+                          ...all,
+                          [pilot.id]: { ...all[pilot.id], license: e.target.value },
+                        }))
+                      }
+                      onBlur={() => savePilot(pilot)}
+                      onKeyDown={(e) => {
+                        if (e.key === 'Enter') {
+                          e.currentTarget.blur();
+                        }
+                      }}
+                      placeholder="Licence no."
+                      className="input text-xs h-7 w-28"
+                    />
+                  </td>
+                  <td className="py-1.5 pr-3 text-right text-gray-300">{pilot.flightCount}</td>
+                  <td className="py-1.5 pr-3 text-right text-gray-300">
+                    {formatHours(pilot.totalDurationSecs)}
+                  </td>
+                  <td className="py-1.5 pr-3 text-right text-gray-300">{pilot.nightFlights}</td>
+                  <td
+                    className="py-1.5 pr-3 text-right text-gray-300"
+                    title={`${pilot.recentNightFlights} at night`}
+                  >
+                    {pilot.recentFlights} · {formatHours(pilot.recentDurationSecs)}
+                  </td>
+                  <td className="py-1.5 pr-3 text-gray-400">{formatDateTime(pilot.lastFlight)}</td>
+                  <td className="py-1.5 text-right whitespace-nowrap">
+                    <button
+                      type="button"
+                      onClick={() => setExpanded(expanded === pilot.id ? null : pilot.id)}
+                      className="text-gray-400 hover:text-white mr-3"
+                    >
+                      {expanded === pilot.id ? 'Close' : 'Logbook'}
+                    </button>
+                    <button
+                      type="button"
+                      onClick={() => deletePilot(pilot)}
+                      className="text-gray-500 hover:text-red-400"
+                    >
+                      Remove
+                    </button>
+                  </td>
+                </tr>
+              ))}
+            </tbody>
+          </table>
+        </div>
+      )}
+
+      {selected && (
+        <div className="mt-4 space-y-3">
+          <div>
+            <h4 className="text-xs font-semibold text-gray-300 mb-2">
+              Aircraft flown by {selected.name}
+            </h4>
+            <p className="text-xs text-gray-500 mb-2">
+              Flights of these aircraft are assigned to {selected.name} when imported.
+            </p>
+            <div className="flex flex-wrap items-center gap-1.5">
+              {selected.aircraftSerials.map((serial) => (
+                <span
+                  key={serial}
+                  className="flex items-center gap-1 px-2 py-0.5 rounded-full text-xs border border-gray-600 text-gray-300 font-mono"
+                >
+                  {serial}
+                  <button
+                    type="button"
+                    onClick={() => setAircraftPilot(serial, null)}
+                    className="text-gray-500 hover:text-white"
+                    title="Remove rule"
+                  >
+                    ×
+                  </button>
+                </span>
+              ))}
+              <select
+                value={ruleSerial}
+                onChange={(e) => setRuleSerial(e.target.value)}
+                className="input text-xs h-7"
+              >
+                <option value="">Add aircraft…</option>
+                {serials
+                  .filter((serial) => !selected.aircraftSerials.includes(serial))
+                  .map((serial) => (
+                    <option key={serial} value={serial}>
+                      {serial}
+                      {ruleOwners.has(serial) ? ` (now ${ruleOwners.get(serial)})` : ''}
+                    </option>
+                  ))}
+              </select>
+              <button
+                type="button"
+                onClick={() => setAircraftPilot(ruleSerial, selected.id)}
+                disabled={!ruleSerial}
+                className="btn-secondary text-xs h-7 px-3"
+              >
+                Assign
+              </button>
+            </div>
+          </div>
+
+          <div className="flex items-center gap-2">
+            <select
+              value={style}
+              onChange={(e) => setStyle(e.target.value as LogbookStyle)}
+              className="input text-xs h-7"
+            >
+              <option value="easa">EASA</option>
+              <option value="part107">FAA Part 107</option>
+            </select>
+            <select
+              value={html ? 'html' : 'csv'}
+              onChange={(e) => setHtml(e.target.value === 'html')}
+              className="input text-xs h-7"
+            >
+              <option value="html">Printable HTML</option>
+              <option value="csv">CSV</option>
+            </select>
+            <button
+              type="button"
+              onClick={() => exportLogbook(selected)}
+              className="btn-secondary text-xs h-7 px-3"
+            >
+              Export logbook
+            </button>
+          </div>
+        </div>
+      )}
+
+      <div className="flex items-center gap-2 mt-3">
+        <input
+          type="text"
+          value={newPilot.name}
+          onChange={(e) => setNewPilot({ ...newPilot, name: e.target.value })}
+          placeholder="Pilot name"
+          className="input text-xs h-7 flex-1"
+        />
+        <input
+          type="text"
+          value={newPilot.license}
+          onChange={(e) => setNewPilot({ ...newPilot, license: e.target.value })}
+          placeholder="Licence no. (optional)"
+          className="input text-xs h-7 w-40"
+        />
+        <button
+          type="button"
+          onClick={addPilot}
+          disabled={!newPilot.name.trim()}
+          className="btn-secondary text-xs h-7 px-3"
+        >
+          Add pilot
+        </button>
+      </div>
+    </div>
+  );
+}
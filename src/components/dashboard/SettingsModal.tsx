@@ -4,7 +4,9 @@
 
 import { useState, useEffect } from 'react';
 import { invoke } from '@tauri-apps/api/core';
+import { open, save } from '@tauri-apps/plugin-dialog';
 import { useFlightStore } from '@/stores/flightStore';
+import type { BackupManifest, ParquetImportReport, ReprocessReport, RestoreReport } from '@/types';
 
 interface SettingsModalProps {
   isOpen: boolean;
@@ -19,6 +21,12 @@ export function SettingsModal({ isOpen, onClose }: SettingsModalProps) {
   const [appDataDir, setAppDataDir] = useState('');
   const [appLogDir, setAppLogDir] = useState('');
   const [confirmDeleteAll, setConfirmDeleteAll] = useState(false);
+  const [isReprocessing, setIsReprocessing] = useState(false);
+  const [parquetPartition, setParquetPartition] = useState<'none' | 'flight' | 'month'>('none');
+  const [isParquetBusy, setIsParquetBusy] = useState(false);
+  const [restoreMerge, setRestoreMerge] = useState(false);
+  const [isBackupBusy, setIsBackupBusy] = useState(false);
+  const [newFieldName, setNewFieldName] = useState('');
   const {
     unitSystem,
     setUnitSystem,
@@ -29,6 +37,10 @@ export function SettingsModal({ isOpen, onClose }: SettingsModalProps) {
     clearSelection,
     donationAcknowledged,
     setDonationAcknowledged,
+    customFields,
+    loadCustomFields,
+    addCustomField,
+    deleteCustomField,
   } = useFlightStore();
 
   // Check if API key exists on mount
@@ -37,6 +49,7 @@ export function SettingsModal({ isOpen, onClose }: SettingsModalProps) {
       checkApiKey();
       getAppDataDir();
       getAppLogDir();
+      loadCustomFields();
     }
   }, [isOpen]);
 
@@ -118,6 +131,130 @@ export function SettingsModal({ isOpen, onClose }: SettingsModalProps) {
     }
   };
 
+  const handleReprocessAll = async () => {
+    setIsReprocessing(true);
+    try {
+      const report = await invoke<ReprocessReport>('reprocess_all_flights');
+      clearSelection();
+      await loadFlights();
+      await loadOverview();
+      setMessage({
+        type: report.failed > 0 ? 'error' : 'success',
+        text:
+          `Re-processed ${report.reprocessed} of ${report.totalFlights} flights` +
+          (report.missingArchive > 0 ? `, ${report.missingArchive} without archived log` : '') +
+          (report.failed > 0 ? `, ${report.failed} failed` : '') +
+          '.',
+      });
+    } catch (err) {
+      setMessage({ type: 'error', text: `Failed to re-process: ${err}` });
+    } finally {
+      setIsReprocessing(false);
+    }
+  };
+
+  const handleParquetExport = async () => {
+    const destinationDir = await open({ directory: true, title: 'Export logbook as Parquet' });
+    if (typeof destinationDir !== 'string') return;
+
+    setIsParquetBusy(true);
+    try {
+      const count = await invoke<number>('export_parquet', {
+        destinationDir,
+        partition: parquetPartition,
+      });
+      setMessage({ type: 'success', text: `Exported ${count} flights as Parquet.` });
+    } catch (err) {
+      setMessage({ type: 'error', text: `${err}` });
+    } finally {
+      setIsParquetBusy(false);
+    }
+  };
+
+  const handleParquetImport = async () => {
+    const sourceDir = await open({ directory: true, title: 'Import Parquet export' });
+    if (typeof sourceDir !== 'string') return;
+
+    setIsParquetBusy(true);
+    try {
+      const report = await invoke<ParquetImportReport>('import_parquet', { sourceDir });
+      await loadFlights();
+      await loadOverview();
+      setMessage({
+        type: 'success',
+        text:
+          `Imported ${report.flightsImported} flights` +
+          (report.flightsSkipped > 0 ? `, ${report.flightsSkipped} already in the library` : '') +
+          '.',
+      });
+    } catch (err) {
+      setMessage({ type: 'error', text: `${err}` });
+    } finally {
+      setIsParquetBusy(false);
+    }
+  };
+
+  const handleAddCustomField = async () => {
+    const name = newFieldName.trim();
+    if (!name) return;
+    await addCustomField(name);
+    setNewFieldName('');
+  };
+
+  const handleBackup = async () => {
+    const destinationPath = await save({
+      title: 'Back up logbook',
+      defaultPath: `logbook-backup-${new Date().toISOString().slice(0, 10)}.zip`,
+      filters: [{ name: 'Backup archive', extensions: ['zip'] }],
+    });
+    if (!destinationPath) return;
+
+    setIsBackupBusy(true);
+    try {
+      const manifest = await invoke<BackupManifest>('create_backup', { destinationPath });
+      setMessage({
+        type: 'success',
+        text: `Backed up ${manifest.flightCount} flights (${manifest.files.length} files).`,
+      });
+    } catch (err) {
+      setMessage({ type: 'error', text: `${err}` });
+    } finally {
+      setIsBackupBusy(false);
+    }
+  };
+
+  const handleRestore = async () => {
+    const archivePath = await open({
+      title: 'Restore logbook backup',
+      filters: [{ name: 'Backup archive', extensions: ['zip'] }],
+    });
+    if (typeof archivePath !== 'string') return;
+
+    setIsBackupBusy(true);
+    try {
+      const report = await invoke<RestoreReport>('restore_backup', {
+        archivePath,
+        mode: restoreMerge ? 'merge' : 'replace',
+      });
+      clearSelection();
+      await loadFlights();
+      await loadOverview();
+      setMessage({
+        type: 'success',
+        text:
+          report.mode === 'merge'
+            ? `Merged ${report.flightsRestored} flights` +
+              (report.flightsSkipped > 0 ? `, ${report.flightsSkipped} already in the library` : '') +
+              '.'
+            : `Restored ${report.flightsRestored} flights. The previous library was kept as a .bak file.`,
+      });
+    } catch (err) {
+      setMessage({ type: 'error', text: `${err}` });
+    } finally {
+      setIsBackupBusy(false);
+    }
+  };
+
   if (!isOpen) return null;
 
   return (
@@ -176,6 +313,56 @@ export function SettingsModal({ isOpen, onClose }: SettingsModalProps) {
             </select>
           </div>
 
+          {/* Custom Fields */}
+          <div>
+            <label className="block text-sm font-medium text-gray-300 mb-2">
+              Custom fields
+            </label>
+            <p className="text-xs text-gray-500 mb-3">
+              Extra values recorded per flight, such as client or job number.
+              Deleting a field removes its values from every flight.
+            </p>
+            {customFields.length > 0 && (
+              <div className="mb-2 flex flex-wrap gap-1.5">
+                {customFields.map((field) => (
+                  <span
+                    key={field}
+                    className="flex items-center gap-1 px-2 py-0.5 rounded-full text-xs border border-gray-600/60 text-gray-300 bg-dji-surface/60"
+                  >
+                    {field}
+                    <button
+                      type="button"
+                      onClick={() => deleteCustomField(field)}
+                      className="text-gray-500 hover:text-red-400"
+                      title="Delete field"
+                    >
+                      ×
+                    </button>
+                  </span>
+                ))}
+              </div>
+            )}
+            <div className="flex items-center gap-2">
+              <input
+                type="text"
+                value={newFieldName}
+                onChange={(e) => setNewFieldName(e.target.value)}
+                onKeyDown={(e) => {
+                  if (e.key === 'Enter') handleAddCustomField();
+                }}
+                placeholder="Field name"
+                className="input flex-1"
+              />
+              <button
+                onClick={handleAddCustomField}
+                disabled={!newFieldName.trim()}
+                className="py-2 px-3 rounded-lg border border-gray-600 text-gray-300 hover:bg-gray-500/10 transition-colors disabled:opacity-50"
+              >
+                Add
+              </button>
+            </div>
+          </div>
+
           {/* API Key Section */}
           <div>
             <label className="block text-sm font-medium text-gray-300 mb-2">
@@ -291,6 +478,64 @@ export function SettingsModal({ isOpen, onClose }: SettingsModalProps) {
             <p className="text-xs text-gray-500 mt-2">
               Your API key is stored locally in <code className="text-gray-400">config.json</code> and never sent to any external servers except DJI's official API.
             </p>
+            <button
+              onClick={handleReprocessAll}
+              disabled={isReprocessing}
+              className="mt-4 w-full py-2 px-3 rounded-lg border border-gray-600 text-gray-300 hover:bg-gray-500/10 transition-colors disabled:opacity-50"
+            >
+              {isReprocessing ? 'Re-processing...' : 'Re-process all flights from archived logs'}
+            </button>
+            <div className="mt-4 flex items-center gap-2">
+              <select
+                className="input flex-1"
+                value={parquetPartition}
+                onChange={(e) => setParquetPartition(e.target.value as 'none' | 'flight' | 'month')}
+                disabled={isParquetBusy}
+              >
+                <option value="none">Parquet: single files</option>
+                <option value="flight">Parquet: by flight</option>
+                <option value="month">Parquet: by month</option>
+              </select>
+              <button
+                onClick={handleParquetExport}
+                disabled={isParquetBusy}
+                className="py-2 px-3 rounded-lg border border-gray-600 text-gray-300 hover:bg-gray-500/10 transition-colors disabled:opacity-50"
+              >
+                Export
+              </button>
+              <button
+                onClick={handleParquetImport}
+                disabled={isParquetBusy}
+                className="py-2 px-3 rounded-lg border border-gray-600 text-gray-300 hover:bg-gray-500/10 transition-colors disabled:opacity-50"
+              >
+                Import
+              </button>
+            </div>
+            <div className="mt-3 flex items-center gap-2">
+              <button
+                onClick={handleBackup}
+                disabled={isBackupBusy}
+                className="flex-1 py-2 px-3 rounded-lg border border-gray-600 text-gray-300 hover:bg-gray-500/10 transition-colors disabled:opacity-50"
+              >
+                Back up
+              </button>
+              <button
+                onClick={handleRestore}
+                disabled={isBackupBusy}
+                className="flex-1 py-2 px-3 rounded-lg border border-gray-600 text-gray-300 hover:bg-gray-500/10 transition-colors disabled:opacity-50"
+              >
+                Restore
+              </button>
+            </div>
+            <label className="mt-2 flex items-center gap-2 text-xs text-gray-400">
+              <input
+                type="checkbox"
+                checked={restoreMerge}
+                onChange={(e) => setRestoreMerge(e.target.checked)}
+                disabled={isBackupBusy}
+              />
+              Merge into the current library instead of replacing it
+            </label>
             {confirmDeleteAll ? (
               <div className="mt-4 rounded-lg border border-red-600/60 bg-red-500/10 p-3">
                 <p className="text-xs text-red-200">
@@ -1,5 +1,9 @@
+export { AircraftRegistry } from './AircraftRegistry';
+export { BatteryRegistry } from './BatteryRegistry';
 export { Dashboard } from './Dashboard';
+export { FlightAnnotations } from './FlightAnnotations';
 export { FlightList } from './FlightList';
 export { FlightImporter } from './FlightImporter';
 export { FlightStats } from './FlightStats';
+export { PilotLogbook } from './PilotLogbook';
 export { SettingsModal } from './SettingsModal';
@@ -11,10 +11,13 @@ import { PathLayer } from '@deck.gl/layers';
 import DeckGL from '@deck.gl/react';
 import 'maplibre-gl/dist/maplibre-gl.css';
 import { getTrackCenter, calculateBounds } from '@/lib/utils';
+import type { CameraEvent, HomeEvent } from '@/types';
 
 interface FlightMapProps {
   track: [number, number, number][]; // [lng, lat, alt][]
   themeMode: 'system' | 'dark' | 'light';
+  cameraEvents?: CameraEvent[];
+  homeEvents?: HomeEvent[];
 }
 
 const MAP_STYLES = {
@@ -58,7 +61,12 @@ const getSessionBool = (key: string, fallback: boolean) => {
   return stored === 'true';
 };
 
-export function FlightMap({ track, themeMode }: FlightMapProps) {
+export function FlightMap({
+  track,
+  themeMode,
+  cameraEvents = [],
+  homeEvents = [],
+}: FlightMapProps) {
   const [viewState, setViewState] = useState({
     longitude: 0,
     latitude: 0,
@@ -164,6 +172,27 @@ export function FlightMap({ track, themeMode }: FlightMapProps) {
   const startPoint = track[0];
   const endPoint = track[track.length - 1];
 
+  // Photo capture points (events without a GPS fix can't be placed)
+  const photoPoints = useMemo(
+    () =>
+      cameraEvents.filter(
+        (e) => e.eventType === 'photo' && e.latitude !== null && e.longitude !== null
+      ),
+    [cameraEvents]
+  );
+
+  // Recorded home points (initial and any mid-flight updates)
+  const homePoints = useMemo(
+    () =>
+      homeEvents.filter(
+        (e) =>
+          (e.eventType === 'homeSet' || e.eventType === 'homeUpdated') &&
+          e.latitude !== null &&
+          e.longitude !== null
+      ),
+    [homeEvents]
+  );
+
   const handleMapMove = useCallback(
     ({ viewState: nextViewState }: { viewState: typeof viewState }) => {
       setViewState(nextViewState);
@@ -267,6 +296,38 @@ export function FlightMap({ track, themeMode }: FlightMapProps) {
           />
         </div>
 
+        {/* Photo Markers (Sky) */}
+        {photoPoints.map((photo, index) => (
+          <Marker
+            key={`photo-${photo.timestampMs}-${index}`}
+            longitude={photo.longitude!}
+            latitude={photo.latitude!}
+            anchor="center"
+          >
+            <div
+              className="w-2.5 h-2.5 bg-sky-400 rounded-full border border-white shadow"
+              title={`Photo ${index + 1} at ${(photo.timestampMs / 1000).toFixed(1)}s`}
+            />
+          </Marker>
+        ))}
+
+        {/* Home Markers (Green) */}
+        {homePoints.map((home, index) => (
+          <Marker
+            key={`home-${home.timestampMs}-${index}`}
+            longitude={home.longitude!}
+            latitude={home.latitude!}
+            anchor="center"
+          >
+            <div
+              className="w-5 h-5 flex items-center justify-center bg-green-500 text-white text-[10px] font-bold rounded-full border-2 border-white shadow-lg"
+              title={index === 0 ? 'Home point' : `Home point updated at ${(home.timestampMs / 1000).toFixed(1)}s`}
+            >
+              H
+            </div>
+          </Marker>
+        ))}
+
         {/* Start Marker (Yellow) */}
         {startPoint && (
           <Marker longitude={startPoint[0]} latitude={startPoint[1]} anchor="center">
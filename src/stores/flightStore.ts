@@ -5,7 +5,15 @@
 
 import { create } from 'zustand';
 import { invoke } from '@tauri-apps/api/core';
-import type { Flight, FlightDataResponse, ImportResult, OverviewStats } from '@/types';
+import type {
+  Flight,
+  FlightDataResponse,
+  FlightPage,
+  FolderImportReport,
+  ImportResult,
+  OverviewStats,
+  Pilot,
+} from '@/types';
 
 interface FlightState {
   // State
@@ -19,14 +27,26 @@ interface FlightState {
   unitSystem: 'metric' | 'imperial';
   themeMode: 'system' | 'dark' | 'light';
   donationAcknowledged: boolean;
+  /** Names of the defined custom fields */
+  customFields: string[];
+  pilots: Pilot[];
 
   // Actions
   loadFlights: () => Promise<void>;
   loadOverview: () => Promise<void>;
   selectFlight: (flightId: number) => Promise<void>;
   importLog: (filePath: string) => Promise<ImportResult>;
+  importFolder: (folderPath: string) => Promise<FolderImportReport | null>;
   deleteFlight: (flightId: number) => Promise<void>;
   updateFlightName: (flightId: number, displayName: string) => Promise<void>;
+  updateFlightNotes: (flightId: number, notes: string) => Promise<void>;
+  setFlightTags: (flightId: number, tags: string[]) => Promise<void>;
+  setFlightCustomField: (flightId: number, field: string, value: string) => Promise<void>;
+  loadCustomFields: () => Promise<void>;
+  addCustomField: (name: string) => Promise<void>;
+  deleteCustomField: (name: string) => Promise<void>;
+  loadPilots: () => Promise<void>;
+  setFlightPilot: (flightId: number, pilotId: number | null) => Promise<void>;
   setUnitSystem: (unitSystem: 'metric' | 'imperial') => void;
   setThemeMode: (themeMode: 'system' | 'dark' | 'light') => void;
   setDonationAcknowledged: (value: boolean) => void;
@@ -34,12 +54,29 @@ interface FlightState {
   clearError: () => void;
 }
 
+// Apply an edit to the flight in the list and, if selected, the loaded data
+const patchFlight =
+  (flightId: number, changes: Partial<Flight>) => (state: FlightState) => ({
+    flights: state.flights.map((flight) =>
+      flight.id === flightId ? { ...flight, ...changes } : flight
+    ),
+    currentFlightData:
+      state.currentFlightData && state.currentFlightData.flight.id === flightId
+        ? {
+            ...state.currentFlightData,
+            flight: { ...state.currentFlightData.flight, ...changes },
+          }
+        : state.currentFlightData,
+  });
+
 export const useFlightStore = create<FlightState>((set, get) => ({
   // Initial state
   flights: [],
   selectedFlightId: null,
   currentFlightData: null,
   overviewStats: null,
+  customFields: [],
+  pilots: [],
   isLoading: false,
   isImporting: false,
   error: null,
@@ -63,7 +100,7 @@ export const useFlightStore = create<FlightState>((set, get) => ({
   loadFlights: async () => {
     set({ isLoading: true, error: null });
     try {
-      const flights = await invoke<Flight[]>('get_flights');
+      const { flights } = await invoke<FlightPage>('get_flights');
       set({ flights, isLoading: false });
 
       // Auto-select last used flight if available (avoid heavy load on fresh startup)
@@ -145,6 +182,25 @@ export const useFlightStore = create<FlightState>((set, get) => ({
     }
   },
 
+  // Import every log in a folder (parsed in parallel by the backend)
+  importFolder: async (folderPath: string) => {
+    set({ isImporting: true, error: null });
+    try {
+      const report = await invoke<FolderImportReport>('import_folder', {
+        folderPath,
+        recursive: true,
+      });
+      if (report.imported > 0) {
+        await get().loadFlights();
+      }
+      set({ isImporting: false });
+      return report;
+    } catch (err) {
+      set({ isImporting: false, error: `Folder import failed: ${err}` });
+      return null;
+    }
+  },
+
   // Delete a flight
   deleteFlight: async (flightId: number) => {
     try {
@@ -190,6 +246,103 @@ export const useFlightStore = create<FlightState>((set, get) => ({
     }
   },
 
+  updateFlightNotes: async (flightId: number, notes: string) => {
+    try {
+      await invoke('update_flight_notes', { flightId, notes });
+      const trimmed = notes.trim();
+      set(patchFlight(flightId, { notes: trimmed.length > 0 ? trimmed : null }));
+    } catch (err) {
+      set({ error: `Failed to update flight notes: ${err}` });
+    }
+  },
+
+  setFlightTags: async (flightId: number, tags: string[]) => {
+    try {
+      const stored = await invoke<string[]>('set_flight_tags', { flightId, tags });
+      set(patchFlight(flightId, { tags: stored }));
+    } catch (err) {
+      set({ error: `Failed to update flight tags: ${err}` });
+    }
+  },
+
+  setFlightCustomField: async (flightId: number, field: string, value: string) => {
+    try {
+      await invoke('set_flight_custom_field', { flightId, field, value });
+      const flight = get().flights.find((f) => f.id === flightId);
+      const customFields = { ...(flight?.customFields ?? {}) };
+      const trimmed = value.trim();
+      if (trimmed.length > 0) {
+        customFields[field] = trimmed;
+      } else {
+        delete customFields[field];
+      }
+      set(patchFlight(flightId, { customFields }));
+    } catch (err) {
+      set({ error: `Failed to update custom field: ${err}` });
+    }
+  },
+
+  loadPilots: async () => {
+    try {
+      const pilots = await invoke<Pilot[]>('get_pilots');
+      set({ pilots });
+    } catch (err) {
+      set({ error: `Failed to load pilots: ${err}` });
+    }
+  },
+
+  setFlightPilot: async (flightId: number, pilotId: number | null) => {
+    try {
+      await invoke('set_flight_pilot', { flightId, pilotId });
+      const pilotName = get().pilots.find((p) => p.id === pilotId)?.name ?? null;
+      set(patchFlight(flightId, { pilotId, pilotName }));
+    } catch (err) {
+      set({ error: `Failed to assign pilot: ${err}` });
+    }
+  },
+
+  loadCustomFields: async () => {
+    try {
+      const customFields = await invoke<string[]>('get_custom_fields');
+      set({ customFields });
+    } catch (err) {
+      set({ error: `Failed to load custom fields: ${err}` });
+    }
+  },
+
+  addCustomField: async (name: string) => {
+    try {
+      await invoke('add_custom_field', { name });
+      await get().loadCustomFields();
+    } catch (err) {
+      set({ error: `Failed to add custom field: ${err}` });
+    }
+  },
+
+  deleteCustomField: async (name: string) => {
+    try {
+      await invoke('delete_custom_field', { name });
+      await get().loadCustomFields();
+
+      // Drop the field's values from the loaded flights
+      const withoutField = (flight: Flight) => ({
+        ...flight,
+        customFields: Object.fromEntries(
+          Object.entries(flight.customFields).filter(([field]) => field !== name)
+        ),
+      });
+      set((state) => ({
+        flights: state.flights.map(withoutField),
+        currentFlightData: state.currentFlightData && {
+          ...state.currentFlightData,
+          flight: withoutField(state.currentFlightData.flight),
+        },
+      }));
+    } catch (err) {
+      set({ error: `Failed to delete custom field: ${err}` });
+    }
+  },
+
   setUnitSystem: (unitSystem) => {
     if (typeof localStorage !== 'undefined') {
       localStorage.setItem('unitSystem', unitSystem);
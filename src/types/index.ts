@@ -12,6 +12,8 @@ export interface Flight {
   droneSerial: string | null;
   aircraftName: string | null;
   batterySerial: string | null;
+  /** Aircraft firmware version recorded in the log */
+  firmwareVersion: string | null;
   startTime: string | null;
   durationSecs: number | null;
   totalDistance: number | null;
@@ -19,7 +21,134 @@ export interface Flight {
   maxSpeed: number | null;
   homeLat?: number | null;
   homeLon?: number | null;
+  /** Darkest light at takeoff or landing; null without time or home point */
+  daylight: Daylight | null;
   pointCount: number | null;
+  /** Archived copy of the original log in raw_logs */
+  archivePath: string | null;
+  /** Path the log was originally imported from */
+  sourcePath: string | null;
+  photoCount: number | null;
+  videoSecs: number | null;
+  /** Number of anomalies flagged at import */
+  issueCount: number;
+  notes: string | null;
+  pilotId: number | null;
+  pilotName: string | null;
+  tags: string[];
+  /** Custom field values by field name */
+  customFields: Record<string, string>;
+}
+
+/** Sun above the horizon, up to 6° below it, or lower */
+export type Daylight = 'day' | 'civilTwilight' | 'night';
+
+export type FlightSortKey = 'startTime' | 'name' | 'duration' | 'distance' | 'maxAltitude';
+
+/** Latitude/longitude box; minLon > maxLon wraps across the antimeridian */
+export interface GeoBounds {
+  minLat: number;
+  minLon: number;
+  maxLat: number;
+  maxLon: number;
+}
+
+/** Filters, ordering and page for get_flights */
+export interface FlightQuery {
+  search?: string | null;
+  tags?: string[];
+  customFields?: Record<string, string>;
+  /** First/last day to include, YYYY-MM-DD (UTC) */
+  dateFrom?: string | null;
+  dateTo?: string | null;
+  droneModel?: string | null;
+  droneSerial?: string | null;
+  batterySerial?: string | null;
+  pilotId?: number | null;
+  daylight?: Daylight | null;
+  minDurationSecs?: number | null;
+  minDistanceM?: number | null;
+  bounds?: GeoBounds | null;
+  sortBy?: FlightSortKey;
+  sortDirection?: 'asc' | 'desc';
+  offset?: number | null;
+  /** Page size (all matching flights if unset) */
+  limit?: number | null;
+}
+
+/** Result from get_flights: one page plus totals over every match */
+export interface FlightPage {
+  flights: Flight[];
+  totalCount: number;
+  totalDurationSecs: number;
+  totalDistanceM: number;
+  offset: number;
+}
+
+export type CameraEventType = 'photo' | 'videoStart' | 'videoStop';
+
+export interface CameraEvent {
+  timestampMs: number;
+  eventType: CameraEventType;
+  latitude: number | null;
+  longitude: number | null;
+  altitude: number | null;
+}
+
+export type FlightEventSeverity = 'info' | 'warning';
+
+/** Tip or warning shown to the pilot by the DJI app */
+export interface FlightEvent {
+  timestampMs: number;
+  severity: FlightEventSeverity;
+  category: string;
+  message: string;
+}
+
+/** Flight event with its flight, from query_flight_events */
+export interface FlightEventEntry extends FlightEvent {
+  flightId: number;
+  displayName: string;
+  startTime: string | null;
+}
+
+export interface FlightEventQuery {
+  severity?: FlightEventSeverity | null;
+  category?: string | null;
+  search?: string | null;
+  limit?: number | null;
+}
+
+export type FindingSeverity = 'warning' | 'critical';
+
+/** Anomaly flagged by the telemetry analysis at import */
+export interface Finding {
+  timestampMs: number;
+  durationMs: number;
+  kind:
+    | 'gpsJump'
+    | 'altitudeLoss'
+    | 'voltageSag'
+    | 'attitudeExcursion'
+    | 'rcSignalLoss'
+    | 'satelliteDrop'
+    | 'cellImbalance';
+  severity: FindingSeverity;
+  message: string;
+  value: number | null;
+}
+
+export type HomeEventType = 'homeSet' | 'homeUpdated' | 'rthStart' | 'rthEnd';
+
+export interface HomeEvent {
+  timestampMs: number;
+  eventType: HomeEventType;
+  /** Home point for home events, aircraft position for RTH events */
+  latitude: number | null;
+  longitude: number | null;
+  altitude: number | null;
+  /** RTH trigger, e.g. low battery */
+  detail: string | null;
 }
 
 /** Telemetry data formatted for ECharts */
@@ -38,6 +167,12 @@ export interface TelemetryData {
   battery: (number | null)[];
   batteryVoltage: (number | null)[];
   batteryTemp: (number | null)[];
+  /** One voltage series per cell; empty when the log has no cell voltages */
+  batteryCells: (number | null)[][];
+  /** Max minus min cell voltage */
+  batteryCellImbalance: (number | null)[];
+  /** Remaining capacity in mAh */
+  batteryRemainingCapacity: (number | null)[];
   satellites: (number | null)[];
   rcSignal: (number | null)[];
   rcUplink?: (number | null)[];
@@ -53,6 +188,14 @@ export interface FlightDataResponse {
   telemetry: TelemetryData;
   /** GPS track: [lng, lat, height][] */
   track: [number, number, number][];
+  /** Photos taken and video start/stop, in time order */
+  cameraEvents: CameraEvent[];
+  /** Home point records and RTH phases, in time order */
+  homeEvents: HomeEvent[];
+  /** App tips and warnings, in time order */
+  events: FlightEvent[];
+  /** Anomalies found in the telemetry, in time order */
+  findings: Finding[];
 }
 
 export interface BatteryUsage {
@@ -96,6 +239,138 @@ export interface BatteryHealthPoint {
   ratePerMin: number;
 }
 
+/** Battery pack from the registry, with lifecycle figures over its flights */
+export interface BatteryPack {
+  serial: string;
+  name: string | null;
+  notes: string | null;
+  /** Set once the pack is retired */
+  retiredAt: string | null;
+  flightCount: number;
+  totalDurationSecs: number;
+  /** Equivalent full cycles (sum of charge used) */
+  cycles: number;
+  firstFlight: string | null;
+  lastFlight: string | null;
+  minCellVoltage: number | null;
+  maxTemp: number | null;
+  /** Median of the latest resistance estimates, mOhm */
+  internalResistanceMohm: number | null;
+  /** Median of the latest per-flight worst cell imbalance, V */
+  cellImbalance: number | null;
+  /** 100 while as good as new, 50 once resistance has doubled */
+  healthScore: number | null;
+  /** Health change per 100 cycles */
+  healthTrend: number | null;
+}
+
+/** One flight in a battery pack's history */
+export interface BatteryFlight {
+  flightId: number;
+  displayName: string;
+  startTime: string | null;
+  durationSecs: number | null;
+  dischargedPercent: number;
+  /** Running cycle count after this flight */
+  cycles: number;
+  minVoltage: number | null;
+  cellCount: number | null;
+  minCellVoltage: number | null;
+  maxTemp: number | null;
+  internalResistanceMohm: number | null;
+  /** Worst spread between cell voltages, V */
+  maxCellImbalance: number | null;
+  healthScore: number | null;
+}
+
+/** Result from get_battery */
+export interface BatteryDetail {
+  pack: BatteryPack;
+  flights: BatteryFlight[];
+}
+
+/** 'due' once most of an interval is used, 'overdue' once it is reached */
+export type MaintenanceStatus = 'ok' | 'due' | 'overdue';
+
+/** Recurring maintenance task of an aircraft */
+export interface MaintenanceItem {
+  id: number;
+  droneSerial: string;
+  name: string;
+  intervalHours: number | null;
+  intervalFlights: number | null;
+  /** Null until first marked done; counts from the first flight */
+  lastDoneAt: string | null;
+  notes: string | null;
+  hoursSince: number;
+  flightsSince: number;
+  /** Negative once overdue */
+  hoursRemaining: number | null;
+  flightsRemaining: number | null;
+  status: MaintenanceStatus;
+}
+
+/** Aircraft registry entry from get_aircraft */
+export interface Aircraft {
+  serial: string;
+  /** User-given name */
+  name: string | null;
+  notes: string | null;
+  /** Model and name recorded in the latest flight */
+  droneModel: string | null;
+  aircraftName: string | null;
+  flightCount: number;
+  /** Airframe time in seconds */
+  totalDurationSecs: number;
+  firstFlight: string | null;
+  lastFlight: string | null;
+  /** In the order they first appeared */
+  firmwareVersions: string[];
+  maintenance: MaintenanceItem[];
+}
+
+/** Pilot from get_pilots, with totals over their assigned flights */
+export interface Pilot {
+  id: number;
+  name: string;
+  licenseNumber: string | null;
+  notes: string | null;
+  /** Aircraft whose flights are assigned to this pilot on import */
+  aircraftSerials: string[];
+  flightCount: number;
+  totalDurationSecs: number;
+  /** Flights with the takeoff or landing after civil dusk */
+  nightFlights: number;
+  /** Totals over the last 90 days */
+  recentFlights: number;
+  recentDurationSecs: number;
+  recentNightFlights: number;
+  lastFlight: string | null;
+}
+
+/** Column layout of an exported logbook */
+export type LogbookStyle = 'easa' | 'part107';
+
+/** One row of a pilot logbook from get_logbook */
+export interface LogbookEntry {
+  flightId: number;
+  displayName: string;
+  pilotId: number | null;
+  pilotName: string | null;
+  droneModel: string | null;
+  droneSerial: string | null;
+  aircraftName: string | null;
+  takeoffTime: string | null;
+  landingTime: string | null;
+  durationSecs: number;
+  homeLat: number | null;
+  homeLon: number | null;
+  maxAltitude: number | null;
+  totalDistance: number | null;
+  daylight: Daylight | null;
+  notes: string | null;
+}
+
 export interface OverviewStats {
   totalFlights: number;
   totalDistanceM: number;
@@ -109,6 +384,10 @@ export interface OverviewStats {
   topFlights: TopFlight[];
   topDistanceFlights: TopDistanceFlight[];
   batteryHealthPoints: BatteryHealthPoint[];
+  /** Flights by light conditions; flights without time or home point are in none */
+  dayFlights: number;
+  civilTwilightFlights: number;
+  nightFlights: number;
 }
 
 /** Result from import_log command */
@@ -119,6 +398,86 @@ export interface ImportResult {
   pointCount: number;
 }
 
+/** Outcome of a single file in a folder import */
+export type FileImportStatus =
+  | 'imported'
+  | 'duplicate'
+  | 'noTelemetry'
+  | 'decryptFailed'
+  | 'parseError'
+  | 'storageError';
+
+export interface FileImportOutcome {
+  filePath: string;
+  status: FileImportStatus;
+  flightId: number | null;
+  pointCount: number;
+  message: string;
+}
+
+/** Result from import_folder command */
+export interface FolderImportReport {
+  totalFiles: number;
+  imported: number;
+  duplicates: number;
+  noTelemetry: number;
+  decryptFailed: number;
+  parseErrors: number;
+  storageErrors: number;
+  totalPoints: number;
+  files: FileImportOutcome[];
+}
+
+/** Result of re-processing one flight from its archived raw log */
+export interface ReprocessOutcome {
+  flightId: number;
+  success: boolean;
+  archivePath: string | null;
+  pointCount: number;
+  message: string;
+}
+
+/** Result from reprocess_all_flights command */
+export interface ReprocessReport {
+  totalFlights: number;
+  reprocessed: number;
+  missingArchive: number;
+  failed: number;
+  flights: ReprocessOutcome[];
+}
+
+/** Result from import_parquet command */
+export interface ParquetImportReport {
+  flightsImported: number;
+  flightsSkipped: number;
+  telemetryRows: number;
+}
+
+/** File entry of a backup manifest */
+export interface BackupFile {
+  path: string;
+  size: number;
+  sha256: string;
+}
+
+/** Result from create_backup command */
+export interface BackupManifest {
+  formatVersion: number;
+  appVersion: string;
+  createdAt: string;
+  flightCount: number;
+  files: BackupFile[];
+}
+
+/** Result from restore_backup command */
+export interface RestoreReport {
+  mode: 'replace' | 'merge';
+  flightsRestored: number;
+  flightsSkipped: number;
+  rawLogsRestored: number;
+  previousDatabase: string | null;
+}
+
 /** Flight statistics */
 export interface FlightStats {
   durationSecs: number;